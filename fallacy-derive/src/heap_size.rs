@@ -0,0 +1,79 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{parse_quote, Data, Fields, GenericParam, Generics};
+
+pub fn impl_heap_size(ast: syn::DeriveInput) -> TokenStream {
+    let name = &ast.ident;
+    let generics = add_trait_bounds(ast.generics.clone());
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    let body = match &ast.data {
+        Data::Struct(data_struct) => match &data_struct.fields {
+            Fields::Named(fields) => {
+                let fields = fields.named.iter().map(|field| &field.ident);
+                quote! {
+                    0 #(+ ::fallacy::mem::HeapSize::heap_size(&self.#fields))*
+                }
+            }
+            Fields::Unnamed(fields) => {
+                let fields = (0..fields.unnamed.len()).map(syn::Index::from);
+                quote! {
+                    0 #(+ ::fallacy::mem::HeapSize::heap_size(&self.#fields))*
+                }
+            }
+            Fields::Unit => quote!(0),
+        },
+        Data::Enum(data_enum) => {
+            let all_variants = data_enum.variants.iter().map(|var| {
+                let variant = &var.ident;
+                match &var.fields {
+                    Fields::Named(fields) => {
+                        let fields = fields.named.iter().map(|field| &field.ident);
+                        let fields2 = fields.clone();
+                        quote! {
+                            #name::#variant { #(#fields,)* } => 0 #(+ ::fallacy::mem::HeapSize::heap_size(#fields2))*,
+                        }
+                    }
+                    Fields::Unnamed(fields) => {
+                        let fields = (0..fields.unnamed.len()).map(|i| format_ident!("__field{}", i));
+                        let fields2 = fields.clone();
+                        quote! {
+                            #name::#variant(#(#fields,)*) => 0 #(+ ::fallacy::mem::HeapSize::heap_size(#fields2))*,
+                        }
+                    }
+                    Fields::Unit => quote! {
+                        #name::#variant => 0,
+                    },
+                }
+            });
+
+            quote! {
+                match self {
+                    #(#all_variants)*
+                }
+            }
+        }
+        Data::Union(_) => {
+            return syn::Error::new(Span::call_site(), "cannot derive HeapSize for union").to_compile_error();
+        }
+    };
+
+    quote! {
+        impl #impl_generics ::fallacy::mem::HeapSize for #name #type_generics #where_clause {
+            #[inline]
+            fn heap_size(&self) -> usize {
+                #body
+            }
+        }
+    }
+}
+
+/// Adds a bound `T: HeapSize` to every type parameter `T`.
+fn add_trait_bounds(mut generics: Generics) -> Generics {
+    for param in &mut generics.params {
+        if let GenericParam::Type(ref mut type_param) = *param {
+            type_param.bounds.push(parse_quote!(::fallacy::mem::HeapSize));
+        }
+    }
+    generics
+}
@@ -0,0 +1,9 @@
+//! Derive macro crate for fallacy.
+
+mod heap_size;
+
+#[proc_macro_derive(HeapSize)]
+pub fn derive_heap_size(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast = syn::parse_macro_input!(input as syn::DeriveInput);
+    heap_size::impl_heap_size(ast).into()
+}
@@ -0,0 +1,1648 @@
+//! Fixed-capacity vector and string types that never allocate.
+//!
+//! [`ArrayVec`] and [`ArrayString`] store their contents in an inline array,
+//! so they can be used on code paths that must not touch the allocator.
+//! Operations that need more room than the array provides return a
+//! [`CapacityError`] instead of an [`AllocError`](crate::alloc::AllocError).
+
+use crate::alloc::AllocError;
+use crate::clone::TryClone;
+use crate::iter::TryFromIterator;
+use crate::mem::HeapSize;
+use crate::vec::Primitive;
+use std::borrow::{Borrow, BorrowMut};
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::mem::{self, ManuallyDrop, MaybeUninit};
+use std::ops::{self, Deref, DerefMut, Index, IndexMut, RangeBounds};
+use std::ptr::{self, NonNull};
+use std::slice::{self, SliceIndex};
+use std::str;
+
+/// The error type for operations that need more capacity than a fixed-capacity
+/// container has.
+///
+/// The error holds the element that could not be added, if any.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CapacityError<T = ()> {
+    element: T,
+}
+
+impl<T> CapacityError<T> {
+    /// Creates a new `CapacityError` holding `element`.
+    #[inline]
+    pub const fn new(element: T) -> CapacityError<T> {
+        CapacityError { element }
+    }
+
+    /// Extracts the element that could not be added.
+    #[inline]
+    pub fn element(self) -> T {
+        self.element
+    }
+
+    /// Converts into a `CapacityError` without the element.
+    #[inline]
+    pub fn simplify(self) -> CapacityError {
+        CapacityError { element: () }
+    }
+}
+
+impl<T> fmt::Display for CapacityError<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("insufficient capacity")
+    }
+}
+
+impl<T> fmt::Debug for CapacityError<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CapacityError: insufficient capacity")
+    }
+}
+
+impl<T> Error for CapacityError<T> {}
+
+/// The error type for operations that create new elements in a fixed-capacity
+/// container.
+///
+/// Creating the elements fails either because the container does not have
+/// enough capacity, or because cloning or creating an element fails to
+/// allocate.
+#[derive(Clone, Copy, Debug)]
+pub enum ExtendError {
+    /// The container does not have enough capacity.
+    Capacity(CapacityError),
+    /// Creating an element failed to allocate.
+    Alloc(AllocError),
+}
+
+impl fmt::Display for ExtendError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtendError::Capacity(e) => fmt::Display::fmt(e, f),
+            ExtendError::Alloc(e) => fmt::Display::fmt(e, f),
+        }
+    }
+}
+
+impl Error for ExtendError {}
+
+impl<T> From<CapacityError<T>> for ExtendError {
+    #[inline]
+    fn from(e: CapacityError<T>) -> Self {
+        ExtendError::Capacity(e.simplify())
+    }
+}
+
+impl From<AllocError> for ExtendError {
+    #[inline]
+    fn from(e: AllocError) -> Self {
+        ExtendError::Alloc(e)
+    }
+}
+
+/// A vector with a fixed capacity of `N` elements, stored inline.
+pub struct ArrayVec<T, const N: usize> {
+    buf: [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> ArrayVec<T, N> {
+    /// Constructs a new, empty `ArrayVec`.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        ArrayVec {
+            buf: [const { MaybeUninit::uninit() }; N],
+            len: 0,
+        }
+    }
+
+    /// Returns the number of elements the vector can hold, which is `N`.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of elements in the vector.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector contains no elements.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the vector is full.
+    #[inline]
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Returns the number of elements that can still be added.
+    #[inline]
+    pub const fn remaining_capacity(&self) -> usize {
+        N - self.len
+    }
+
+    /// Returns a raw pointer to the vector's buffer.
+    #[inline]
+    pub const fn as_ptr(&self) -> *const T {
+        self.buf.as_ptr().cast()
+    }
+
+    /// Returns an unsafe mutable pointer to the vector's buffer.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.buf.as_mut_ptr().cast()
+    }
+
+    /// Returns a pointer to the buffer of the vector behind `this`, without
+    /// creating a reference to the vector.
+    ///
+    /// # Safety
+    ///
+    /// `this` must point to a valid `ArrayVec`.
+    #[inline]
+    pub(crate) unsafe fn raw_mut_ptr(this: *mut Self) -> *mut T {
+        ptr::addr_of_mut!((*this).buf).cast()
+    }
+
+    /// Extracts a slice containing the entire vector.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+
+    /// Extracts a mutable slice of the entire vector.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
+
+    /// Forces the length of the vector to `new_len`.
+    ///
+    /// # Safety
+    ///
+    /// - `new_len` must be less than or equal to `N`.
+    /// - The elements at `old_len..new_len` must be initialized.
+    #[inline]
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        debug_assert!(new_len <= N);
+        self.len = new_len;
+    }
+
+    /// Appends an element to the back of the vector.
+    ///
+    /// If the vector is full, an error is returned with the element.
+    #[inline]
+    pub fn try_push(&mut self, value: T) -> Result<(), CapacityError<T>> {
+        if self.len == N {
+            return Err(CapacityError::new(value));
+        }
+        unsafe {
+            self.push_unchecked(value);
+        }
+        Ok(())
+    }
+
+    /// Appends an element to the back of the vector without checking the capacity.
+    ///
+    /// # Safety
+    ///
+    /// The vector must not be full.
+    #[inline]
+    pub unsafe fn push_unchecked(&mut self, value: T) {
+        debug_assert!(self.len < N);
+        ptr::write(self.as_mut_ptr().add(self.len), value);
+        self.len += 1;
+    }
+
+    /// Removes the last element from the vector and returns it, or [`None`] if it
+    /// is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Some(unsafe { ptr::read(self.as_ptr().add(self.len)) })
+    }
+
+    /// Inserts an element at position `index` within the vector, shifting all
+    /// elements after it to the right.
+    ///
+    /// If the vector is full, an error is returned with the element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    #[inline]
+    pub fn try_insert(&mut self, index: usize, element: T) -> Result<(), CapacityError<T>> {
+        let len = self.len;
+        if index > len {
+            panic!("insertion index (is {}) should be <= len (is {})", index, len);
+        }
+        if len == N {
+            return Err(CapacityError::new(element));
+        }
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            ptr::copy(p, p.add(1), len - index);
+            ptr::write(p, element);
+            self.len = len + 1;
+        }
+        Ok(())
+    }
+
+    /// Removes and returns the element at position `index` within the vector,
+    /// shifting all elements after it to the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    pub fn remove(&mut self, index: usize) -> T {
+        let len = self.len;
+        if index >= len {
+            panic!("removal index (is {}) should be < len (is {})", index, len);
+        }
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            let value = ptr::read(p);
+            ptr::copy(p.add(1), p, len - index - 1);
+            self.len = len - 1;
+            value
+        }
+    }
+
+    /// Removes an element from the vector and returns it.
+    ///
+    /// The removed element is replaced by the last element of the vector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        let len = self.len;
+        if index >= len {
+            panic!("swap_remove index (is {}) should be < len (is {})", index, len);
+        }
+        unsafe {
+            let base = self.as_mut_ptr();
+            let value = ptr::read(base.add(index));
+            ptr::copy(base.add(len - 1), base.add(index), 1);
+            self.len = len - 1;
+            value
+        }
+    }
+
+    /// Shortens the vector, keeping the first `len` elements and dropping
+    /// the rest.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        let old_len = self.len;
+        if len >= old_len {
+            return;
+        }
+        unsafe {
+            self.len = len;
+            let tail = ptr::slice_from_raw_parts_mut(self.as_mut_ptr().add(len), old_len - len);
+            ptr::drop_in_place(tail);
+        }
+    }
+
+    /// Clears the vector, removing all values.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Retains only the elements specified by the predicate.
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.retain_mut(|elem| f(elem));
+    }
+
+    /// Retains only the elements specified by the predicate, passing a mutable
+    /// reference to it.
+    #[inline]
+    pub fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let len = self.len;
+        let mut del = 0;
+        {
+            let v = self.as_mut_slice();
+            for i in 0..len {
+                if !f(&mut v[i]) {
+                    del += 1;
+                } else if del > 0 {
+                    v.swap(i - del, i);
+                }
+            }
+        }
+        self.truncate(len - del);
+    }
+
+    /// Removes all but the first of consecutive elements in the vector satisfying a given equality
+    /// relation.
+    ///
+    /// The `same_bucket` function is passed references to two elements from the vector and
+    /// must determine if the elements compare equal. The elements are passed in opposite order
+    /// from their order in the slice, so if `same_bucket(a, b)` returns `true`, `a` is removed.
+    ///
+    /// If the vector is sorted, this removes all duplicates.
+    #[inline]
+    pub fn dedup_by<F>(&mut self, same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        let len = self.as_mut_slice().partition_dedup_by(same_bucket).0.len();
+        self.truncate(len);
+    }
+
+    /// Removes all but the first of consecutive elements in the vector that resolve to the same
+    /// key.
+    ///
+    /// If the vector is sorted, this removes all duplicates.
+    #[inline]
+    pub fn dedup_by_key<F, K>(&mut self, mut key: F)
+    where
+        F: FnMut(&mut T) -> K,
+        K: PartialEq,
+    {
+        self.dedup_by(|a, b| key(a) == key(b))
+    }
+
+    /// Splits the collection into two at the given index.
+    ///
+    /// Returns a new vector containing the elements in the range `[at, len)`.
+    /// After the call, the original vector will be left containing the
+    /// elements `[0, at)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    #[inline]
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let len = self.len;
+        if at > len {
+            panic!("`at` split index (is {}) should be <= len (is {})", at, len);
+        }
+
+        let mut other = ArrayVec::new();
+        unsafe {
+            self.set_len(at);
+            ptr::copy_nonoverlapping(self.as_ptr().add(at), other.as_mut_ptr(), len - at);
+            other.set_len(len - at);
+        }
+        other
+    }
+
+    /// Creates a draining iterator that removes the specified range in the vector
+    /// and yields the removed items.
+    ///
+    /// When the iterator **is** dropped, all elements in the range are removed
+    /// from the vector, even if the iterator was not fully consumed. If the
+    /// iterator **is not** dropped (with `mem::forget` for example), it is
+    /// unspecified how many elements are removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the vector.
+    #[inline]
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T, N>
+    where
+        R: RangeBounds<usize>,
+    {
+        let len = self.len;
+        let ops::Range { start, end } = slice::range(range, ..len);
+        // The drained range and the tail are not part of the vector until
+        // the iterator is dropped, so leaking it leaks them as well.
+        self.len = start;
+        let vec = NonNull::from(self);
+        unsafe {
+            // The element pointer is derived from `vec`, so that using `vec`
+            // when the iterator is dropped does not invalidate it.
+            let ptr = NonNull::new_unchecked(ArrayVec::raw_mut_ptr(vec.as_ptr()).add(start));
+            Drain {
+                ptr,
+                remaining: end - start,
+                tail_start: end,
+                tail_len: len - end,
+                vec,
+                phantom: PhantomData,
+            }
+        }
+    }
+
+    /// Appends elements from an iterator to the vector.
+    ///
+    /// If the vector becomes full, an error is returned with the element that
+    /// did not fit, and the elements appended before it are kept in the vector.
+    #[inline]
+    pub fn try_extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), CapacityError<T>> {
+        for value in iter {
+            self.try_push(value)?;
+        }
+        Ok(())
+    }
+
+    /// Moves all the elements of `other` into `self`, leaving `other` empty.
+    ///
+    /// If the elements do not fit, an error is returned and both vectors are
+    /// left unchanged.
+    #[inline]
+    pub fn try_append(&mut self, other: &mut Self) -> Result<(), CapacityError> {
+        let count = other.len();
+        if self.remaining_capacity() < count {
+            return Err(CapacityError::new(()));
+        }
+        unsafe {
+            ptr::copy_nonoverlapping(other.as_ptr(), self.as_mut_ptr().add(self.len), count);
+            other.set_len(0);
+        }
+        self.len += count;
+        Ok(())
+    }
+
+    /// Replaces the specified range in the vector with the given `replace_with`
+    /// iterator. The removed elements are dropped.
+    ///
+    /// If the lower bound of the iterator's `size_hint` does not fit, an error
+    /// is returned and the vector is left unchanged.
+    ///
+    /// If the iterator yields more elements than fit, an error is returned.
+    /// The range has then been replaced by the elements yielded before the one
+    /// that did not fit, and the tail of the vector is kept after them. The
+    /// element that did not fit is dropped, and the rest of the iterator is not
+    /// consumed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the vector.
+    #[inline]
+    pub fn try_splice<R, I>(&mut self, range: R, replace_with: I) -> Result<(), CapacityError>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        let len = self.len;
+        let ops::Range { start, end } = slice::range(range, ..len);
+        let mut replace_with = replace_with.into_iter();
+
+        if let Some(additional) = replace_with.size_hint().0.checked_sub(end - start) {
+            if self.remaining_capacity() < additional {
+                return Err(CapacityError::new(()));
+            }
+        }
+
+        unsafe {
+            let mut splice = Splice {
+                vec: self,
+                tail_start: end,
+                tail_len: len - end,
+            };
+            splice.vec.set_len(start);
+            let removed = ptr::slice_from_raw_parts_mut(splice.vec.as_mut_ptr().add(start), end - start);
+            ptr::drop_in_place(removed);
+
+            while splice.fill(&mut replace_with) {
+                let value = match replace_with.next() {
+                    Some(value) => value,
+                    None => break,
+                };
+                splice.try_move_tail(replace_with.size_hint().0.saturating_add(1))?;
+                splice.vec.push_unchecked(value);
+            }
+
+            // tail moved back by guard
+        }
+
+        Ok(())
+    }
+
+    /// Returns the remaining spare capacity of the vector as a slice of
+    /// `MaybeUninit<T>`.
+    ///
+    /// The returned slice can be used to fill the vector with data before
+    /// marking the data as initialized using the [`set_len`] method.
+    ///
+    /// [`set_len`]: ArrayVec::set_len
+    #[inline]
+    pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        &mut self.buf[self.len..]
+    }
+
+    /// Resizes the vector in-place so that `len` is equal to `new_len`.
+    ///
+    /// If `new_len` is greater than `len`, the vector is extended by the
+    /// difference, with each additional slot filled with the result of
+    /// calling the closure `f`. If `new_len` is less than `len`, the vector
+    /// is simply truncated.
+    ///
+    /// If `new_len` is greater than `N` or `f` fails, an error is returned and
+    /// the vector is left unchanged.
+    #[inline]
+    pub fn try_resize_with<F>(&mut self, new_len: usize, mut f: F) -> Result<(), ExtendError>
+    where
+        F: FnMut() -> Result<T, AllocError>,
+    {
+        let len = self.len;
+        if new_len <= len {
+            self.truncate(new_len);
+            return Ok(());
+        }
+        if new_len > N {
+            return Err(ExtendError::Capacity(CapacityError::new(())));
+        }
+        for _ in len..new_len {
+            match f() {
+                Ok(value) => unsafe { self.push_unchecked(value) },
+                Err(e) => {
+                    self.truncate(len);
+                    return Err(ExtendError::Alloc(e));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Copies and appends all elements in a slice to the vector.
+    ///
+    /// If the slice does not fit, an error is returned and the vector is
+    /// left unchanged.
+    #[inline]
+    pub fn try_copy_from_slice(&mut self, other: &[T]) -> Result<(), CapacityError>
+    where
+        T: Copy,
+    {
+        if self.remaining_capacity() < other.len() {
+            return Err(CapacityError::new(()));
+        }
+        unsafe {
+            ptr::copy_nonoverlapping(other.as_ptr(), self.as_mut_ptr().add(self.len), other.len());
+        }
+        self.len += other.len();
+        Ok(())
+    }
+
+    /// Returns the inner array if the vector is full, otherwise returns the
+    /// vector itself.
+    #[inline]
+    pub fn into_inner(self) -> Result<[T; N], Self> {
+        if self.len < N {
+            return Err(self);
+        }
+        let this = ManuallyDrop::new(self);
+        Ok(unsafe { ptr::read(this.as_ptr().cast()) })
+    }
+}
+
+impl<T: TryClone, const N: usize> ArrayVec<T, N> {
+    /// Clones and appends all elements in a slice to the vector.
+    ///
+    /// For `Copy` types, the elements are copied with a single `memcpy`.
+    ///
+    /// If the slice does not fit or cloning an element fails, an error is
+    /// returned and the vector is left unchanged.
+    #[inline]
+    pub fn try_extend_from_slice(&mut self, other: &[T]) -> Result<(), ExtendError> {
+        self.spec_extend_from_slice(other)
+    }
+
+    /// Clones elements from `src` range to the end of the vector.
+    ///
+    /// If the elements do not fit or cloning an element fails, an error is
+    /// returned and the vector is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the vector.
+    #[inline]
+    pub fn try_extend_from_within<R>(&mut self, src: R) -> Result<(), ExtendError>
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = slice::range(src, ..self.len);
+        if self.remaining_capacity() < range.len() {
+            return Err(ExtendError::Capacity(CapacityError::new(())));
+        }
+
+        let len = self.len;
+        for i in range {
+            match self[i].try_clone() {
+                Ok(value) => unsafe { self.push_unchecked(value) },
+                Err(e) => {
+                    self.truncate(len);
+                    return Err(ExtendError::Alloc(e));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Resizes the vector in-place so that `len` is equal to `new_len`.
+    ///
+    /// If `new_len` is greater than `len`, the vector is extended by the
+    /// difference, with each additional slot filled with `value`.
+    /// If `new_len` is less than `len`, the vector is simply truncated.
+    ///
+    /// If `new_len` is greater than `N` or cloning `value` fails, an error is
+    /// returned and the vector is left unchanged.
+    #[inline]
+    pub fn try_resize(&mut self, new_len: usize, value: T) -> Result<(), ExtendError> {
+        let len = self.len;
+        if new_len <= len {
+            self.truncate(new_len);
+            return Ok(());
+        }
+        if new_len > N {
+            return Err(ExtendError::Capacity(CapacityError::new(())));
+        }
+        for _ in len + 1..new_len {
+            match value.try_clone() {
+                Ok(value) => unsafe { self.push_unchecked(value) },
+                Err(e) => {
+                    self.truncate(len);
+                    return Err(ExtendError::Alloc(e));
+                }
+            }
+        }
+        unsafe { self.push_unchecked(value) };
+        Ok(())
+    }
+}
+
+impl<T: PartialEq, const N: usize> ArrayVec<T, N> {
+    /// Removes consecutive repeated elements in the vector according to the
+    /// [`PartialEq`] trait implementation.
+    ///
+    /// If the vector is sorted, this removes all duplicates.
+    #[inline]
+    pub fn dedup(&mut self) {
+        self.dedup_by(|a, b| a == b)
+    }
+}
+
+/// Specialization trait used for `ArrayVec::try_extend_from_slice`.
+trait SpecExtendFromSlice<T> {
+    fn spec_extend_from_slice(&mut self, other: &[T]) -> Result<(), ExtendError>;
+}
+
+impl<T: TryClone, const N: usize> SpecExtendFromSlice<T> for ArrayVec<T, N> {
+    #[inline]
+    default fn spec_extend_from_slice(&mut self, other: &[T]) -> Result<(), ExtendError> {
+        if self.remaining_capacity() < other.len() {
+            return Err(ExtendError::Capacity(CapacityError::new(())));
+        }
+
+        let len = self.len;
+        for value in other {
+            match value.try_clone() {
+                Ok(value) => unsafe { self.push_unchecked(value) },
+                Err(e) => {
+                    self.truncate(len);
+                    return Err(ExtendError::Alloc(e));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[inline]
+fn extend_from_slice_copy<T: Primitive, const N: usize>(
+    vec: &mut ArrayVec<T, N>,
+    other: &[T],
+) -> Result<(), ExtendError> {
+    let count = other.len();
+    if vec.remaining_capacity() < count {
+        return Err(ExtendError::Capacity(CapacityError::new(())));
+    }
+
+    // SAFETY: `T` is `Copy`, so the elements can be duplicated bitwise.
+    unsafe {
+        ptr::copy_nonoverlapping(other.as_ptr(), vec.as_mut_ptr().add(vec.len), count);
+    }
+    vec.len += count;
+    Ok(())
+}
+
+macro_rules! impl_spec_extend_from_slice {
+    ($($t: ty),*) => {
+        $(impl<const N: usize> SpecExtendFromSlice<$t> for ArrayVec<$t, N> {
+            #[inline]
+            fn spec_extend_from_slice(&mut self, other: &[$t]) -> Result<(), ExtendError> {
+                extend_from_slice_copy(self, other)
+            }
+        })*
+    }
+}
+
+impl_spec_extend_from_slice!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, bool);
+
+impl<T, const N: usize> Drop for ArrayVec<T, N> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(self.as_mut_slice());
+        }
+    }
+}
+
+impl<T, const N: usize> Default for ArrayVec<T, N> {
+    #[inline]
+    fn default() -> Self {
+        ArrayVec::new()
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for ArrayVec<T, N> {
+    #[inline]
+    fn from(array: [T; N]) -> Self {
+        let array = ManuallyDrop::new(array);
+        let mut vec = ArrayVec::new();
+        unsafe {
+            ptr::copy_nonoverlapping(array.as_ptr(), vec.as_mut_ptr(), N);
+            vec.set_len(N);
+        }
+        vec
+    }
+}
+
+impl<T: Copy, const N: usize> TryFrom<&[T]> for ArrayVec<T, N> {
+    type Error = CapacityError;
+
+    #[inline]
+    fn try_from(slice: &[T]) -> Result<Self, Self::Error> {
+        let mut vec = ArrayVec::new();
+        vec.try_copy_from_slice(slice)?;
+        Ok(vec)
+    }
+}
+
+impl<T, const N: usize> Deref for ArrayVec<T, N> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> DerefMut for ArrayVec<T, N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, I: SliceIndex<[T]>, const N: usize> Index<I> for ArrayVec<T, N> {
+    type Output = I::Output;
+
+    #[inline]
+    fn index(&self, index: I) -> &Self::Output {
+        Index::index(self.as_slice(), index)
+    }
+}
+
+impl<T, I: SliceIndex<[T]>, const N: usize> IndexMut<I> for ArrayVec<T, N> {
+    #[inline]
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        IndexMut::index_mut(self.as_mut_slice(), index)
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for ArrayVec<T, N> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, const N: usize> AsMut<[T]> for ArrayVec<T, N> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T, const N: usize> Borrow<[T]> for ArrayVec<T, N> {
+    #[inline]
+    fn borrow(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, const N: usize> BorrowMut<[T]> for ArrayVec<T, N> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArrayVec<T, N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_slice(), f)
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize, const M: usize> PartialEq<ArrayVec<U, M>> for ArrayVec<T, N> {
+    #[inline]
+    fn eq(&self, other: &ArrayVec<U, M>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U]> for ArrayVec<T, N> {
+    #[inline]
+    fn eq(&self, other: &[U]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for ArrayVec<T, N> {}
+
+impl<T: PartialOrd, const N: usize> PartialOrd for ArrayVec<T, N> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        PartialOrd::partial_cmp(self.as_slice(), other.as_slice())
+    }
+}
+
+impl<T: Ord, const N: usize> Ord for ArrayVec<T, N> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        Ord::cmp(self.as_slice(), other.as_slice())
+    }
+}
+
+impl<T: Hash, const N: usize> Hash for ArrayVec<T, N> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(self.as_slice(), state)
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for ArrayVec<T, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        let mut vec = ArrayVec::new();
+        for value in self {
+            unsafe {
+                vec.push_unchecked(value.clone());
+            }
+        }
+        vec
+    }
+}
+
+impl<T: TryClone, const N: usize> TryClone for ArrayVec<T, N> {
+    #[inline]
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        let mut vec = ArrayVec::new();
+        for value in self {
+            let value = value.try_clone()?;
+            unsafe {
+                vec.push_unchecked(value);
+            }
+        }
+        Ok(vec)
+    }
+}
+
+impl<T: HeapSize, const N: usize> HeapSize for ArrayVec<T, N> {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.as_slice().heap_size()
+    }
+}
+
+impl<T, const N: usize> TryFromIterator<T> for ArrayVec<T, N> {
+    type Error = CapacityError<T>;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, Self::Error> {
+        let mut vec = ArrayVec::new();
+        for value in iter {
+            vec.try_push(value)?;
+        }
+        Ok(vec)
+    }
+}
+
+impl<T, const N: usize> IntoIterator for ArrayVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        let mut vec = self;
+        let end = vec.len;
+        // The elements are moved out by the iterator, and the ones left are
+        // dropped by the iterator.
+        vec.len = 0;
+        IntoIter { vec, start: 0, end }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a ArrayVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut ArrayVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// An iterator that moves out of an [`ArrayVec`].
+///
+/// This `struct` is created by the `into_iter` method on [`ArrayVec`]
+/// (provided by the [`IntoIterator`] trait).
+pub struct IntoIter<T, const N: usize> {
+    vec: ArrayVec<T, N>,
+    start: usize,
+    end: usize,
+}
+
+impl<T, const N: usize> IntoIter<T, N> {
+    /// Returns the remaining items of this iterator as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.vec.as_ptr().add(self.start), self.end - self.start) }
+    }
+
+    /// Returns the remaining items of this iterator as a mutable slice.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.vec.as_mut_ptr().add(self.start), self.end - self.start) }
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for IntoIter<T, N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.start == self.end {
+            None
+        } else {
+            let item = unsafe { ptr::read(self.vec.as_ptr().add(self.start)) };
+            self.start += 1;
+            Some(item)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.start == self.end {
+            None
+        } else {
+            self.end -= 1;
+            Some(unsafe { ptr::read(self.vec.as_ptr().add(self.end)) })
+        }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> FusedIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(self.as_mut_slice());
+        }
+    }
+}
+
+/// A draining iterator for [`ArrayVec`].
+///
+/// This `struct` is created by [`ArrayVec::drain`].
+pub struct Drain<'a, T, const N: usize> {
+    /// The next item to yield.
+    ptr: NonNull<T>,
+    remaining: usize,
+    tail_start: usize,
+    tail_len: usize,
+    vec: NonNull<ArrayVec<T, N>>,
+    phantom: PhantomData<&'a mut ArrayVec<T, N>>,
+}
+
+unsafe impl<T: Send, const N: usize> Send for Drain<'_, T, N> {}
+unsafe impl<T: Sync, const N: usize> Sync for Drain<'_, T, N> {}
+
+impl<T, const N: usize> Drain<'_, T, N> {
+    /// Returns the remaining items of this iterator as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.remaining) }
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for Drain<'_, T, N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Drain").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T, const N: usize> Iterator for Drain<'_, T, N> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        unsafe {
+            let item = ptr::read(self.ptr.as_ptr());
+            self.ptr = self.ptr.add(1);
+            self.remaining -= 1;
+            Some(item)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for Drain<'_, T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        unsafe { Some(ptr::read(self.ptr.as_ptr().add(self.remaining))) }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for Drain<'_, T, N> {}
+
+impl<T, const N: usize> FusedIterator for Drain<'_, T, N> {}
+
+impl<T, const N: usize> Drop for Drain<'_, T, N> {
+    #[inline]
+    fn drop(&mut self) {
+        /// Moves the tail back, even if dropping an item panics.
+        struct DropGuard<'r, 'a, T, const N: usize>(&'r mut Drain<'a, T, N>);
+
+        impl<T, const N: usize> Drop for DropGuard<'_, '_, T, N> {
+            #[inline]
+            fn drop(&mut self) {
+                unsafe {
+                    let vec = self.0.vec.as_mut();
+                    let start = vec.len();
+                    if self.0.tail_start != start {
+                        let ptr = vec.as_mut_ptr();
+                        ptr::copy(ptr.add(self.0.tail_start), ptr.add(start), self.0.tail_len);
+                    }
+                    vec.set_len(start + self.0.tail_len);
+                }
+            }
+        }
+
+        let remaining = ptr::slice_from_raw_parts_mut(self.ptr.as_ptr(), mem::replace(&mut self.remaining, 0));
+        let _guard = DropGuard(self);
+        unsafe {
+            ptr::drop_in_place(remaining);
+        }
+    }
+}
+
+/// Moves the tail of the vector back after the replaced range on drop,
+/// including when the iterator panics or does not fit.
+struct Splice<'a, T, const N: usize> {
+    vec: &'a mut ArrayVec<T, N>,
+    tail_start: usize,
+    tail_len: usize,
+}
+
+impl<T, const N: usize> Splice<'_, T, N> {
+    /// Fills the gap with items from `replace_with`.
+    /// Returns `true` if the gap was filled, `false` if the iterator ran out.
+    #[inline]
+    unsafe fn fill<I: Iterator<Item = T>>(&mut self, replace_with: &mut I) -> bool {
+        while self.vec.len < self.tail_start {
+            match replace_with.next() {
+                Some(value) => self.vec.push_unchecked(value),
+                None => return false,
+            }
+        }
+        true
+    }
+
+    /// Makes room for inserting up to `additional` more elements before the
+    /// tail, and at least one.
+    #[inline]
+    unsafe fn try_move_tail(&mut self, additional: usize) -> Result<(), CapacityError> {
+        let spare = N - self.tail_start - self.tail_len;
+        if spare == 0 {
+            return Err(CapacityError::new(()));
+        }
+
+        let new_tail_start = self.tail_start + additional.min(spare);
+        let ptr = self.vec.as_mut_ptr();
+        ptr::copy(ptr.add(self.tail_start), ptr.add(new_tail_start), self.tail_len);
+        self.tail_start = new_tail_start;
+        Ok(())
+    }
+}
+
+impl<T, const N: usize> Drop for Splice<'_, T, N> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            let start = self.vec.len;
+            if self.tail_start != start {
+                let ptr = self.vec.as_mut_ptr();
+                ptr::copy(ptr.add(self.tail_start), ptr.add(start), self.tail_len);
+            }
+            self.vec.set_len(start + self.tail_len);
+        }
+    }
+}
+
+/// A UTF-8–encoded string with a fixed capacity of `N` bytes, stored inline.
+///
+/// `ArrayString` implements [`fmt::Write`], so formatted output can be
+/// written into a stack buffer with [`write!`]:
+///
+/// ```
+/// use fallacy::arrayvec::ArrayString;
+/// use std::fmt::Write;
+///
+/// let mut s = ArrayString::<16>::new();
+/// write!(s, "{}-{}", 1, 2).unwrap();
+/// assert_eq!(s, "1-2");
+/// ```
+#[derive(Clone, Copy)]
+pub struct ArrayString<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> ArrayString<N> {
+    /// Creates a new empty `ArrayString`.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        ArrayString { buf: [0; N], len: 0 }
+    }
+
+    /// Creates a new `ArrayString` holding a copy of `s`.
+    ///
+    /// If `s` does not fit, an error is returned with the string slice.
+    #[inline]
+    pub fn try_from_str(s: &str) -> Result<Self, CapacityError<&str>> {
+        let mut string = ArrayString::new();
+        string.try_push_str(s)?;
+        Ok(string)
+    }
+
+    /// Returns this string's capacity in bytes, which is `N`.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the length of this string, in bytes.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if this string has a length of zero.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if this string is full.
+    #[inline]
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Returns the number of bytes that can still be added.
+    #[inline]
+    pub const fn remaining_capacity(&self) -> usize {
+        N - self.len
+    }
+
+    /// Extracts a string slice containing the entire string.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(self.as_bytes()) }
+    }
+
+    /// Converts this string into a mutable string slice.
+    #[inline]
+    pub fn as_mut_str(&mut self) -> &mut str {
+        unsafe { str::from_utf8_unchecked_mut(&mut self.buf[..self.len]) }
+    }
+
+    /// Returns a byte slice of this string's contents.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    /// Appends a given string slice onto the end of this string.
+    ///
+    /// If the slice does not fit, an error is returned with the slice and the
+    /// string is left unchanged.
+    #[inline]
+    pub fn try_push_str<'a>(&mut self, string: &'a str) -> Result<(), CapacityError<&'a str>> {
+        if self.remaining_capacity() < string.len() {
+            return Err(CapacityError::new(string));
+        }
+        self.buf[self.len..self.len + string.len()].copy_from_slice(string.as_bytes());
+        self.len += string.len();
+        Ok(())
+    }
+
+    /// Appends the given [`char`] to the end of this string.
+    ///
+    /// If the `char` does not fit, an error is returned with the `char`.
+    #[inline]
+    pub fn try_push(&mut self, ch: char) -> Result<(), CapacityError<char>> {
+        let ch_len = ch.len_utf8();
+        if self.remaining_capacity() < ch_len {
+            return Err(CapacityError::new(ch));
+        }
+        ch.encode_utf8(&mut self.buf[self.len..]);
+        self.len += ch_len;
+        Ok(())
+    }
+
+    /// Inserts a character into this string at a byte position.
+    ///
+    /// If the `char` does not fit, an error is returned with the `char`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the string's length, or if it does not
+    /// lie on a [`char`] boundary.
+    #[inline]
+    pub fn try_insert(&mut self, idx: usize, ch: char) -> Result<(), CapacityError<char>> {
+        assert!(self.as_str().is_char_boundary(idx));
+        let mut bits = [0; 4];
+        let bits = ch.encode_utf8(&mut bits).as_bytes();
+        if self.remaining_capacity() < bits.len() {
+            return Err(CapacityError::new(ch));
+        }
+        self.insert_bytes(idx, bits);
+        Ok(())
+    }
+
+    /// Inserts a string slice into this string at a byte position.
+    ///
+    /// If the slice does not fit, an error is returned with the slice and the
+    /// string is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the string's length, or if it does not
+    /// lie on a [`char`] boundary.
+    #[inline]
+    pub fn try_insert_str<'a>(&mut self, idx: usize, string: &'a str) -> Result<(), CapacityError<&'a str>> {
+        assert!(self.as_str().is_char_boundary(idx));
+        if self.remaining_capacity() < string.len() {
+            return Err(CapacityError::new(string));
+        }
+        self.insert_bytes(idx, string.as_bytes());
+        Ok(())
+    }
+
+    /// Inserts `bytes` at `idx`, which must be a char boundary, and there
+    /// must be room for them.
+    #[inline]
+    fn insert_bytes(&mut self, idx: usize, bytes: &[u8]) {
+        let len = self.len;
+        let amt = bytes.len();
+        self.buf.copy_within(idx..len, idx + amt);
+        self.buf[idx..idx + amt].copy_from_slice(bytes);
+        self.len = len + amt;
+    }
+
+    /// Removes a [`char`] from this string at a byte position and returns it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than or equal to the string's length,
+    /// or if it does not lie on a [`char`] boundary.
+    #[inline]
+    pub fn remove(&mut self, idx: usize) -> char {
+        let ch = match self.as_str()[idx..].chars().next() {
+            Some(ch) => ch,
+            None => panic!("cannot remove a char from the end of a string"),
+        };
+        let next = idx + ch.len_utf8();
+        self.buf.copy_within(next..self.len, idx);
+        self.len -= next - idx;
+        ch
+    }
+
+    /// Retains only the characters specified by the predicate.
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(char) -> bool,
+    {
+        /// Keeps the characters processed so far if `f` panics.
+        struct SetLenOnDrop<'a, const N: usize> {
+            s: &'a mut ArrayString<N>,
+            idx: usize,
+            del: usize,
+        }
+
+        impl<const N: usize> Drop for SetLenOnDrop<'_, N> {
+            #[inline]
+            fn drop(&mut self) {
+                self.s.len = self.idx - self.del;
+            }
+        }
+
+        let len = self.len;
+        let mut guard = SetLenOnDrop {
+            s: self,
+            idx: 0,
+            del: 0,
+        };
+        while guard.idx < len {
+            let idx = guard.idx;
+            let ch = unsafe { str::from_utf8_unchecked(&guard.s.buf[idx..len]) }
+                .chars()
+                .next()
+                .unwrap();
+            let ch_len = ch.len_utf8();
+            if !f(ch) {
+                guard.del += ch_len;
+            } else if guard.del > 0 {
+                guard.s.buf.copy_within(idx..idx + ch_len, idx - guard.del);
+            }
+            guard.idx += ch_len;
+        }
+    }
+
+    /// Splits the string into two at the given byte index.
+    ///
+    /// Returns a new `ArrayString` containing the bytes `[at, len)`. After
+    /// the call, the original string will be left containing the bytes
+    /// `[0, at)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is not on a [`char`] boundary, or if it is beyond the
+    /// last code point of the string.
+    #[inline]
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(self.as_str().is_char_boundary(at));
+        let mut other = ArrayString::new();
+        other.buf[..self.len - at].copy_from_slice(&self.buf[at..self.len]);
+        other.len = self.len - at;
+        self.len = at;
+        other
+    }
+
+    /// Removes the last character from the string and returns it, or [`None`]
+    /// if it is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next_back()?;
+        self.len -= ch.len_utf8();
+        Some(ch)
+    }
+
+    /// Shortens this string to the specified length.
+    ///
+    /// If `new_len` is greater than the string's current length, this has no
+    /// effect.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` does not lie on a [`char`] boundary.
+    #[inline]
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len < self.len {
+            assert!(self.as_str().is_char_boundary(new_len));
+            self.len = new_len;
+        }
+    }
+
+    /// Truncates this string, removing all contents.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl<const N: usize> Default for ArrayString<N> {
+    #[inline]
+    fn default() -> Self {
+        ArrayString::new()
+    }
+}
+
+impl<'a, const N: usize> TryFrom<&'a str> for ArrayString<N> {
+    type Error = CapacityError<&'a str>;
+
+    #[inline]
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        ArrayString::try_from_str(s)
+    }
+}
+
+impl<const N: usize> Deref for ArrayString<N> {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> DerefMut for ArrayString<N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl<const N: usize> fmt::Display for ArrayString<N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> fmt::Debug for ArrayString<N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> fmt::Write for ArrayString<N> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.try_push_str(s).map_err(|_| fmt::Error)
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.try_push(c).map_err(|_| fmt::Error)
+    }
+}
+
+impl<const N: usize> PartialEq for ArrayString<N> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> Eq for ArrayString<N> {}
+
+impl<const N: usize> PartialEq<str> for ArrayString<N> {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for ArrayString<N> {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const N: usize> PartialEq<ArrayString<N>> for str {
+    #[inline]
+    fn eq(&self, other: &ArrayString<N>) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl<const N: usize> PartialEq<ArrayString<N>> for &str {
+    #[inline]
+    fn eq(&self, other: &ArrayString<N>) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl<const N: usize> PartialOrd for ArrayString<N> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize> Ord for ArrayString<N> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl<const N: usize> Hash for ArrayString<N> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl<const N: usize> Borrow<str> for ArrayString<N> {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl<const N: usize> AsRef<str> for ArrayString<N> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl<const N: usize> AsMut<str> for ArrayString<N> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut str {
+        self
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for ArrayString<N> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<const N: usize> TryClone for ArrayString<N> {
+    #[inline]
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        Ok(*self)
+    }
+}
+
+impl<const N: usize> HeapSize for ArrayString<N> {
+    #[inline(always)]
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
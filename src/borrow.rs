@@ -2,6 +2,7 @@
 
 use crate::alloc::AllocError;
 use crate::clone::TryClone;
+use crate::mem::HeapSize;
 use crate::string::String;
 use crate::vec::Vec;
 use std::borrow::Borrow;
@@ -69,15 +70,15 @@ impl TryToOwned for [u8] {
 
     #[inline]
     fn try_to_owned(&self) -> Result<Self::Owned, AllocError> {
-        let mut s = Vec::new();
-        s.try_copy_from_slice(self)?;
+        let mut s = Vec::try_with_capacity(self.len())?;
+        s.try_extend_from_slice(self)?;
         Ok(s)
     }
 
     #[inline]
     fn try_clone_into(&self, target: &mut Vec<u8>) -> Result<(), AllocError> {
         target.clear();
-        target.try_copy_from_slice(self)
+        target.try_extend_from_slice(self)
     }
 }
 
@@ -124,6 +125,19 @@ impl<B: ?Sized + TryToOwned> TryClone for Cow<'_, B> {
     }
 }
 
+impl<B: ?Sized + TryToOwned> HeapSize for Cow<'_, B>
+where
+    B::Owned: HeapSize,
+{
+    #[inline]
+    fn heap_size(&self) -> usize {
+        match self {
+            Cow::Borrowed(_) => 0,
+            Cow::Owned(o) => o.heap_size(),
+        }
+    }
+}
+
 impl<B: ?Sized + TryToOwned> Cow<'_, B> {
     /// Returns true if the data is borrowed, i.e. if `to_mut` would require additional work.
     #[inline]
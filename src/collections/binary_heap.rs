@@ -0,0 +1,827 @@
+//! A priority queue implemented with a binary heap.
+//!
+//! [`BinaryHeap`] is a max-heap backed by a [`Vec`]. [`IndexedBinaryHeap`]
+//! additionally tracks the position of every entry by a `usize` key, so the
+//! priority of an entry can be changed in place (the decrease-key operation
+//! of Dijkstra-style algorithms).
+
+use crate::alloc::AllocError;
+use crate::clone::TryClone;
+use crate::iter::{self, TryFromIterator};
+use crate::mem::HeapSize;
+use crate::vec::{Drain, IntoIter, Vec};
+use std::alloc::{Allocator, Global};
+use std::fmt;
+use std::iter::FusedIterator;
+use std::mem::{self, ManuallyDrop};
+use std::num::NonZeroUsize;
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+use std::slice;
+
+/// A priority queue implemented with a binary heap.
+///
+/// This is a max-heap: [`pop`](BinaryHeap::pop) returns the greatest element.
+/// Use [`std::cmp::Reverse`] to get a min-heap.
+pub struct BinaryHeap<T, A: Allocator = Global> {
+    data: Vec<T, A>,
+}
+
+impl<T: Ord> BinaryHeap<T> {
+    /// Creates an empty `BinaryHeap` as a max-heap.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        BinaryHeap { data: Vec::new() }
+    }
+
+    /// Creates an empty `BinaryHeap` with space for at least `capacity` elements.
+    #[inline]
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, AllocError> {
+        Ok(BinaryHeap {
+            data: Vec::try_with_capacity(capacity)?,
+        })
+    }
+}
+
+impl<T: Ord, A: Allocator> BinaryHeap<T, A> {
+    /// Creates an empty `BinaryHeap` with the provided allocator.
+    #[inline]
+    pub fn new_in(alloc: A) -> Self {
+        BinaryHeap {
+            data: Vec::new_in(alloc),
+        }
+    }
+
+    /// Creates an empty `BinaryHeap` with space for at least `capacity` elements
+    /// with the provided allocator.
+    #[inline]
+    pub fn try_with_capacity_in(capacity: usize, alloc: A) -> Result<Self, AllocError> {
+        Ok(BinaryHeap {
+            data: Vec::try_with_capacity_in(capacity, alloc)?,
+        })
+    }
+
+    /// Returns a mutable reference to the greatest item in the binary heap, or
+    /// `None` if it is empty.
+    ///
+    /// If the item is modified, the heap is restored when the [`PeekMut`] is
+    /// dropped.
+    #[inline]
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, A>> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(PeekMut {
+                heap: self,
+                original_len: None,
+            })
+        }
+    }
+
+    /// Removes the greatest item from the binary heap and returns it, or `None`
+    /// if it is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.data.pop().map(|mut item| {
+            if !self.is_empty() {
+                mem::swap(&mut item, &mut self.data[0]);
+                // SAFETY: the heap is not empty.
+                unsafe { self.sift_down(0) };
+            }
+            item
+        })
+    }
+
+    /// Pushes an item onto the binary heap.
+    #[inline]
+    pub fn try_push(&mut self, item: T) -> Result<(), AllocError> {
+        let old_len = self.len();
+        self.data.try_push(item)?;
+        // SAFETY: `old_len` is the index of the pushed item.
+        unsafe { self.sift_up(0, old_len) };
+        Ok(())
+    }
+
+    /// Pushes the items of an iterator onto the binary heap.
+    ///
+    /// The heap reserves the lower bound of the iterator's `size_hint` up
+    /// front. If an allocation fails, the items pushed so far are kept.
+    #[inline]
+    pub fn try_extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), AllocError> {
+        let iter = iter.into_iter();
+        self.try_reserve(iter.size_hint().0)?;
+        for item in iter {
+            self.try_push(item)?;
+        }
+        Ok(())
+    }
+
+    /// Moves all the elements of `other` into `self`, leaving `other` empty.
+    ///
+    /// If the allocation fails, both heaps are left unchanged.
+    #[inline]
+    pub fn try_append(&mut self, other: &mut Self) -> Result<(), AllocError> {
+        let old_len = self.len();
+        self.data.try_append(&mut other.data)?;
+        for pos in old_len..self.len() {
+            // SAFETY: `pos` is the index of an appended item.
+            unsafe { self.sift_up(0, pos) };
+        }
+        Ok(())
+    }
+
+    /// Consumes the `BinaryHeap` and returns a vector in sorted (ascending)
+    /// order.
+    ///
+    /// The elements are sorted in place with a heap sort.
+    #[inline]
+    pub fn into_sorted_vec(mut self) -> Vec<T, A> {
+        let mut end = self.len();
+        while end > 1 {
+            end -= 1;
+            self.data.swap(0, end);
+            // SAFETY: `end` is in `1..len`.
+            unsafe { self.sift_down_range(0, end) };
+        }
+        self.into_vec()
+    }
+
+    /// Retains only the elements specified by the predicate.
+    #[inline]
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.data.retain(f);
+        self.rebuild();
+    }
+
+    /// Moves the element at `pos` up the heap, and returns its new position.
+    ///
+    /// # Safety
+    ///
+    /// `pos` must be less than `self.len()`.
+    #[inline]
+    unsafe fn sift_up(&mut self, start: usize, pos: usize) -> usize {
+        // Take out the value at `pos` and create a hole. If a comparison
+        // panics, the hole is filled back when it is dropped.
+        let mut hole = Hole::new(&mut self.data, pos);
+
+        while hole.pos() > start {
+            let parent = (hole.pos() - 1) / 2;
+            if hole.element() <= hole.get(parent) {
+                break;
+            }
+            hole.move_to(parent);
+        }
+        hole.pos()
+    }
+
+    /// Moves the element at `pos` down the heap, considering only the
+    /// elements before `end`.
+    ///
+    /// # Safety
+    ///
+    /// `pos` must be less than `end`, which must be at most `self.len()`.
+    #[inline]
+    unsafe fn sift_down_range(&mut self, pos: usize, end: usize) {
+        let mut hole = Hole::new(&mut self.data, pos);
+        let mut child = 2 * hole.pos() + 1;
+
+        // Loop while both children exist.
+        while child + 1 < end {
+            // Pick the greater of the two children.
+            child += (hole.get(child) <= hole.get(child + 1)) as usize;
+            if hole.element() >= hole.get(child) {
+                return;
+            }
+            hole.move_to(child);
+            child = 2 * hole.pos() + 1;
+        }
+
+        if child + 1 == end && hole.element() < hole.get(child) {
+            hole.move_to(child);
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `pos` must be less than `self.len()`.
+    #[inline]
+    unsafe fn sift_down(&mut self, pos: usize) {
+        let len = self.len();
+        self.sift_down_range(pos, len);
+    }
+
+    #[inline]
+    fn rebuild(&mut self) {
+        let mut n = self.len() / 2;
+        while n > 0 {
+            n -= 1;
+            // SAFETY: `n` is less than `self.len()`.
+            unsafe { self.sift_down(n) };
+        }
+    }
+}
+
+impl<T, A: Allocator> BinaryHeap<T, A> {
+    /// Returns the greatest item in the binary heap, or `None` if it is empty.
+    #[inline]
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    /// Returns the number of elements the binary heap can hold without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Returns the length of the binary heap.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Checks if the binary heap is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        self.data.allocator()
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements.
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        self.data.try_reserve(additional)
+    }
+
+    /// Tries to reserve the minimum capacity for exactly `additional` more elements.
+    #[inline]
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), AllocError> {
+        self.data.try_reserve_exact(additional)
+    }
+
+    /// Shrinks the capacity of the binary heap as much as possible.
+    #[inline]
+    pub fn try_shrink_to_fit(&mut self) -> Result<(), AllocError> {
+        self.data.try_shrink_to_fit()
+    }
+
+    /// Returns an iterator visiting all values in the underlying vector, in
+    /// arbitrary order.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.data.iter()
+    }
+
+    /// Returns a slice of all values in the underlying vector, in arbitrary order.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self.data.as_slice()
+    }
+
+    /// Consumes the `BinaryHeap` and returns the underlying vector in arbitrary order.
+    #[inline]
+    pub fn into_vec(self) -> Vec<T, A> {
+        self.data
+    }
+
+    /// Clears the binary heap, returning an iterator over the removed elements
+    /// in arbitrary order.
+    #[inline]
+    pub fn drain(&mut self) -> Drain<'_, T, A> {
+        self.data.drain(..)
+    }
+
+    /// Drops all items from the binary heap.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+}
+
+/// Converts a [`Vec`] into a [`BinaryHeap`].
+///
+/// This conversion happens in-place, and has *O*(*n*) time complexity.
+impl<T: Ord, A: Allocator> From<Vec<T, A>> for BinaryHeap<T, A> {
+    #[inline]
+    fn from(vec: Vec<T, A>) -> Self {
+        let mut heap = BinaryHeap { data: vec };
+        heap.rebuild();
+        heap
+    }
+}
+
+impl<T, A: Allocator> From<BinaryHeap<T, A>> for Vec<T, A> {
+    #[inline]
+    fn from(heap: BinaryHeap<T, A>) -> Self {
+        heap.data
+    }
+}
+
+impl<T: Ord> Default for BinaryHeap<T> {
+    #[inline]
+    fn default() -> Self {
+        BinaryHeap::new()
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for BinaryHeap<T, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: TryClone, A: Allocator + Clone> TryClone for BinaryHeap<T, A> {
+    #[inline]
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        Ok(BinaryHeap {
+            data: self.data.try_clone()?,
+        })
+    }
+
+    #[inline]
+    fn try_clone_from(&mut self, source: &Self) -> Result<(), AllocError> {
+        self.data.try_clone_from(&source.data)
+    }
+}
+
+impl<T: HeapSize, A: Allocator> HeapSize for BinaryHeap<T, A> {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.data.heap_size()
+    }
+}
+
+impl<T: Ord> TryFromIterator<T> for BinaryHeap<T> {
+    type Error = AllocError;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, Self::Error> {
+        Ok(BinaryHeap::from(Vec::try_from_iter(iter)?))
+    }
+}
+
+impl<T: Ord, E> TryFromIterator<Result<T, E>> for BinaryHeap<T>
+where
+    E: From<AllocError>,
+{
+    type Error = E;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = Result<T, E>>>(iter: I) -> Result<Self, Self::Error> {
+        iter::try_process(iter.into_iter(), |shunt| BinaryHeap::try_from_iter(shunt))
+    }
+}
+
+impl<T, A: Allocator> IntoIterator for BinaryHeap<T, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    /// Creates a consuming iterator that moves each value out of the binary
+    /// heap in arbitrary order.
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a BinaryHeap<T, A> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A mutable reference to the greatest item of a [`BinaryHeap`].
+///
+/// This `struct` is created by the [`peek_mut`] method on [`BinaryHeap`].
+/// When it is dropped, the heap is restored if the item was mutably borrowed.
+///
+/// [`peek_mut`]: BinaryHeap::peek_mut
+pub struct PeekMut<'a, T: 'a + Ord, A: Allocator = Global> {
+    heap: &'a mut BinaryHeap<T, A>,
+    // If a set_len + sift_down are required, this is Some. If a &mut T has not
+    // yet been exposed to peek_mut()'s caller, it's None.
+    original_len: Option<NonZeroUsize>,
+}
+
+impl<'a, T: Ord, A: Allocator> PeekMut<'a, T, A> {
+    /// Removes the peeked value from the heap and returns it.
+    #[inline]
+    pub fn pop(mut this: PeekMut<'a, T, A>) -> T {
+        if let Some(original_len) = this.original_len.take() {
+            // SAFETY: this is how many elements were in the heap before the
+            // length was shortened by `deref_mut`.
+            unsafe { this.heap.data.set_len(original_len.get()) };
+            // The item is removed next, so its value does not need to be
+            // sifted down first.
+        }
+        // SAFETY: `PeekMut` is only created for non-empty heaps.
+        let value = unsafe { this.heap.pop().unwrap_unchecked() };
+        mem::forget(this);
+        value
+    }
+}
+
+impl<T: Ord + fmt::Debug, A: Allocator> fmt::Debug for PeekMut<'_, T, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PeekMut").field(&self.heap.data[0]).finish()
+    }
+}
+
+impl<T: Ord, A: Allocator> Drop for PeekMut<'_, T, A> {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(original_len) = self.original_len {
+            // SAFETY: this is how many elements were in the heap before the
+            // length was shortened by `deref_mut`, and `PeekMut` is only
+            // created for non-empty heaps.
+            unsafe {
+                self.heap.data.set_len(original_len.get());
+                self.heap.sift_down(0);
+            }
+        }
+    }
+}
+
+impl<T: Ord, A: Allocator> Deref for PeekMut<'_, T, A> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.heap.data[0]
+    }
+}
+
+impl<T: Ord, A: Allocator> DerefMut for PeekMut<'_, T, A> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        let len = self.heap.len();
+        if len > 1 {
+            // Shorten the heap to the peeked item while it may be modified,
+            // so that leaking the `PeekMut` leaks the other items instead of
+            // leaving the heap out of order.
+            //
+            // SAFETY: `len > 1`, and the items past the new length are still
+            // initialized and restored by `drop`.
+            unsafe { self.heap.data.set_len(1) };
+            self.original_len = NonZeroUsize::new(len);
+        }
+        &mut self.heap.data[0]
+    }
+}
+
+/// Hole represents a hole in a slice i.e., an index without valid value
+/// (because it was moved from or duplicated).
+///
+/// In drop, `Hole` will restore the slice by filling the hole position with
+/// the value that was originally removed.
+struct Hole<'a, T: 'a> {
+    data: &'a mut [T],
+    elt: ManuallyDrop<T>,
+    pos: usize,
+}
+
+impl<'a, T> Hole<'a, T> {
+    /// Creates a new `Hole` at index `pos`.
+    ///
+    /// # Safety
+    ///
+    /// `pos` must be within the data slice.
+    #[inline]
+    unsafe fn new(data: &'a mut [T], pos: usize) -> Self {
+        debug_assert!(pos < data.len());
+        let elt = ptr::read(data.get_unchecked(pos));
+        Hole {
+            data,
+            elt: ManuallyDrop::new(elt),
+            pos,
+        }
+    }
+
+    #[inline]
+    fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns a reference to the element removed.
+    #[inline]
+    fn element(&self) -> &T {
+        &self.elt
+    }
+
+    /// Returns a reference to the element at `index`.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be within the data slice and not equal to pos.
+    #[inline]
+    unsafe fn get(&self, index: usize) -> &T {
+        debug_assert!(index != self.pos);
+        debug_assert!(index < self.data.len());
+        self.data.get_unchecked(index)
+    }
+
+    /// Moves the hole to a new location.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be within the data slice and not equal to pos.
+    #[inline]
+    unsafe fn move_to(&mut self, index: usize) {
+        debug_assert!(index != self.pos);
+        debug_assert!(index < self.data.len());
+        let ptr = self.data.as_mut_ptr();
+        ptr::copy_nonoverlapping(ptr.add(index), ptr.add(self.pos), 1);
+        self.pos = index;
+    }
+}
+
+impl<T> Drop for Hole<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        // fill the hole again
+        unsafe {
+            let pos = self.pos;
+            ptr::copy_nonoverlapping(&*self.elt, self.data.get_unchecked_mut(pos), 1);
+        }
+    }
+}
+
+/// Marks a key which is not in an [`IndexedBinaryHeap`].
+const ABSENT: usize = usize::MAX;
+
+/// A max-heap of priorities `P` addressed by `usize` keys.
+///
+/// Every key is in the heap at most once, and its priority can be changed
+/// in place with [`change_priority`](IndexedBinaryHeap::change_priority).
+/// The keys are used as indices into a position table, so they should be
+/// small and dense, such as the node indices of a graph.
+///
+/// Use [`std::cmp::Reverse`] priorities to get a min-heap, e.g. for
+/// Dijkstra's algorithm.
+pub struct IndexedBinaryHeap<P> {
+    heap: Vec<(usize, P)>,
+    positions: Vec<usize>,
+}
+
+impl<P: Ord> IndexedBinaryHeap<P> {
+    /// Creates an empty `IndexedBinaryHeap`.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        IndexedBinaryHeap {
+            heap: Vec::new(),
+            positions: Vec::new(),
+        }
+    }
+
+    /// Creates an empty `IndexedBinaryHeap` with space for at least `capacity`
+    /// entries with keys less than `capacity`.
+    #[inline]
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, AllocError> {
+        let mut positions = Vec::try_with_capacity(capacity)?;
+        positions.try_resize(capacity, ABSENT)?;
+        Ok(IndexedBinaryHeap {
+            heap: Vec::try_with_capacity(capacity)?,
+            positions,
+        })
+    }
+
+    /// Returns the number of entries in the heap.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Returns `true` if the heap contains no entries.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Returns `true` if the heap contains an entry for `key`.
+    #[inline]
+    pub fn contains_key(&self, key: usize) -> bool {
+        self.position(key).is_some()
+    }
+
+    /// Returns the priority of `key`, or `None` if it is not in the heap.
+    #[inline]
+    pub fn get(&self, key: usize) -> Option<&P> {
+        self.position(key).map(|pos| &self.heap[pos].1)
+    }
+
+    /// Returns the entry with the greatest priority, or `None` if the heap is empty.
+    #[inline]
+    pub fn peek(&self) -> Option<(usize, &P)> {
+        self.heap.first().map(|(key, priority)| (*key, priority))
+    }
+
+    /// Inserts `key` with the given priority.
+    ///
+    /// If `key` is already in the heap, its priority is changed and the old
+    /// priority is returned. Only inserting a new key may allocate.
+    #[inline]
+    pub fn try_push(&mut self, key: usize, priority: P) -> Result<Option<P>, AllocError> {
+        if let Some(pos) = self.position(key) {
+            let old = mem::replace(&mut self.heap[pos].1, priority);
+            self.update(pos);
+            return Ok(Some(old));
+        }
+        assert!(key != ABSENT, "key out of range");
+
+        if key >= self.positions.len() {
+            self.positions.try_resize(key + 1, ABSENT)?;
+        }
+        let pos = self.heap.len();
+        self.heap.try_push((key, priority))?;
+        self.positions[key] = pos;
+        self.sift_up(pos);
+        Ok(None)
+    }
+
+    /// Changes the priority of `key`, and returns the old priority.
+    ///
+    /// Returns `None` and drops `priority` if `key` is not in the heap. This
+    /// never allocates.
+    #[inline]
+    pub fn change_priority(&mut self, key: usize, priority: P) -> Option<P> {
+        let pos = self.position(key)?;
+        let old = mem::replace(&mut self.heap[pos].1, priority);
+        self.update(pos);
+        Some(old)
+    }
+
+    /// Removes the entry with the greatest priority and returns it, or `None`
+    /// if the heap is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<(usize, P)> {
+        let last = self.heap.len().checked_sub(1)?;
+        self.swap(0, last);
+        let (key, priority) = self.heap.pop()?;
+        self.positions[key] = ABSENT;
+        if !self.heap.is_empty() {
+            self.sift_down(0);
+        }
+        Some((key, priority))
+    }
+
+    /// Removes `key` from the heap and returns its priority, or `None` if it
+    /// is not in the heap.
+    #[inline]
+    pub fn remove(&mut self, key: usize) -> Option<P> {
+        let pos = self.position(key)?;
+        let last = self.heap.len() - 1;
+        self.swap(pos, last);
+        let (_, priority) = self.heap.pop()?;
+        self.positions[key] = ABSENT;
+        if pos < self.heap.len() {
+            self.update(pos);
+        }
+        Some(priority)
+    }
+
+    /// Returns an iterator visiting all entries in arbitrary order.
+    #[inline]
+    pub fn iter(&self) -> IndexedIter<'_, P> {
+        IndexedIter(self.heap.iter())
+    }
+
+    /// Removes all entries from the heap.
+    #[inline]
+    pub fn clear(&mut self) {
+        for (key, _) in self.heap.iter() {
+            self.positions[*key] = ABSENT;
+        }
+        self.heap.clear();
+    }
+
+    #[inline]
+    fn position(&self, key: usize) -> Option<usize> {
+        match self.positions.get(key) {
+            Some(&pos) if pos != ABSENT => Some(pos),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn swap(&mut self, i: usize, j: usize) {
+        self.heap.swap(i, j);
+        self.positions[self.heap[i].0] = i;
+        self.positions[self.heap[j].0] = j;
+    }
+
+    /// Restores the heap after the priority at `pos` was changed.
+    #[inline]
+    fn update(&mut self, pos: usize) {
+        let pos = self.sift_up(pos);
+        self.sift_down(pos);
+    }
+
+    #[inline]
+    fn sift_up(&mut self, mut pos: usize) -> usize {
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if self.heap[pos].1 <= self.heap[parent].1 {
+                break;
+            }
+            self.swap(pos, parent);
+            pos = parent;
+        }
+        pos
+    }
+
+    #[inline]
+    fn sift_down(&mut self, mut pos: usize) {
+        let len = self.heap.len();
+        loop {
+            let mut child = 2 * pos + 1;
+            if child >= len {
+                break;
+            }
+            if child + 1 < len && self.heap[child].1 < self.heap[child + 1].1 {
+                child += 1;
+            }
+            if self.heap[pos].1 >= self.heap[child].1 {
+                break;
+            }
+            self.swap(pos, child);
+            pos = child;
+        }
+    }
+}
+
+impl<P: Ord> Default for IndexedBinaryHeap<P> {
+    #[inline]
+    fn default() -> Self {
+        IndexedBinaryHeap::new()
+    }
+}
+
+impl<P: fmt::Debug> fmt::Debug for IndexedBinaryHeap<P> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.heap.iter().map(|(k, v)| (k, v))).finish()
+    }
+}
+
+impl<P: TryClone> TryClone for IndexedBinaryHeap<P> {
+    #[inline]
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        let mut heap = Vec::try_with_capacity(self.heap.len())?;
+        for (key, priority) in self.heap.iter() {
+            heap.try_push((*key, priority.try_clone()?))?;
+        }
+        Ok(IndexedBinaryHeap {
+            heap,
+            positions: self.positions.try_clone()?,
+        })
+    }
+}
+
+impl<P: HeapSize> HeapSize for IndexedBinaryHeap<P> {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.heap.heap_size() + self.positions.heap_size()
+    }
+}
+
+/// An iterator over the entries of an [`IndexedBinaryHeap`].
+///
+/// This `struct` is created by the [`iter`] method on [`IndexedBinaryHeap`].
+///
+/// [`iter`]: IndexedBinaryHeap::iter
+#[derive(Clone, Debug)]
+pub struct IndexedIter<'a, P>(slice::Iter<'a, (usize, P)>);
+
+impl<'a, P> Iterator for IndexedIter<'a, P> {
+    type Item = (usize, &'a P);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(key, priority)| (*key, priority))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<P> ExactSizeIterator for IndexedIter<'_, P> {}
+
+impl<P> FusedIterator for IndexedIter<'_, P> {}
@@ -0,0 +1,1942 @@
+//! An ordered map based on a B-Tree.
+//!
+//! The nodes of the tree live in a single arena vector and are linked by
+//! index. Before the tree is modified, all the nodes an operation may need
+//! are reserved in the arena, so a failed allocation leaves the map unchanged.
+
+use crate::alloc::AllocError;
+use crate::arrayvec::ArrayVec;
+use crate::clone::TryClone;
+use crate::iter::{self, TryFromIterator};
+use crate::mem::HeapSize;
+use crate::vec::Vec;
+use std::alloc::{Allocator, Global};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Bound, Index, RangeBounds};
+use std::ptr;
+
+const B: usize = 6;
+/// The maximum number of entries in a node.
+const CAPACITY: usize = 2 * B - 1;
+/// The maximum number of children of a node.
+const EDGE_CAPACITY: usize = 2 * B;
+/// The minimum number of entries in a node other than the root.
+const MIN_LEN: usize = B - 1;
+/// Marks the absence of a node.
+const NONE: usize = usize::MAX;
+
+/// The position of an entry, as the index of its node and its index in the node.
+type Pos = (usize, usize);
+
+struct Node<K, V> {
+    keys: ArrayVec<K, CAPACITY>,
+    vals: ArrayVec<V, CAPACITY>,
+    /// The children of an internal node, empty for a leaf.
+    edges: ArrayVec<usize, EDGE_CAPACITY>,
+    /// The parent of the node, or the next free node if the node is free.
+    parent: usize,
+    /// The index of the node in the edges of its parent.
+    parent_idx: usize,
+}
+
+impl<K, V> Node<K, V> {
+    #[inline]
+    fn new(parent: usize) -> Self {
+        Node {
+            keys: ArrayVec::new(),
+            vals: ArrayVec::new(),
+            edges: ArrayVec::new(),
+            parent,
+            parent_idx: 0,
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    #[inline]
+    fn is_leaf(&self) -> bool {
+        self.edges.is_empty()
+    }
+}
+
+/// An ordered map based on a B-Tree.
+///
+/// Unlike `std::collections::BTreeMap`, every operation that allocates
+/// nodes reports an [`AllocError`] instead of aborting. Removing entries
+/// never allocates.
+pub struct BTreeMap<K, V, A: Allocator = Global> {
+    nodes: Vec<Node<K, V>, A>,
+    root: usize,
+    /// The head of the list of free nodes, linked through `Node::parent`.
+    free: usize,
+    free_len: usize,
+    len: usize,
+}
+
+impl<K, V> BTreeMap<K, V> {
+    /// Makes a new, empty `BTreeMap`.
+    ///
+    /// Does not allocate anything on its own.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        BTreeMap {
+            nodes: Vec::new(),
+            root: NONE,
+            free: NONE,
+            free_len: 0,
+            len: 0,
+        }
+    }
+}
+
+impl<K, V, A: Allocator> BTreeMap<K, V, A> {
+    /// Makes a new, empty `BTreeMap` with the provided allocator.
+    #[inline]
+    pub fn new_in(alloc: A) -> Self {
+        BTreeMap {
+            nodes: Vec::new_in(alloc),
+            root: NONE,
+            free: NONE,
+            free_len: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of elements in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        self.nodes.allocator()
+    }
+
+    /// Clears the map, removing all elements.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.root = NONE;
+        self.free = NONE;
+        self.free_len = 0;
+        self.len = 0;
+    }
+
+    /// Returns the first key-value pair in the map. The key in this pair is
+    /// the minimum key in the map.
+    #[inline]
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.first_pos().map(|(id, idx)| {
+            let node = self.node(id);
+            (&node.keys[idx], &node.vals[idx])
+        })
+    }
+
+    /// Returns the last key-value pair in the map. The key in this pair is
+    /// the maximum key in the map.
+    #[inline]
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.last_pos().map(|(id, idx)| {
+            let node = self.node(id);
+            (&node.keys[idx], &node.vals[idx])
+        })
+    }
+
+    /// Removes and returns the first element in the map. The key of this
+    /// element is the minimum key that was in the map.
+    #[inline]
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        let pos = self.first_pos()?;
+        let (key, value, _) = self.remove_at(pos);
+        Some((key, value))
+    }
+
+    /// Removes and returns the last element in the map. The key of this
+    /// element is the maximum key that was in the map.
+    #[inline]
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        let pos = self.last_pos()?;
+        let (key, value, _) = self.remove_at(pos);
+        Some((key, value))
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// The elements are visited in ascending key order.
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let mut pos = self.first_pos();
+        while let Some((id, idx)) = pos {
+            let node = &mut self.nodes[id];
+            if f(&node.keys[idx], &mut node.vals[idx]) {
+                pos = unsafe { next_pos(self.nodes.as_ptr(), (id, idx)) };
+            } else {
+                pos = self.remove_at((id, idx)).2;
+            }
+        }
+    }
+
+    /// Gets an iterator over the entries of the map, sorted by key.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            range: RawRange {
+                nodes: self.nodes.as_ptr(),
+                range: self.first_pos().zip(self.last_pos()),
+            },
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Gets a mutable iterator over the entries of the map, sorted by key.
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        let range = self.first_pos().zip(self.last_pos());
+        IterMut {
+            range: RawRange {
+                nodes: self.nodes.as_mut_ptr(),
+                range,
+            },
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Gets an iterator over the keys of the map, in sorted order.
+    #[inline]
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys(self.iter())
+    }
+
+    /// Gets an iterator over the values of the map, in order by key.
+    #[inline]
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values(self.iter())
+    }
+
+    /// Gets a mutable iterator over the values of the map, in order by key.
+    #[inline]
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut(self.iter_mut())
+    }
+
+    #[inline]
+    fn node(&self, id: usize) -> &Node<K, V> {
+        &self.nodes[id]
+    }
+
+    #[inline]
+    fn node_mut(&mut self, id: usize) -> &mut Node<K, V> {
+        &mut self.nodes[id]
+    }
+
+    #[inline]
+    fn first_pos(&self) -> Option<Pos> {
+        if self.root == NONE {
+            return None;
+        }
+        let mut id = self.root;
+        while let Some(&child) = self.node(id).edges.first() {
+            id = child;
+        }
+        Some((id, 0))
+    }
+
+    #[inline]
+    fn last_pos(&self) -> Option<Pos> {
+        if self.root == NONE {
+            return None;
+        }
+        let mut id = self.root;
+        while let Some(&child) = self.node(id).edges.last() {
+            id = child;
+        }
+        Some((id, self.node(id).len() - 1))
+    }
+
+    /// Returns the number of nodes needed to insert a new entry into `leaf`.
+    #[inline]
+    fn nodes_needed(&self, leaf: usize) -> usize {
+        if leaf == NONE {
+            return 1;
+        }
+        // Every full node on the way up gets split, and a new root is needed
+        // if the root gets split.
+        let mut count = 0;
+        let mut id = leaf;
+        while id != NONE && self.node(id).len() == CAPACITY {
+            count += 1;
+            id = self.node(id).parent;
+        }
+        if id == NONE {
+            count += 1;
+        }
+        count
+    }
+
+    /// Makes sure that `additional` nodes can be created without allocating.
+    #[inline]
+    fn reserve_nodes(&mut self, additional: usize) -> Result<(), AllocError> {
+        self.nodes.try_reserve(additional.saturating_sub(self.free_len))
+    }
+
+    /// Stores a node in the arena, which must have room for it.
+    #[inline]
+    fn alloc_node(&mut self, node: Node<K, V>) -> usize {
+        if self.free != NONE {
+            let id = self.free;
+            self.free = self.node(id).parent;
+            self.free_len -= 1;
+            self.nodes[id] = node;
+            id
+        } else {
+            let id = self.nodes.len();
+            if self.nodes.push_within_capacity(node).is_err() {
+                unreachable!("nodes are reserved");
+            }
+            id
+        }
+    }
+
+    /// Removes a node from the tree, and returns its contents.
+    #[inline]
+    fn free_node(&mut self, id: usize) -> Node<K, V> {
+        let node = mem::replace(&mut self.nodes[id], Node::new(self.free));
+        self.free = id;
+        self.free_len += 1;
+        node
+    }
+
+    /// Updates the parent links of the children of `id`, starting at edge `from`.
+    #[inline]
+    fn fix_children(&mut self, id: usize, from: usize) {
+        for i in from..self.node(id).edges.len() {
+            let child = self.node(id).edges[i];
+            let child = self.node_mut(child);
+            child.parent = id;
+            child.parent_idx = i;
+        }
+    }
+
+    /// Inserts a new entry at the position returned by a failed search, and
+    /// returns the position of the entry.
+    ///
+    /// The nodes needed by the insertion must have been reserved.
+    #[inline]
+    fn insert_at(&mut self, leaf: usize, idx: usize, key: K, value: V) -> Pos {
+        self.len += 1;
+        if leaf == NONE {
+            let mut node = Node::new(NONE);
+            array_push(&mut node.keys, key);
+            array_push(&mut node.vals, value);
+            self.root = self.alloc_node(node);
+            return (self.root, 0);
+        }
+        self.insert_into(leaf, idx, key, value, NONE)
+    }
+
+    /// Inserts an entry at `idx` in node `id`, with `edge` as its right child
+    /// unless it is `NONE`. If the node is full, it is split first.
+    fn insert_into(&mut self, id: usize, idx: usize, key: K, value: V, edge: usize) -> Pos {
+        if self.node(id).len() < CAPACITY {
+            let node = self.node_mut(id);
+            array_insert(&mut node.keys, idx, key);
+            array_insert(&mut node.vals, idx, value);
+            if edge != NONE {
+                array_insert(&mut node.edges, idx + 1, edge);
+                self.fix_children(id, idx + 1);
+            }
+            return (id, idx);
+        }
+
+        let (median_key, median_value, right) = self.split(id);
+        // The new entry never becomes the median, so its position is final.
+        let pos = if idx <= MIN_LEN {
+            self.insert_into(id, idx, key, value, edge)
+        } else {
+            self.insert_into(right, idx - B, key, value, edge)
+        };
+
+        let parent = self.node(id).parent;
+        if parent == NONE {
+            let mut root = Node::new(NONE);
+            array_push(&mut root.keys, median_key);
+            array_push(&mut root.vals, median_value);
+            array_push(&mut root.edges, id);
+            array_push(&mut root.edges, right);
+            self.root = self.alloc_node(root);
+            self.fix_children(self.root, 0);
+        } else {
+            let parent_idx = self.node(id).parent_idx;
+            self.insert_into(parent, parent_idx, median_key, median_value, right);
+        }
+        pos
+    }
+
+    /// Splits a full node in two halves around its median entry, and returns
+    /// the median entry and the new right node.
+    #[inline]
+    fn split(&mut self, id: usize) -> (K, V, usize) {
+        let node = self.node_mut(id);
+        let keys = array_split_off(&mut node.keys, B);
+        let vals = array_split_off(&mut node.vals, B);
+        let edges = if node.is_leaf() {
+            ArrayVec::new()
+        } else {
+            array_split_off(&mut node.edges, B)
+        };
+        let median_key = node.keys.pop().unwrap();
+        let median_value = node.vals.pop().unwrap();
+        let right = Node {
+            keys,
+            vals,
+            edges,
+            parent: node.parent,
+            parent_idx: 0,
+        };
+        let right = self.alloc_node(right);
+        self.fix_children(right, 0);
+        (median_key, median_value, right)
+    }
+
+    /// Removes the entry at `pos`, and returns it with the position of the
+    /// next entry.
+    fn remove_at(&mut self, pos: Pos) -> (K, V, Option<Pos>) {
+        let mut next = unsafe { next_pos(self.nodes.as_ptr(), pos) };
+        let (id, idx) = pos;
+        self.len -= 1;
+
+        let (key, value, leaf) = if self.node(id).is_leaf() {
+            let node = self.node_mut(id);
+            let key = node.keys.remove(idx);
+            let value = node.vals.remove(idx);
+            if let Some(next) = &mut next {
+                if next.0 == id {
+                    next.1 -= 1;
+                }
+            }
+            (key, value, id)
+        } else {
+            // Replace the entry with its predecessor, which is in a leaf. The
+            // next entry is in another leaf, which is not touched.
+            let mut leaf = self.node(id).edges[idx];
+            while let Some(&child) = self.node(leaf).edges.last() {
+                leaf = child;
+            }
+            let node = self.node_mut(leaf);
+            let key = node.keys.pop().unwrap();
+            let value = node.vals.pop().unwrap();
+            let node = self.node_mut(id);
+            let key = mem::replace(&mut node.keys[idx], key);
+            let value = mem::replace(&mut node.vals[idx], value);
+            (key, value, leaf)
+        };
+
+        self.rebalance(leaf, &mut next);
+        if self.len == 0 {
+            self.clear();
+        }
+        (key, value, next)
+    }
+
+    /// Restores the minimum length of the nodes from `id` up to the root,
+    /// keeping `track` at the same entry.
+    fn rebalance(&mut self, mut id: usize, track: &mut Option<Pos>) {
+        loop {
+            let node = self.node(id);
+            let (parent, parent_idx, len) = (node.parent, node.parent_idx, node.len());
+            if parent == NONE {
+                if len == 0 {
+                    let node = self.free_node(id);
+                    self.root = node.edges.first().copied().unwrap_or(NONE);
+                    if self.root != NONE {
+                        let root = self.node_mut(self.root);
+                        root.parent = NONE;
+                        root.parent_idx = 0;
+                    }
+                }
+                return;
+            }
+            if len >= MIN_LEN {
+                return;
+            }
+
+            let parent_node = self.node(parent);
+            if parent_idx > 0 && self.node(parent_node.edges[parent_idx - 1]).len() > MIN_LEN {
+                self.steal_left(parent, parent_idx, track);
+                return;
+            }
+            if parent_idx < parent_node.len() && self.node(parent_node.edges[parent_idx + 1]).len() > MIN_LEN {
+                self.steal_right(parent, parent_idx, track);
+                return;
+            }
+            if parent_idx > 0 {
+                self.merge(parent, parent_idx - 1, track);
+            } else {
+                self.merge(parent, parent_idx, track);
+            }
+            id = parent;
+        }
+    }
+
+    /// Moves the last entry of the left sibling of child `idx` of `parent`
+    /// through the parent into the child.
+    #[inline]
+    fn steal_left(&mut self, parent: usize, idx: usize, track: &mut Option<Pos>) {
+        let id = self.node(parent).edges[idx];
+        let left = self.node(parent).edges[idx - 1];
+
+        let left_node = self.node_mut(left);
+        let left_len = left_node.len();
+        let key = left_node.keys.pop().unwrap();
+        let value = left_node.vals.pop().unwrap();
+        let edge = left_node.edges.pop();
+
+        let parent_node = self.node_mut(parent);
+        let key = mem::replace(&mut parent_node.keys[idx - 1], key);
+        let value = mem::replace(&mut parent_node.vals[idx - 1], value);
+
+        let node = self.node_mut(id);
+        array_insert(&mut node.keys, 0, key);
+        array_insert(&mut node.vals, 0, value);
+        if let Some(edge) = edge {
+            array_insert(&mut node.edges, 0, edge);
+            self.fix_children(id, 0);
+        }
+
+        if let Some(pos) = track {
+            if pos.0 == id {
+                pos.1 += 1;
+            } else if *pos == (parent, idx - 1) {
+                *pos = (id, 0);
+            } else if *pos == (left, left_len - 1) {
+                *pos = (parent, idx - 1);
+            }
+        }
+    }
+
+    /// Moves the first entry of the right sibling of child `idx` of `parent`
+    /// through the parent into the child.
+    #[inline]
+    fn steal_right(&mut self, parent: usize, idx: usize, track: &mut Option<Pos>) {
+        let id = self.node(parent).edges[idx];
+        let right = self.node(parent).edges[idx + 1];
+
+        let right_node = self.node_mut(right);
+        let key = right_node.keys.remove(0);
+        let value = right_node.vals.remove(0);
+        let edge = if right_node.is_leaf() {
+            None
+        } else {
+            Some(right_node.edges.remove(0))
+        };
+
+        let parent_node = self.node_mut(parent);
+        let key = mem::replace(&mut parent_node.keys[idx], key);
+        let value = mem::replace(&mut parent_node.vals[idx], value);
+
+        let node = self.node_mut(id);
+        let len = node.len();
+        array_push(&mut node.keys, key);
+        array_push(&mut node.vals, value);
+        if let Some(edge) = edge {
+            array_push(&mut node.edges, edge);
+            self.fix_children(id, len + 1);
+            self.fix_children(right, 0);
+        }
+
+        if let Some(pos) = track {
+            if *pos == (parent, idx) {
+                *pos = (id, len);
+            } else if *pos == (right, 0) {
+                *pos = (parent, idx);
+            } else if pos.0 == right {
+                pos.1 -= 1;
+            }
+        }
+    }
+
+    /// Merges child `idx + 1` of `parent` and the parent's entry `idx` into
+    /// child `idx`.
+    #[inline]
+    fn merge(&mut self, parent: usize, idx: usize, track: &mut Option<Pos>) {
+        let left = self.node(parent).edges[idx];
+
+        let parent_node = self.node_mut(parent);
+        let key = parent_node.keys.remove(idx);
+        let value = parent_node.vals.remove(idx);
+        let right = parent_node.edges.remove(idx + 1);
+        self.fix_children(parent, idx + 1);
+
+        let Node { keys, vals, edges, .. } = self.free_node(right);
+        let left_node = self.node_mut(left);
+        let left_len = left_node.len();
+        let edge_len = left_node.edges.len();
+        array_push(&mut left_node.keys, key);
+        array_push(&mut left_node.vals, value);
+        for key in keys {
+            array_push(&mut left_node.keys, key);
+        }
+        for value in vals {
+            array_push(&mut left_node.vals, value);
+        }
+        for edge in edges {
+            array_push(&mut left_node.edges, edge);
+        }
+        self.fix_children(left, edge_len);
+
+        if let Some(pos) = track {
+            if *pos == (parent, idx) {
+                *pos = (left, left_len);
+            } else if pos.0 == parent && pos.1 > idx {
+                pos.1 -= 1;
+            } else if pos.0 == right {
+                *pos = (left, left_len + 1 + pos.1);
+            }
+        }
+    }
+}
+
+impl<K: TryClone, V, A: Allocator + Clone> BTreeMap<K, V, A> {
+    /// Clones the map, cloning the values of each node with `clone_vals`.
+    #[inline]
+    pub(crate) fn try_clone_with<F>(&self, clone_vals: F) -> Result<Self, AllocError>
+    where
+        F: Fn(&ArrayVec<V, CAPACITY>) -> Result<ArrayVec<V, CAPACITY>, AllocError>,
+    {
+        let mut nodes = Vec::try_with_capacity_in(self.nodes.len(), self.allocator().clone())?;
+        for node in self.nodes.iter() {
+            let node = Node {
+                keys: node.keys.try_clone()?,
+                vals: clone_vals(&node.vals)?,
+                edges: node.edges.clone(),
+                parent: node.parent,
+                parent_idx: node.parent_idx,
+            };
+            if nodes.push_within_capacity(node).is_err() {
+                unreachable!("nodes are reserved");
+            }
+        }
+        Ok(BTreeMap {
+            nodes,
+            root: self.root,
+            free: self.free,
+            free_len: self.free_len,
+            len: self.len,
+        })
+    }
+}
+
+impl<K: Ord, V, A: Allocator> BTreeMap<K, V, A> {
+    /// Returns a reference to the value corresponding to the key.
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        let (id, idx) = self.find(key).ok()?;
+        Some(&self.node(id).vals[idx])
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key.
+    #[inline]
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        let (id, idx) = self.find(key).ok()?;
+        let node = self.node(id);
+        Some((&node.keys[idx], &node.vals[idx]))
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    #[inline]
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        let (id, idx) = self.find(key).ok()?;
+        Some(&mut self.node_mut(id).vals[idx])
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.find(key).is_ok()
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map did not have this key present, `None` is returned.
+    ///
+    /// If the map did have this key present, the value is updated, and the old
+    /// value is returned. The key is not updated, though.
+    ///
+    /// If the allocation fails, the map is left unchanged.
+    #[inline]
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, AllocError> {
+        match self.find(&key) {
+            Ok((id, idx)) => Ok(Some(mem::replace(&mut self.node_mut(id).vals[idx], value))),
+            Err((leaf, idx)) => {
+                self.reserve_nodes(self.nodes_needed(leaf))?;
+                self.insert_at(leaf, idx, key, value);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Gets the given key's corresponding entry in the map for in-place manipulation.
+    ///
+    /// The nodes needed to insert into a vacant entry are allocated up front,
+    /// so the entry API itself never fails.
+    #[inline]
+    pub fn try_entry(&mut self, key: K) -> Result<Entry<'_, K, V, A>, AllocError> {
+        match self.find(&key) {
+            Ok(pos) => Ok(Entry::Occupied(OccupiedEntry { pos, map: self })),
+            Err((leaf, idx)) => {
+                self.reserve_nodes(self.nodes_needed(leaf))?;
+                Ok(Entry::Vacant(VacantEntry {
+                    key,
+                    leaf,
+                    idx,
+                    map: self,
+                }))
+            }
+        }
+    }
+
+    /// Removes a key from the map, returning the value at the key if the key
+    /// was previously in the map.
+    #[inline]
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    /// Removes a key from the map, returning the stored key and value if the
+    /// key was previously in the map.
+    #[inline]
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        let pos = self.find(key).ok()?;
+        let (key, value, _) = self.remove_at(pos);
+        Some((key, value))
+    }
+
+    /// Constructs a double-ended iterator over a sub-range of elements in the map.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`, or if range `start == end` and both
+    /// bounds are `Excluded`.
+    #[inline]
+    pub fn range<T, R>(&self, range: R) -> Range<'_, K, V>
+    where
+        T: ?Sized + Ord,
+        K: Borrow<T>,
+        R: RangeBounds<T>,
+    {
+        Range {
+            range: RawRange {
+                nodes: self.nodes.as_ptr(),
+                range: self.find_range(range),
+            },
+            marker: PhantomData,
+        }
+    }
+
+    /// Constructs a mutable double-ended iterator over a sub-range of elements
+    /// in the map.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`, or if range `start == end` and both
+    /// bounds are `Excluded`.
+    #[inline]
+    pub fn range_mut<T, R>(&mut self, range: R) -> RangeMut<'_, K, V>
+    where
+        T: ?Sized + Ord,
+        K: Borrow<T>,
+        R: RangeBounds<T>,
+    {
+        let range = self.find_range(range);
+        RangeMut {
+            range: RawRange {
+                nodes: self.nodes.as_mut_ptr(),
+                range,
+            },
+            marker: PhantomData,
+        }
+    }
+
+    /// Moves all elements from `other` into `self`, leaving `other` empty.
+    ///
+    /// If a key from `other` is already present in `self`, the respective
+    /// value from `self` will be overwritten with the respective value from `other`.
+    ///
+    /// If the allocation fails, both maps are left unchanged.
+    #[inline]
+    pub fn try_append(&mut self, other: &mut Self) -> Result<(), AllocError> {
+        if other.is_empty() {
+            return Ok(());
+        }
+        // Every node but the root holds at least `MIN_LEN` entries, which
+        // bounds the number of nodes the merged map can need.
+        let nodes = (self.len + other.len) / MIN_LEN + 1;
+        self.nodes.try_reserve(nodes.saturating_sub(self.nodes.len()))?;
+        while let Some((key, value)) = other.pop_first() {
+            self.try_insert(key, value).expect("nodes are reserved");
+        }
+        Ok(())
+    }
+
+    /// Finds the position of `key`, or the leaf position where it would be
+    /// inserted.
+    #[inline]
+    fn find<Q>(&self, key: &Q) -> Result<Pos, Pos>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        if self.root == NONE {
+            return Err((NONE, 0));
+        }
+        let mut id = self.root;
+        loop {
+            let node = self.node(id);
+            match search(node, key) {
+                Ok(idx) => return Ok((id, idx)),
+                Err(idx) if node.is_leaf() => return Err((id, idx)),
+                Err(idx) => id = node.edges[idx],
+            }
+        }
+    }
+
+    /// Returns the positions of the first and the last entry in `range`.
+    #[inline]
+    fn find_range<T, R>(&self, range: R) -> Option<(Pos, Pos)>
+    where
+        T: ?Sized + Ord,
+        K: Borrow<T>,
+        R: RangeBounds<T>,
+    {
+        match (range.start_bound(), range.end_bound()) {
+            (Bound::Excluded(s), Bound::Excluded(e)) if s == e => {
+                panic!("range start and end are equal and excluded in BTreeMap")
+            }
+            (Bound::Included(s) | Bound::Excluded(s), Bound::Included(e) | Bound::Excluded(e)) if s > e => {
+                panic!("range start is greater than range end in BTreeMap")
+            }
+            _ => {}
+        }
+        let front = self.lower_bound(range.start_bound())?;
+        let back = self.upper_bound(range.end_bound())?;
+        if self.node(front.0).keys[front.1] <= self.node(back.0).keys[back.1] {
+            Some((front, back))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the position of the first entry above `bound`.
+    #[inline]
+    fn lower_bound<Q>(&self, bound: Bound<&Q>) -> Option<Pos>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        let (key, excluded) = match bound {
+            Bound::Included(key) => (key, false),
+            Bound::Excluded(key) => (key, true),
+            Bound::Unbounded => return self.first_pos(),
+        };
+        if self.root == NONE {
+            return None;
+        }
+        let mut found = None;
+        let mut id = self.root;
+        loop {
+            let node = self.node(id);
+            let idx = match search(node, key) {
+                Ok(idx) if !excluded => return Some((id, idx)),
+                Ok(idx) => idx + 1,
+                Err(idx) => idx,
+            };
+            if idx < node.len() {
+                found = Some((id, idx));
+            }
+            if node.is_leaf() {
+                return found;
+            }
+            id = node.edges[idx];
+        }
+    }
+
+    /// Returns the position of the last entry below `bound`.
+    #[inline]
+    fn upper_bound<Q>(&self, bound: Bound<&Q>) -> Option<Pos>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        let (key, excluded) = match bound {
+            Bound::Included(key) => (key, false),
+            Bound::Excluded(key) => (key, true),
+            Bound::Unbounded => return self.last_pos(),
+        };
+        if self.root == NONE {
+            return None;
+        }
+        let mut found = None;
+        let mut id = self.root;
+        loop {
+            let node = self.node(id);
+            let idx = match search(node, key) {
+                Ok(idx) if !excluded => return Some((id, idx)),
+                Ok(idx) | Err(idx) => idx,
+            };
+            if idx > 0 {
+                found = Some((id, idx - 1));
+            }
+            if node.is_leaf() {
+                return found;
+            }
+            id = node.edges[idx];
+        }
+    }
+}
+
+impl<K: Ord, V, A: Allocator + Clone> BTreeMap<K, V, A> {
+    /// Splits the collection into two at the given key. Returns everything
+    /// after the given key, including the key.
+    ///
+    /// If the allocation fails, the map is left unchanged.
+    #[inline]
+    pub fn try_split_off<Q>(&mut self, key: &Q) -> Result<Self, AllocError>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        let mut other = BTreeMap::new_in(self.allocator().clone());
+
+        let mut count = 0;
+        let mut pos = self.lower_bound(Bound::Included(key));
+        while let Some(p) = pos {
+            count += 1;
+            pos = unsafe { next_pos(self.nodes.as_ptr(), p) };
+        }
+        if count == 0 {
+            return Ok(other);
+        }
+
+        // Every node but the root holds at least `MIN_LEN` entries.
+        other.nodes.try_reserve_exact(count / MIN_LEN + 1)?;
+        for _ in 0..count {
+            let (key, value) = self.pop_last().unwrap();
+            other.try_insert(key, value).expect("nodes are reserved");
+        }
+        Ok(other)
+    }
+}
+
+/// Searches the keys of a node.
+#[inline]
+fn search<K, V, Q>(node: &Node<K, V>, key: &Q) -> Result<usize, usize>
+where
+    K: Borrow<Q>,
+    Q: ?Sized + Ord,
+{
+    node.keys.binary_search_by(|k| k.borrow().cmp(key))
+}
+
+#[inline]
+fn array_push<T, const N: usize>(array: &mut ArrayVec<T, N>, value: T) {
+    if array.try_push(value).is_err() {
+        unreachable!("node is full");
+    }
+}
+
+#[inline]
+fn array_insert<T, const N: usize>(array: &mut ArrayVec<T, N>, idx: usize, value: T) {
+    if array.try_insert(idx, value).is_err() {
+        unreachable!("node is full");
+    }
+}
+
+/// Moves the elements from `at` onwards into a new array.
+#[inline]
+fn array_split_off<T, const N: usize>(array: &mut ArrayVec<T, N>, at: usize) -> ArrayVec<T, N> {
+    let len = array.len();
+    let mut other = ArrayVec::new();
+    unsafe {
+        ptr::copy_nonoverlapping(array.as_ptr().add(at), other.as_mut_ptr(), len - at);
+        array.set_len(at);
+        other.set_len(len - at);
+    }
+    other
+}
+
+// The functions below navigate the tree through raw pointers, so that the
+// iterators can hand out references to keys and values while navigating.
+// They only create references to the `keys` and `edges` of a node, never to
+// the `vals` or to the node itself.
+
+/// # Safety
+///
+/// `nodes` must point to the nodes of a map, and `id` must be a node in the tree.
+#[inline]
+unsafe fn edges<'a, K, V>(nodes: *const Node<K, V>, id: usize) -> &'a [usize] {
+    (*ptr::addr_of!((*nodes.add(id)).edges)).as_slice()
+}
+
+/// # Safety
+///
+/// `nodes` must point to the nodes of a map, and `id` must be a node in the tree.
+#[inline]
+unsafe fn node_len<K, V>(nodes: *const Node<K, V>, id: usize) -> usize {
+    (*ptr::addr_of!((*nodes.add(id)).keys)).len()
+}
+
+/// # Safety
+///
+/// `nodes` must point to the nodes of a map, and `id` must be a node in the tree.
+#[inline]
+unsafe fn parent<K, V>(nodes: *const Node<K, V>, id: usize) -> (usize, usize) {
+    let node = nodes.add(id);
+    (*ptr::addr_of!((*node).parent), *ptr::addr_of!((*node).parent_idx))
+}
+
+/// # Safety
+///
+/// `nodes` must point to the nodes of a map, and `pos` must be an entry in the tree.
+#[inline]
+unsafe fn key<'a, K, V>(nodes: *const Node<K, V>, (id, idx): Pos) -> &'a K {
+    let keys: &'a ArrayVec<K, CAPACITY> = &*ptr::addr_of!((*nodes.add(id)).keys);
+    &keys[idx]
+}
+
+/// # Safety
+///
+/// `nodes` must point to the nodes of a map, and `pos` must be an entry in the tree.
+#[inline]
+unsafe fn value<'a, K, V>(nodes: *const Node<K, V>, (id, idx): Pos) -> &'a V {
+    &*ArrayVec::raw_mut_ptr(ptr::addr_of!((*nodes.add(id)).vals) as *mut ArrayVec<V, CAPACITY>).add(idx)
+}
+
+/// # Safety
+///
+/// `nodes` must point to the nodes of a map, and `pos` must be an entry in the tree.
+/// The entry must not be borrowed elsewhere.
+#[inline]
+unsafe fn value_mut<'a, K, V>(nodes: *mut Node<K, V>, (id, idx): Pos) -> &'a mut V {
+    &mut *ArrayVec::raw_mut_ptr(ptr::addr_of_mut!((*nodes.add(id)).vals)).add(idx)
+}
+
+/// Returns the position of the entry after `pos` in key order.
+///
+/// # Safety
+///
+/// `nodes` must point to the nodes of a map, and `pos` must be an entry in the tree.
+#[inline]
+unsafe fn next_pos<K, V>(nodes: *const Node<K, V>, (id, idx): Pos) -> Option<Pos> {
+    if let Some(&child) = edges(nodes, id).get(idx + 1) {
+        let mut id = child;
+        while let Some(&child) = edges(nodes, id).first() {
+            id = child;
+        }
+        return Some((id, 0));
+    }
+    if idx + 1 < node_len(nodes, id) {
+        return Some((id, idx + 1));
+    }
+    let mut id = id;
+    loop {
+        let (parent, parent_idx) = parent(nodes, id);
+        if parent == NONE {
+            return None;
+        }
+        if parent_idx < node_len(nodes, parent) {
+            return Some((parent, parent_idx));
+        }
+        id = parent;
+    }
+}
+
+/// Returns the position of the entry before `pos` in key order.
+///
+/// # Safety
+///
+/// `nodes` must point to the nodes of a map, and `pos` must be an entry in the tree.
+#[inline]
+unsafe fn prev_pos<K, V>(nodes: *const Node<K, V>, (id, idx): Pos) -> Option<Pos> {
+    if let Some(&child) = edges(nodes, id).get(idx) {
+        let mut id = child;
+        while let Some(&child) = edges(nodes, id).last() {
+            id = child;
+        }
+        return Some((id, node_len(nodes, id) - 1));
+    }
+    if idx > 0 {
+        return Some((id, idx - 1));
+    }
+    let mut id = id;
+    loop {
+        let (parent, parent_idx) = parent(nodes, id);
+        if parent == NONE {
+            return None;
+        }
+        if parent_idx > 0 {
+            return Some((parent, parent_idx - 1));
+        }
+        id = parent;
+    }
+}
+
+/// The positions of the remaining entries of an iterator.
+struct RawRange<K, V> {
+    nodes: *const Node<K, V>,
+    /// The first and the last remaining entry, or `None` if there are none.
+    range: Option<(Pos, Pos)>,
+}
+
+impl<K, V> RawRange<K, V> {
+    /// # Safety
+    ///
+    /// The tree must not have been modified since the range was created.
+    #[inline]
+    unsafe fn next(&mut self) -> Option<Pos> {
+        let (front, back) = self.range?;
+        self.range = if front == back {
+            None
+        } else {
+            next_pos(self.nodes, front).map(|front| (front, back))
+        };
+        Some(front)
+    }
+
+    /// # Safety
+    ///
+    /// The tree must not have been modified since the range was created.
+    #[inline]
+    unsafe fn next_back(&mut self) -> Option<Pos> {
+        let (front, back) = self.range?;
+        self.range = if front == back {
+            None
+        } else {
+            prev_pos(self.nodes, back).map(|back| (front, back))
+        };
+        Some(back)
+    }
+}
+
+impl<K, V> Clone for RawRange<K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        RawRange {
+            nodes: self.nodes,
+            range: self.range,
+        }
+    }
+}
+
+/// An iterator over the entries of a `BTreeMap`.
+///
+/// This `struct` is created by the [`iter`] method on [`BTreeMap`].
+///
+/// [`iter`]: BTreeMap::iter
+pub struct Iter<'a, K, V> {
+    range: RawRange<K, V>,
+    len: usize,
+    marker: PhantomData<&'a Node<K, V>>,
+}
+
+unsafe impl<K: Sync, V: Sync> Send for Iter<'_, K, V> {}
+unsafe impl<K: Sync, V: Sync> Sync for Iter<'_, K, V> {}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Iter {
+            range: self.range.clone(),
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Iter<'_, K, V> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        unsafe {
+            let pos = self.range.next()?;
+            self.len -= 1;
+            Some((key(self.range.nodes, pos), value(self.range.nodes, pos)))
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(&'a K, &'a V)> {
+        unsafe {
+            let pos = self.range.next_back()?;
+            self.len -= 1;
+            Some((key(self.range.nodes, pos), value(self.range.nodes, pos)))
+        }
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+/// A mutable iterator over the entries of a `BTreeMap`.
+///
+/// This `struct` is created by the [`iter_mut`] method on [`BTreeMap`].
+///
+/// [`iter_mut`]: BTreeMap::iter_mut
+pub struct IterMut<'a, K, V> {
+    range: RawRange<K, V>,
+    len: usize,
+    marker: PhantomData<&'a mut Node<K, V>>,
+}
+
+unsafe impl<K: Sync, V: Send> Send for IterMut<'_, K, V> {}
+unsafe impl<K: Sync, V: Sync> Sync for IterMut<'_, K, V> {}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(&'a K, &'a mut V)> {
+        unsafe {
+            let pos = self.range.next()?;
+            self.len -= 1;
+            let nodes = self.range.nodes as *mut Node<K, V>;
+            Some((key(nodes, pos), value_mut(nodes, pos)))
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for IterMut<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(&'a K, &'a mut V)> {
+        unsafe {
+            let pos = self.range.next_back()?;
+            self.len -= 1;
+            let nodes = self.range.nodes as *mut Node<K, V>;
+            Some((key(nodes, pos), value_mut(nodes, pos)))
+        }
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+
+impl<K, V> FusedIterator for IterMut<'_, K, V> {}
+
+/// An iterator over the keys of a `BTreeMap`.
+///
+/// This `struct` is created by the [`keys`] method on [`BTreeMap`].
+///
+/// [`keys`]: BTreeMap::keys
+pub struct Keys<'a, K, V>(Iter<'a, K, V>);
+
+impl<K, V> Clone for Keys<'_, K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Keys(self.0.clone())
+    }
+}
+
+impl<K: fmt::Debug, V> fmt::Debug for Keys<'_, K, V> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a K> {
+        self.0.next().map(|(k, _)| k)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Keys<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a K> {
+        self.0.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {}
+
+impl<K, V> FusedIterator for Keys<'_, K, V> {}
+
+/// An iterator over the values of a `BTreeMap`.
+///
+/// This `struct` is created by the [`values`] method on [`BTreeMap`].
+///
+/// [`values`]: BTreeMap::values
+pub struct Values<'a, K, V>(Iter<'a, K, V>);
+
+impl<K, V> Clone for Values<'_, K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Values(self.0.clone())
+    }
+}
+
+impl<K, V: fmt::Debug> fmt::Debug for Values<'_, K, V> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a V> {
+        self.0.next().map(|(_, v)| v)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Values<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a V> {
+        self.0.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<K, V> ExactSizeIterator for Values<'_, K, V> {}
+
+impl<K, V> FusedIterator for Values<'_, K, V> {}
+
+/// A mutable iterator over the values of a `BTreeMap`.
+///
+/// This `struct` is created by the [`values_mut`] method on [`BTreeMap`].
+///
+/// [`values_mut`]: BTreeMap::values_mut
+pub struct ValuesMut<'a, K, V>(IterMut<'a, K, V>);
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut V> {
+        self.0.next().map(|(_, v)| v)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for ValuesMut<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut V> {
+        self.0.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<K, V> ExactSizeIterator for ValuesMut<'_, K, V> {}
+
+impl<K, V> FusedIterator for ValuesMut<'_, K, V> {}
+
+/// An iterator over a sub-range of entries in a `BTreeMap`.
+///
+/// This `struct` is created by the [`range`] method on [`BTreeMap`].
+///
+/// [`range`]: BTreeMap::range
+pub struct Range<'a, K, V> {
+    range: RawRange<K, V>,
+    marker: PhantomData<&'a Node<K, V>>,
+}
+
+unsafe impl<K: Sync, V: Sync> Send for Range<'_, K, V> {}
+unsafe impl<K: Sync, V: Sync> Sync for Range<'_, K, V> {}
+
+impl<K, V> Clone for Range<'_, K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Range {
+            range: self.range.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Range<'_, K, V> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        unsafe {
+            let pos = self.range.next()?;
+            Some((key(self.range.nodes, pos), value(self.range.nodes, pos)))
+        }
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Range<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(&'a K, &'a V)> {
+        unsafe {
+            let pos = self.range.next_back()?;
+            Some((key(self.range.nodes, pos), value(self.range.nodes, pos)))
+        }
+    }
+}
+
+impl<K, V> FusedIterator for Range<'_, K, V> {}
+
+/// A mutable iterator over a sub-range of entries in a `BTreeMap`.
+///
+/// This `struct` is created by the [`range_mut`] method on [`BTreeMap`].
+///
+/// [`range_mut`]: BTreeMap::range_mut
+pub struct RangeMut<'a, K, V> {
+    range: RawRange<K, V>,
+    marker: PhantomData<&'a mut Node<K, V>>,
+}
+
+unsafe impl<K: Sync, V: Send> Send for RangeMut<'_, K, V> {}
+unsafe impl<K: Sync, V: Sync> Sync for RangeMut<'_, K, V> {}
+
+impl<'a, K, V> Iterator for RangeMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(&'a K, &'a mut V)> {
+        unsafe {
+            let pos = self.range.next()?;
+            let nodes = self.range.nodes as *mut Node<K, V>;
+            Some((key(nodes, pos), value_mut(nodes, pos)))
+        }
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for RangeMut<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(&'a K, &'a mut V)> {
+        unsafe {
+            let pos = self.range.next_back()?;
+            let nodes = self.range.nodes as *mut Node<K, V>;
+            Some((key(nodes, pos), value_mut(nodes, pos)))
+        }
+    }
+}
+
+impl<K, V> FusedIterator for RangeMut<'_, K, V> {}
+
+/// An owning iterator over the entries of a `BTreeMap`, sorted by key.
+///
+/// This `struct` is created by the `into_iter` method on [`BTreeMap`]
+/// (provided by the [`IntoIterator`] trait).
+pub struct IntoIter<K, V, A: Allocator = Global> {
+    map: BTreeMap<K, V, A>,
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, A: Allocator> fmt::Debug for IntoIter<K, V, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.map.iter()).finish()
+    }
+}
+
+impl<K, V, A: Allocator> Iterator for IntoIter<K, V, A> {
+    type Item = (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(K, V)> {
+        self.map.pop_first()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.map.len(), Some(self.map.len()))
+    }
+}
+
+impl<K, V, A: Allocator> DoubleEndedIterator for IntoIter<K, V, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(K, V)> {
+        self.map.pop_last()
+    }
+}
+
+impl<K, V, A: Allocator> ExactSizeIterator for IntoIter<K, V, A> {}
+
+impl<K, V, A: Allocator> FusedIterator for IntoIter<K, V, A> {}
+
+impl<K, V, A: Allocator> IntoIterator for BTreeMap<K, V, A> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V, A>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { map: self }
+    }
+}
+
+impl<'a, K, V, A: Allocator> IntoIterator for &'a BTreeMap<K, V, A> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, A: Allocator> IntoIterator for &'a mut BTreeMap<K, V, A> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// A view into a single entry in a map, which may either be vacant or occupied.
+///
+/// This `enum` is constructed from the [`try_entry`] method on [`BTreeMap`].
+///
+/// [`try_entry`]: BTreeMap::try_entry
+pub enum Entry<'a, K, V, A: Allocator = Global> {
+    /// A vacant entry.
+    Vacant(VacantEntry<'a, K, V, A>),
+    /// An occupied entry.
+    Occupied(OccupiedEntry<'a, K, V, A>),
+}
+
+/// A view into a vacant entry in a `BTreeMap`.
+/// It is part of the [`Entry`] enum.
+pub struct VacantEntry<'a, K, V, A: Allocator = Global> {
+    key: K,
+    leaf: usize,
+    idx: usize,
+    map: &'a mut BTreeMap<K, V, A>,
+}
+
+/// A view into an occupied entry in a `BTreeMap`.
+/// It is part of the [`Entry`] enum.
+pub struct OccupiedEntry<'a, K, V, A: Allocator = Global> {
+    pos: Pos,
+    map: &'a mut BTreeMap<K, V, A>,
+}
+
+impl<'a, K, V, A: Allocator> Entry<'a, K, V, A> {
+    /// Ensures a value is in the entry by inserting the default if empty, and
+    /// returns a mutable reference to the value in the entry.
+    #[inline]
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the default
+    /// function if empty, and returns a mutable reference to the value in the entry.
+    #[inline]
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the default
+    /// function called with the key if empty, and returns a mutable reference
+    /// to the value in the entry.
+    #[inline]
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the default value if empty,
+    /// and returns a mutable reference to the value in the entry.
+    #[inline]
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Returns a reference to this entry's key.
+    #[inline]
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any
+    /// potential inserts into the map.
+    #[inline]
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+impl<'a, K, V, A: Allocator> VacantEntry<'a, K, V, A> {
+    /// Gets a reference to the key that would be used when inserting a value
+    /// through the `VacantEntry`.
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Takes ownership of the key.
+    #[inline]
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Sets the value of the entry with the `VacantEntry`'s key, and returns
+    /// a mutable reference to it.
+    ///
+    /// This never allocates, the nodes were reserved by [`BTreeMap::try_entry`].
+    #[inline]
+    pub fn insert(self, value: V) -> &'a mut V {
+        let (id, idx) = self.map.insert_at(self.leaf, self.idx, self.key, value);
+        &mut self.map.node_mut(id).vals[idx]
+    }
+}
+
+impl<'a, K, V, A: Allocator> OccupiedEntry<'a, K, V, A> {
+    /// Gets a reference to the key in the entry.
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.map.node(self.pos.0).keys[self.pos.1]
+    }
+
+    /// Gets a reference to the value in the entry.
+    #[inline]
+    pub fn get(&self) -> &V {
+        &self.map.node(self.pos.0).vals[self.pos.1]
+    }
+
+    /// Gets a mutable reference to the value in the entry.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.node_mut(self.pos.0).vals[self.pos.1]
+    }
+
+    /// Converts the entry into a mutable reference to its value.
+    #[inline]
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.node_mut(self.pos.0).vals[self.pos.1]
+    }
+
+    /// Sets the value of the entry, and returns the entry's old value.
+    #[inline]
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    /// Takes the key-value pair out of the map, and returns it.
+    #[inline]
+    pub fn remove_entry(self) -> (K, V) {
+        let (key, value, _) = self.map.remove_at(self.pos);
+        (key, value)
+    }
+
+    /// Takes the value of the entry out of the map, and returns it.
+    #[inline]
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, A: Allocator> fmt::Debug for Entry<'_, K, V, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Entry::Vacant(entry) => f.debug_tuple("Entry").field(entry).finish(),
+            Entry::Occupied(entry) => f.debug_tuple("Entry").field(entry).finish(),
+        }
+    }
+}
+
+impl<K: fmt::Debug, V, A: Allocator> fmt::Debug for VacantEntry<'_, K, V, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VacantEntry").field(self.key()).finish()
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, A: Allocator> fmt::Debug for OccupiedEntry<'_, K, V, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedEntry")
+            .field("key", self.key())
+            .field("value", self.get())
+            .finish()
+    }
+}
+
+impl<K, V> Default for BTreeMap<K, V> {
+    #[inline]
+    fn default() -> Self {
+        BTreeMap::new()
+    }
+}
+
+impl<K, Q, V, A: Allocator> Index<&Q> for BTreeMap<K, V, A>
+where
+    K: Borrow<Q> + Ord,
+    Q: ?Sized + Ord,
+{
+    type Output = V;
+
+    /// Returns a reference to the value corresponding to the supplied key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not present in the `BTreeMap`.
+    #[inline]
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, A: Allocator> fmt::Debug for BTreeMap<K, V, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: PartialEq, V: PartialEq, A: Allocator> PartialEq for BTreeMap<K, V, A> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(a, b)| a == b)
+    }
+}
+
+impl<K: Eq, V: Eq, A: Allocator> Eq for BTreeMap<K, V, A> {}
+
+impl<K: PartialOrd, V: PartialOrd, A: Allocator> PartialOrd for BTreeMap<K, V, A> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<K: Ord, V: Ord, A: Allocator> Ord for BTreeMap<K, V, A> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<K: Hash, V: Hash, A: Allocator> Hash for BTreeMap<K, V, A> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for entry in self {
+            entry.hash(state);
+        }
+    }
+}
+
+impl<K: TryClone, V: TryClone, A: Allocator + Clone> TryClone for BTreeMap<K, V, A> {
+    #[inline]
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        self.try_clone_with(TryClone::try_clone)
+    }
+}
+
+impl<K: HeapSize, V: HeapSize, A: Allocator> HeapSize for BTreeMap<K, V, A> {
+    /// Reports the capacity of the node arena, like [`Vec`] reports its
+    /// capacity. It includes the nodes on the free list, which are reused
+    /// by later insertions.
+    #[inline]
+    fn heap_size(&self) -> usize {
+        let entries: usize = self.iter().map(|(k, v)| k.heap_size() + v.heap_size()).sum();
+        self.nodes.capacity() * mem::size_of::<Node<K, V>>() + entries
+    }
+}
+
+impl<K: Ord, V> TryFromIterator<(K, V)> for BTreeMap<K, V> {
+    type Error = AllocError;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Result<Self, Self::Error> {
+        let mut map = BTreeMap::new();
+        for (key, value) in iter {
+            map.try_insert(key, value)?;
+        }
+        Ok(map)
+    }
+}
+
+impl<K: Ord, V, E> TryFromIterator<Result<(K, V), E>> for BTreeMap<K, V>
+where
+    E: From<AllocError>,
+{
+    type Error = E;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = Result<(K, V), E>>>(iter: I) -> Result<Self, Self::Error> {
+        iter::try_process(iter.into_iter(), |shunt| BTreeMap::try_from_iter(shunt))
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use super::BTreeMap;
+    use serde_crate::de::{Error, MapAccess, Visitor};
+    use serde_crate::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::fmt;
+    use std::marker::PhantomData;
+
+    impl<K: Serialize, V: Serialize> Serialize for BTreeMap<K, V> {
+        #[inline]
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_map(self)
+        }
+    }
+
+    impl<'de, K, V> Deserialize<'de> for BTreeMap<K, V>
+    where
+        K: Deserialize<'de> + Ord,
+        V: Deserialize<'de>,
+    {
+        #[inline]
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            struct MapVisitor<K, V> {
+                _marker: PhantomData<BTreeMap<K, V>>,
+            }
+
+            impl<'de, K, V> Visitor<'de> for MapVisitor<K, V>
+            where
+                K: Deserialize<'de> + Ord,
+                V: Deserialize<'de>,
+            {
+                type Value = BTreeMap<K, V>;
+
+                #[inline]
+                fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.write_str("a map")
+                }
+
+                #[inline]
+                fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+                where
+                    A: MapAccess<'de>,
+                {
+                    let mut values = BTreeMap::new();
+
+                    while let Some((key, value)) = map.next_entry()? {
+                        values.try_insert(key, value).map_err(A::Error::custom)?;
+                    }
+
+                    Ok(values)
+                }
+            }
+
+            let visitor = MapVisitor { _marker: PhantomData };
+            deserializer.deserialize_map(visitor)
+        }
+    }
+}
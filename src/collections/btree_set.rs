@@ -0,0 +1,504 @@
+//! An ordered set based on a B-Tree.
+
+use crate::alloc::AllocError;
+use crate::clone::TryClone;
+use crate::collections::btree_map::{self, BTreeMap};
+use crate::iter::{self, TryFromIterator};
+use crate::mem::HeapSize;
+use std::alloc::{Allocator, Global};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FusedIterator;
+use std::ops::RangeBounds;
+
+/// An ordered set based on a B-Tree.
+///
+/// Unlike `std::collections::BTreeSet`, every operation that allocates
+/// nodes reports an [`AllocError`] instead of aborting.
+pub struct BTreeSet<T, A: Allocator = Global> {
+    map: BTreeMap<T, (), A>,
+}
+
+impl<T> BTreeSet<T> {
+    /// Makes a new, empty `BTreeSet`.
+    ///
+    /// Does not allocate anything on its own.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        BTreeSet { map: BTreeMap::new() }
+    }
+}
+
+impl<T, A: Allocator> BTreeSet<T, A> {
+    /// Makes a new, empty `BTreeSet` with the provided allocator.
+    #[inline]
+    pub fn new_in(alloc: A) -> Self {
+        BTreeSet {
+            map: BTreeMap::new_in(alloc),
+        }
+    }
+
+    /// Returns the number of elements in the set.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the set contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        self.map.allocator()
+    }
+
+    /// Clears the set, removing all elements.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.map.clear()
+    }
+
+    /// Returns a reference to the first element in the set, if any.
+    /// This element is always the minimum of all elements in the set.
+    #[inline]
+    pub fn first(&self) -> Option<&T> {
+        self.map.first_key_value().map(|(k, _)| k)
+    }
+
+    /// Returns a reference to the last element in the set, if any.
+    /// This element is always the maximum of all elements in the set.
+    #[inline]
+    pub fn last(&self) -> Option<&T> {
+        self.map.last_key_value().map(|(k, _)| k)
+    }
+
+    /// Removes the first element from the set and returns it, if any.
+    /// The first element is always the minimum element in the set.
+    #[inline]
+    pub fn pop_first(&mut self) -> Option<T> {
+        self.map.pop_first().map(|(k, _)| k)
+    }
+
+    /// Removes the last element from the set and returns it, if any.
+    /// The last element is always the maximum element in the set.
+    #[inline]
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.map.pop_last().map(|(k, _)| k)
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// The elements are visited in ascending order.
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.map.retain(|k, _| f(k));
+    }
+
+    /// Gets an iterator that visits the elements in the `BTreeSet` in ascending order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self.map.keys())
+    }
+}
+
+impl<T: Ord, A: Allocator> BTreeSet<T, A> {
+    /// Returns `true` if the set contains an element equal to the value.
+    #[inline]
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.map.contains_key(value)
+    }
+
+    /// Returns a reference to the element in the set, if any, that is equal to the value.
+    #[inline]
+    pub fn get<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.map.get_key_value(value).map(|(k, _)| k)
+    }
+
+    /// Adds a value to the set.
+    ///
+    /// Returns whether the value was newly inserted. That is:
+    ///
+    /// - If the set did not previously contain an equal value, `true` is returned.
+    /// - If the set already contained an equal value, `false` is returned, and
+    ///   the entry is not updated.
+    ///
+    /// If the allocation fails, the set is left unchanged.
+    #[inline]
+    pub fn try_insert(&mut self, value: T) -> Result<bool, AllocError> {
+        Ok(self.map.try_insert(value, ())?.is_none())
+    }
+
+    /// If the set contains an element equal to the value, removes it from the
+    /// set and drops it. Returns whether such an element was present.
+    #[inline]
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    /// Removes and returns the element in the set, if any, that is equal to the value.
+    #[inline]
+    pub fn take<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.map.remove_entry(value).map(|(k, _)| k)
+    }
+
+    /// Constructs a double-ended iterator over a sub-range of elements in the set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`, or if range `start == end` and both
+    /// bounds are `Excluded`.
+    #[inline]
+    pub fn range<K, R>(&self, range: R) -> Range<'_, T>
+    where
+        K: ?Sized + Ord,
+        T: Borrow<K>,
+        R: RangeBounds<K>,
+    {
+        Range(self.map.range(range))
+    }
+
+    /// Moves all elements from `other` into `self`, leaving `other` empty.
+    ///
+    /// If the allocation fails, both sets are left unchanged.
+    #[inline]
+    pub fn try_append(&mut self, other: &mut Self) -> Result<(), AllocError> {
+        self.map.try_append(&mut other.map)
+    }
+}
+
+impl<T: Ord, A: Allocator + Clone> BTreeSet<T, A> {
+    /// Splits the collection into two at the value. Returns a new collection
+    /// with all elements greater than or equal to the value.
+    ///
+    /// If the allocation fails, the set is left unchanged.
+    #[inline]
+    pub fn try_split_off<Q>(&mut self, value: &Q) -> Result<Self, AllocError>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        Ok(BTreeSet {
+            map: self.map.try_split_off(value)?,
+        })
+    }
+}
+
+/// An iterator over the items of a `BTreeSet`.
+///
+/// This `struct` is created by the [`iter`] method on [`BTreeSet`].
+///
+/// [`iter`]: BTreeSet::iter
+pub struct Iter<'a, T>(btree_map::Keys<'a, T, ()>);
+
+impl<T> Clone for Iter<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Iter(self.0.clone())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Iter<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Iter").field(&self.0).finish()
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        self.0.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.0.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// An iterator over a sub-range of items in a `BTreeSet`.
+///
+/// This `struct` is created by the [`range`] method on [`BTreeSet`].
+///
+/// [`range`]: BTreeSet::range
+pub struct Range<'a, T>(btree_map::Range<'a, T, ()>);
+
+impl<T> Clone for Range<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Range(self.0.clone())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Range<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, T> Iterator for Range<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        self.0.next().map(|(k, _)| k)
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Range<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.0.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<T> FusedIterator for Range<'_, T> {}
+
+/// An owning iterator over the items of a `BTreeSet` in ascending order.
+///
+/// This `struct` is created by the `into_iter` method on [`BTreeSet`]
+/// (provided by the [`IntoIterator`] trait).
+pub struct IntoIter<T, A: Allocator = Global>(btree_map::IntoIter<T, (), A>);
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for IntoIter<T, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.0).finish()
+    }
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.0.next().map(|(k, _)| k)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.0.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> IntoIterator for BTreeSet<T, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self.map.into_iter())
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a BTreeSet<T, A> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> Default for BTreeSet<T> {
+    #[inline]
+    fn default() -> Self {
+        BTreeSet::new()
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for BTreeSet<T, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, A: Allocator> PartialEq for BTreeSet<T, A> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<T: Eq, A: Allocator> Eq for BTreeSet<T, A> {}
+
+impl<T: PartialOrd, A: Allocator> PartialOrd for BTreeSet<T, A> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord, A: Allocator> Ord for BTreeSet<T, A> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash, A: Allocator> Hash for BTreeSet<T, A> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for elt in self {
+            elt.hash(state);
+        }
+    }
+}
+
+impl<T: TryClone, A: Allocator + Clone> TryClone for BTreeSet<T, A> {
+    #[inline]
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        Ok(BTreeSet {
+            map: self.map.try_clone_with(|vals| Ok(vals.clone()))?,
+        })
+    }
+}
+
+impl<T: HeapSize, A: Allocator> HeapSize for BTreeSet<T, A> {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.map.heap_size()
+    }
+}
+
+impl<T: Ord> TryFromIterator<T> for BTreeSet<T> {
+    type Error = AllocError;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, Self::Error> {
+        let mut set = BTreeSet::new();
+        for value in iter {
+            set.try_insert(value)?;
+        }
+        Ok(set)
+    }
+}
+
+impl<T: Ord, E> TryFromIterator<Result<T, E>> for BTreeSet<T>
+where
+    E: From<AllocError>,
+{
+    type Error = E;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = Result<T, E>>>(iter: I) -> Result<Self, Self::Error> {
+        iter::try_process(iter.into_iter(), |shunt| BTreeSet::try_from_iter(shunt))
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use super::BTreeSet;
+    use serde_crate::de::{Error, SeqAccess, Visitor};
+    use serde_crate::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::fmt;
+    use std::marker::PhantomData;
+
+    impl<T: Serialize> Serialize for BTreeSet<T> {
+        #[inline]
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_seq(self)
+        }
+    }
+
+    impl<'de, T> Deserialize<'de> for BTreeSet<T>
+    where
+        T: Deserialize<'de> + Ord,
+    {
+        #[inline]
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            struct SeqVisitor<T> {
+                _marker: PhantomData<BTreeSet<T>>,
+            }
+
+            impl<'de, T> Visitor<'de> for SeqVisitor<T>
+            where
+                T: Deserialize<'de> + Ord,
+            {
+                type Value = BTreeSet<T>;
+
+                #[inline]
+                fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.write_str("a sequence")
+                }
+
+                #[inline]
+                fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                where
+                    A: SeqAccess<'de>,
+                {
+                    let mut values = BTreeSet::new();
+
+                    while let Some(value) = seq.next_element()? {
+                        values.try_insert(value).map_err(A::Error::custom)?;
+                    }
+
+                    Ok(values)
+                }
+            }
+
+            let visitor = SeqVisitor { _marker: PhantomData };
+            deserializer.deserialize_seq(visitor)
+        }
+    }
+}
@@ -0,0 +1,1430 @@
+//! A hash map that preserves the insertion order of its entries.
+//!
+//! The entries are stored in a [`Vec`], and a hash table of indices into the
+//! entries is used for lookups. Both are grown through `try_reserve` before
+//! anything is inserted, so a failed allocation leaves the map unchanged.
+
+use crate::alloc::AllocError;
+use crate::clone::TryClone;
+use crate::iter::{self, TryFromIterator};
+use crate::mem::HeapSize;
+use crate::vec::{self, Vec};
+use fallacy_hash::FxBuildHasher;
+use std::alloc::{Allocator, Global};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::FusedIterator;
+use std::mem;
+use std::ops::{Index, IndexMut};
+use std::slice;
+
+/// Marks an empty slot of the hash table.
+const EMPTY: usize = usize::MAX;
+/// The minimum number of slots of a non-empty hash table.
+const MIN_SLOTS: usize = 8;
+
+/// A hash map that preserves the insertion order of its entries.
+///
+/// The entries can also be accessed by their index, in `0..len()`.
+/// [`swap_remove`] removes an entry in `O(1)` but moves the last entry into
+/// its place, while [`shift_remove`] keeps the order of the remaining entries
+/// but takes `O(n)` time.
+///
+/// [`swap_remove`]: IndexMap::swap_remove
+/// [`shift_remove`]: IndexMap::shift_remove
+pub struct IndexMap<K, V, S = FxBuildHasher, A: Allocator = Global> {
+    entries: Vec<Bucket<K, V>, A>,
+    /// An open addressing hash table of indices into `entries`, probed linearly.
+    indices: Vec<usize, A>,
+    hash_builder: S,
+}
+
+struct Bucket<K, V> {
+    hash: u64,
+    key: K,
+    value: V,
+}
+
+impl<K, V> IndexMap<K, V> {
+    /// Creates an empty `IndexMap`.
+    ///
+    /// The map is initially created with a capacity of 0, so it will not allocate
+    /// until it is first inserted into.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        IndexMap::with_hasher(FxBuildHasher::default())
+    }
+
+    /// Creates an empty `IndexMap` with at least the specified capacity.
+    #[inline]
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, AllocError> {
+        IndexMap::try_with_capacity_and_hasher(capacity, FxBuildHasher::default())
+    }
+}
+
+impl<K, V, S> IndexMap<K, V, S> {
+    /// Creates an empty `IndexMap` which will use the given hash builder to hash keys.
+    #[inline]
+    pub const fn with_hasher(hash_builder: S) -> Self {
+        IndexMap {
+            entries: Vec::new(),
+            indices: Vec::new(),
+            hash_builder,
+        }
+    }
+}
+
+impl<K, V, S, A: Allocator + Clone> IndexMap<K, V, S, A> {
+    /// Creates an empty `IndexMap` which will use the given hash builder to hash keys,
+    /// and the given allocator to allocate its entries.
+    #[inline]
+    pub fn with_hasher_in(hash_builder: S, alloc: A) -> Self {
+        IndexMap {
+            entries: Vec::new_in(alloc.clone()),
+            indices: Vec::new_in(alloc),
+            hash_builder,
+        }
+    }
+
+    /// Creates an empty `IndexMap` with at least the specified capacity, using
+    /// `hash_builder` to hash the keys.
+    #[inline]
+    pub fn try_with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Result<Self, AllocError>
+    where
+        A: Default,
+    {
+        let mut map = IndexMap::with_hasher_in(hash_builder, A::default());
+        map.try_reserve(capacity)?;
+        Ok(map)
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements to be
+    /// inserted in the map.
+    ///
+    /// If the allocation fails, the map is left unchanged.
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        let len = self.len().checked_add(additional).expect("capacity overflow");
+        self.entries.try_reserve(additional)?;
+        if len > max_len(self.indices.len()) {
+            let slots = slots_for(len);
+            let mut indices = Vec::try_with_capacity_in(slots, self.indices.allocator().clone())?;
+            indices.try_resize(slots, EMPTY)?;
+            self.indices = indices;
+            self.rebuild_indices();
+        }
+        Ok(())
+    }
+}
+
+impl<K, V, S, A: Allocator> IndexMap<K, V, S, A> {
+    /// Returns the number of elements the map can hold without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.entries.capacity().min(max_len(self.indices.len()))
+    }
+
+    /// Returns the number of elements in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns a reference to the map's [`BuildHasher`].
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        self.entries.allocator()
+    }
+
+    /// Clears the map, removing all key-value pairs. Keeps the allocated memory
+    /// for reuse.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.indices.fill(EMPTY);
+    }
+
+    /// Shortens the map, keeping the first `len` elements and dropping the rest.
+    ///
+    /// If `len` is greater than the map's current length, this has no effect.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.entries.truncate(len);
+            self.rebuild_indices();
+        }
+    }
+
+    /// An iterator visiting all key-value pairs in insertion order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            iter: self.entries.iter(),
+        }
+    }
+
+    /// An iterator visiting all key-value pairs in insertion order, with
+    /// mutable references to the values.
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            iter: self.entries.iter_mut(),
+        }
+    }
+
+    /// An iterator visiting all keys in insertion order.
+    #[inline]
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys {
+            iter: self.entries.iter(),
+        }
+    }
+
+    /// An iterator visiting all values in insertion order.
+    #[inline]
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values {
+            iter: self.entries.iter(),
+        }
+    }
+
+    /// An iterator visiting all values mutably in insertion order.
+    #[inline]
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut {
+            iter: self.entries.iter_mut(),
+        }
+    }
+
+    /// Returns the key-value pair at the given index.
+    #[inline]
+    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        self.entries.get(index).map(|b| (&b.key, &b.value))
+    }
+
+    /// Returns the key and a mutable reference to the value at the given index.
+    #[inline]
+    pub fn get_index_mut(&mut self, index: usize) -> Option<(&K, &mut V)> {
+        self.entries.get_mut(index).map(|b| (&b.key, &mut b.value))
+    }
+
+    /// Returns the first key-value pair.
+    #[inline]
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.get_index(0)
+    }
+
+    /// Returns the last key-value pair.
+    #[inline]
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.entries.last().map(|b| (&b.key, &b.value))
+    }
+
+    /// Removes the last key-value pair and returns it.
+    #[inline]
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        let index = self.len().checked_sub(1)?;
+        Some(self.swap_remove_index(index).unwrap())
+    }
+
+    /// Removes the key-value pair at the given index, replacing it with the
+    /// last pair, and returns it.
+    ///
+    /// This perturbs the position of the last pair, and takes `O(1)` time.
+    #[inline]
+    pub fn swap_remove_index(&mut self, index: usize) -> Option<(K, V)> {
+        if index >= self.len() {
+            return None;
+        }
+        let slot = self.slot_of(index);
+        let bucket = self.swap_remove_slot(slot, index);
+        Some((bucket.key, bucket.value))
+    }
+
+    /// Removes the key-value pair at the given index, shifting all the pairs
+    /// that follow it, and returns it.
+    ///
+    /// This preserves the order of the remaining pairs, and takes `O(n)` time.
+    #[inline]
+    pub fn shift_remove_index(&mut self, index: usize) -> Option<(K, V)> {
+        if index >= self.len() {
+            return None;
+        }
+        let slot = self.slot_of(index);
+        let bucket = self.shift_remove_slot(slot, index);
+        Some((bucket.key, bucket.value))
+    }
+
+    /// Retains only the elements specified by the predicate, in order.
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let len = self.len();
+        let guard = RebuildIndicesOnUnwind(self);
+        guard.0.entries.retain_mut(|b| f(&b.key, &mut b.value));
+        mem::forget(guard);
+        if self.len() < len {
+            self.rebuild_indices();
+        }
+    }
+
+    /// Sorts the map's key-value pairs by key.
+    ///
+    /// The sort allocates its scratch memory before the map is modified. If
+    /// the allocation fails, the map is left unchanged.
+    #[inline]
+    pub fn try_sort_keys(&mut self) -> Result<(), AllocError>
+    where
+        K: Ord,
+    {
+        self.try_sort_by(|k1, _, k2, _| k1.cmp(k2))
+    }
+
+    /// Sorts the map's key-value pairs with a comparison function.
+    ///
+    /// The sort is stable, and allocates its scratch memory before the map is
+    /// modified. If the allocation fails, the map is left unchanged.
+    #[inline]
+    pub fn try_sort_by<F>(&mut self, mut cmp: F) -> Result<(), AllocError>
+    where
+        F: FnMut(&K, &V, &K, &V) -> Ordering,
+    {
+        let guard = RebuildIndicesOnUnwind(self);
+        let res = guard
+            .0
+            .entries
+            .try_sort_by(|a, b| cmp(&a.key, &a.value, &b.key, &b.value));
+        mem::forget(guard);
+        res?;
+        self.rebuild_indices();
+        Ok(())
+    }
+
+    /// Reverses the order of the map's key-value pairs in place.
+    #[inline]
+    pub fn reverse(&mut self) {
+        self.entries.reverse();
+        self.rebuild_indices();
+    }
+
+    /// Returns the slot of the table which holds `index`.
+    #[inline]
+    fn slot_of(&self, index: usize) -> usize {
+        let mask = self.indices.len() - 1;
+        let mut slot = self.entries[index].hash as usize & mask;
+        while self.indices[slot] != index {
+            slot = (slot + 1) & mask;
+        }
+        slot
+    }
+
+    /// Returns the slot of the table which holds an entry accepted by `eq`.
+    #[inline]
+    fn find_slot<F>(&self, hash: u64, mut eq: F) -> Option<usize>
+    where
+        F: FnMut(&Bucket<K, V>) -> bool,
+    {
+        if self.indices.is_empty() {
+            return None;
+        }
+        let mask = self.indices.len() - 1;
+        let mut slot = hash as usize & mask;
+        loop {
+            let index = self.indices[slot];
+            if index == EMPTY {
+                return None;
+            }
+            let bucket = &self.entries[index];
+            if bucket.hash == hash && eq(bucket) {
+                return Some(slot);
+            }
+            slot = (slot + 1) & mask;
+        }
+    }
+
+    /// Stores `index` in the first empty slot for `hash`. The table must
+    /// have room for it.
+    #[inline]
+    fn insert_index(&mut self, hash: u64, index: usize) {
+        let mask = self.indices.len() - 1;
+        let mut slot = hash as usize & mask;
+        while self.indices[slot] != EMPTY {
+            slot = (slot + 1) & mask;
+        }
+        self.indices[slot] = index;
+    }
+
+    /// Empties a slot, shifting back the entries of the same probe sequence
+    /// so that no lookup stops early.
+    #[inline]
+    fn erase_slot(&mut self, slot: usize) {
+        let mask = self.indices.len() - 1;
+        let mut hole = slot;
+        let mut next = (slot + 1) & mask;
+        loop {
+            let index = self.indices[next];
+            if index == EMPTY {
+                break;
+            }
+            let ideal = self.entries[index].hash as usize & mask;
+            // The entry can fill the hole if the hole lies between its ideal slot and its slot.
+            if next.wrapping_sub(ideal) & mask >= next.wrapping_sub(hole) & mask {
+                self.indices[hole] = index;
+                hole = next;
+            }
+            next = (next + 1) & mask;
+        }
+        self.indices[hole] = EMPTY;
+    }
+
+    /// Rebuilds the table from the entries, without allocating.
+    #[inline]
+    fn rebuild_indices(&mut self) {
+        self.indices.fill(EMPTY);
+        for index in 0..self.entries.len() {
+            self.insert_index(self.entries[index].hash, index);
+        }
+    }
+
+    #[inline]
+    fn swap_remove_slot(&mut self, slot: usize, index: usize) -> Bucket<K, V> {
+        self.erase_slot(slot);
+        let last = self.entries.len() - 1;
+        if index != last {
+            let last_slot = self.slot_of(last);
+            self.indices[last_slot] = index;
+        }
+        self.entries.swap_remove(index)
+    }
+
+    #[inline]
+    fn shift_remove_slot(&mut self, slot: usize, index: usize) -> Bucket<K, V> {
+        self.erase_slot(slot);
+        for i in self.indices.iter_mut() {
+            if *i != EMPTY && *i > index {
+                *i -= 1;
+            }
+        }
+        self.entries.remove(index)
+    }
+
+    /// Clones the map, cloning the values with `clone_value`.
+    #[inline]
+    pub(crate) fn try_clone_with<F>(&self, clone_value: F) -> Result<Self, AllocError>
+    where
+        K: TryClone,
+        S: Clone,
+        A: Clone,
+        F: Fn(&V) -> Result<V, AllocError>,
+    {
+        let mut entries = Vec::try_with_capacity_in(self.len(), self.allocator().clone())?;
+        for bucket in self.entries.iter() {
+            let bucket = Bucket {
+                hash: bucket.hash,
+                key: bucket.key.try_clone()?,
+                value: clone_value(&bucket.value)?,
+            };
+            if entries.push_within_capacity(bucket).is_err() {
+                unreachable!("entries are reserved");
+            }
+        }
+        Ok(IndexMap {
+            entries,
+            indices: self.indices.try_clone()?,
+            hash_builder: self.hash_builder.clone(),
+        })
+    }
+}
+
+impl<K, V, S, A> IndexMap<K, V, S, A>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    A: Allocator,
+{
+    /// Returns a reference to the value corresponding to the key.
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let index = self.get_index_of(key)?;
+        Some(&self.entries[index].value)
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key.
+    #[inline]
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let index = self.get_index_of(key)?;
+        let bucket = &self.entries[index];
+        Some((&bucket.key, &bucket.value))
+    }
+
+    /// Returns the index, key and value corresponding to the supplied key.
+    #[inline]
+    pub fn get_full<Q>(&self, key: &Q) -> Option<(usize, &K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let index = self.get_index_of(key)?;
+        let bucket = &self.entries[index];
+        Some((index, &bucket.key, &bucket.value))
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    #[inline]
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let index = self.get_index_of(key)?;
+        Some(&mut self.entries[index].value)
+    }
+
+    /// Returns the index of the key, if it is present in the map.
+    #[inline]
+    pub fn get_index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let slot = self.find(key)?;
+        Some(self.indices[slot])
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Removes a key from the map, returning the value at the key if the key
+    /// was previously in the map.
+    ///
+    /// Like [`Vec::swap_remove`], the pair is removed by swapping it with the
+    /// last element of the map. This perturbs the position of the last pair,
+    /// and takes `O(1)` time.
+    #[inline]
+    pub fn swap_remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.swap_remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Removes a key from the map, returning the stored key and value if the
+    /// key was previously in the map.
+    ///
+    /// This perturbs the position of the last pair, and takes `O(1)` time.
+    #[inline]
+    pub fn swap_remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let slot = self.find(key)?;
+        let bucket = self.swap_remove_slot(slot, self.indices[slot]);
+        Some((bucket.key, bucket.value))
+    }
+
+    /// Removes a key from the map, returning the value at the key if the key
+    /// was previously in the map.
+    ///
+    /// Like [`Vec::remove`], the pair is removed by shifting all of the
+    /// elements that follow it. This preserves the order of the remaining
+    /// pairs, and takes `O(n)` time.
+    #[inline]
+    pub fn shift_remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.shift_remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Removes a key from the map, returning the stored key and value if the
+    /// key was previously in the map.
+    ///
+    /// This preserves the order of the remaining pairs, and takes `O(n)` time.
+    #[inline]
+    pub fn shift_remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let slot = self.find(key)?;
+        let bucket = self.shift_remove_slot(slot, self.indices[slot]);
+        Some((bucket.key, bucket.value))
+    }
+
+    #[inline]
+    fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let hash = self.hash_builder.hash_one(key);
+        self.find_slot(hash, |b| b.key.borrow() == key)
+    }
+}
+
+impl<K, V, S, A> IndexMap<K, V, S, A>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    A: Allocator + Clone,
+{
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map did not have this key present, the pair is appended to the
+    /// end of the map, and `None` is returned.
+    ///
+    /// If the map did have this key present, the value is updated in place,
+    /// and the old value is returned. The key is not updated, though.
+    ///
+    /// If the allocation fails, the map is left unchanged.
+    #[inline]
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, AllocError> {
+        Ok(self.try_insert_full(key, value)?.1)
+    }
+
+    /// Inserts a key-value pair into the map, and returns the index of the pair
+    /// with the old value, if any.
+    ///
+    /// If the allocation fails, the map is left unchanged.
+    #[inline]
+    pub fn try_insert_full(&mut self, key: K, value: V) -> Result<(usize, Option<V>), AllocError> {
+        match self.try_entry(key)? {
+            Entry::Occupied(mut entry) => Ok((entry.index(), Some(entry.insert(value)))),
+            Entry::Vacant(entry) => {
+                let index = entry.index();
+                entry.insert(value);
+                Ok((index, None))
+            }
+        }
+    }
+
+    /// Gets the given key's corresponding entry in the map for in-place manipulation.
+    ///
+    /// The capacity needed to insert into a vacant entry is reserved up front,
+    /// so the entry API itself never fails.
+    #[inline]
+    pub fn try_entry(&mut self, key: K) -> Result<Entry<'_, K, V, S, A>, AllocError> {
+        let hash = self.hash_builder.hash_one(&key);
+        match self.find_slot(hash, |b| b.key == key) {
+            Some(slot) => Ok(Entry::Occupied(OccupiedEntry { map: self, slot })),
+            None => {
+                self.try_reserve(1)?;
+                Ok(Entry::Vacant(VacantEntry { map: self, hash, key }))
+            }
+        }
+    }
+
+    /// Inserts all the key-value pairs of an iterator into the map.
+    ///
+    /// If the allocation fails, the pairs inserted so far stay in the map.
+    #[inline]
+    pub fn try_extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) -> Result<(), AllocError> {
+        let iter = iter.into_iter();
+        self.try_reserve(iter.size_hint().0)?;
+        for (key, value) in iter {
+            self.try_insert(key, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the maximum number of entries a table with `slots` slots can index.
+///
+/// The load is capped at 3/4, which keeps the clusters of linear probing short.
+#[inline]
+fn max_len(slots: usize) -> usize {
+    slots / 4 * 3
+}
+
+/// Returns the number of slots needed to index `len` entries.
+#[inline]
+fn slots_for(len: usize) -> usize {
+    let slots = len
+        .checked_add(len.div_ceil(3))
+        .and_then(usize::checked_next_power_of_two)
+        .expect("capacity overflow");
+    slots.max(MIN_SLOTS)
+}
+
+/// Rebuilds the hash table of a map when dropped during unwinding, after a
+/// user callback that moves the entries panicked.
+struct RebuildIndicesOnUnwind<'a, K, V, S, A: Allocator>(&'a mut IndexMap<K, V, S, A>);
+
+impl<K, V, S, A: Allocator> Drop for RebuildIndicesOnUnwind<'_, K, V, S, A> {
+    #[inline]
+    fn drop(&mut self) {
+        self.0.rebuild_indices();
+    }
+}
+
+/// A view into a single entry in a map, which may either be vacant or occupied.
+///
+/// This `enum` is constructed from the [`try_entry`] method on [`IndexMap`].
+///
+/// [`try_entry`]: IndexMap::try_entry
+pub enum Entry<'a, K, V, S = FxBuildHasher, A: Allocator = Global> {
+    /// An occupied entry.
+    Occupied(OccupiedEntry<'a, K, V, S, A>),
+    /// A vacant entry.
+    Vacant(VacantEntry<'a, K, V, S, A>),
+}
+
+/// A view into an occupied entry in an `IndexMap`.
+/// It is part of the [`Entry`] enum.
+pub struct OccupiedEntry<'a, K, V, S = FxBuildHasher, A: Allocator = Global> {
+    map: &'a mut IndexMap<K, V, S, A>,
+    slot: usize,
+}
+
+/// A view into a vacant entry in an `IndexMap`.
+/// It is part of the [`Entry`] enum.
+pub struct VacantEntry<'a, K, V, S = FxBuildHasher, A: Allocator = Global> {
+    map: &'a mut IndexMap<K, V, S, A>,
+    hash: u64,
+    key: K,
+}
+
+impl<'a, K, V, S, A: Allocator> Entry<'a, K, V, S, A> {
+    /// Ensures a value is in the entry by inserting the default if empty, and
+    /// returns a mutable reference to the value in the entry.
+    #[inline]
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the default
+    /// function if empty, and returns a mutable reference to the value in the entry.
+    #[inline]
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the default
+    /// function called with the key if empty, and returns a mutable reference
+    /// to the value in the entry.
+    #[inline]
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the default value if empty,
+    /// and returns a mutable reference to the value in the entry.
+    #[inline]
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Returns a reference to this entry's key.
+    #[inline]
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Returns the index where the key-value pair exists or will be inserted.
+    #[inline]
+    pub fn index(&self) -> usize {
+        match self {
+            Entry::Occupied(entry) => entry.index(),
+            Entry::Vacant(entry) => entry.index(),
+        }
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any
+    /// potential inserts into the map.
+    #[inline]
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+impl<'a, K, V, S, A: Allocator> OccupiedEntry<'a, K, V, S, A> {
+    /// Gets a reference to the key in the entry.
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.map.entries[self.index()].key
+    }
+
+    /// Returns the index of the key-value pair.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.map.indices[self.slot]
+    }
+
+    /// Gets a reference to the value in the entry.
+    #[inline]
+    pub fn get(&self) -> &V {
+        &self.map.entries[self.index()].value
+    }
+
+    /// Gets a mutable reference to the value in the entry.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut V {
+        let index = self.index();
+        &mut self.map.entries[index].value
+    }
+
+    /// Converts the entry into a mutable reference to its value.
+    #[inline]
+    pub fn into_mut(self) -> &'a mut V {
+        let index = self.index();
+        &mut self.map.entries[index].value
+    }
+
+    /// Sets the value of the entry, and returns the entry's old value.
+    #[inline]
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the key-value pair by swapping it with the last pair, and
+    /// returns the value.
+    #[inline]
+    pub fn swap_remove(self) -> V {
+        self.swap_remove_entry().1
+    }
+
+    /// Removes the key-value pair by swapping it with the last pair, and
+    /// returns the pair.
+    #[inline]
+    pub fn swap_remove_entry(self) -> (K, V) {
+        let index = self.index();
+        let bucket = self.map.swap_remove_slot(self.slot, index);
+        (bucket.key, bucket.value)
+    }
+
+    /// Removes the key-value pair by shifting all the pairs that follow it,
+    /// and returns the value.
+    #[inline]
+    pub fn shift_remove(self) -> V {
+        self.shift_remove_entry().1
+    }
+
+    /// Removes the key-value pair by shifting all the pairs that follow it,
+    /// and returns the pair.
+    #[inline]
+    pub fn shift_remove_entry(self) -> (K, V) {
+        let index = self.index();
+        let bucket = self.map.shift_remove_slot(self.slot, index);
+        (bucket.key, bucket.value)
+    }
+}
+
+impl<'a, K, V, S, A: Allocator> VacantEntry<'a, K, V, S, A> {
+    /// Gets a reference to the key that would be used when inserting a value
+    /// through the `VacantEntry`.
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Takes ownership of the key.
+    #[inline]
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Returns the index where the key-value pair will be inserted.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Appends the key-value pair to the map, and returns a mutable reference
+    /// to the value.
+    ///
+    /// This never allocates, the capacity was reserved by [`IndexMap::try_entry`].
+    #[inline]
+    pub fn insert(self, value: V) -> &'a mut V {
+        let index = self.map.len();
+        let bucket = Bucket {
+            hash: self.hash,
+            key: self.key,
+            value,
+        };
+        if self.map.entries.push_within_capacity(bucket).is_err() {
+            unreachable!("entries are reserved");
+        }
+        self.map.insert_index(self.hash, index);
+        &mut self.map.entries[index].value
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, S, A: Allocator> fmt::Debug for Entry<'_, K, V, S, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Entry::Occupied(entry) => f.debug_tuple("Entry").field(entry).finish(),
+            Entry::Vacant(entry) => f.debug_tuple("Entry").field(entry).finish(),
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, S, A: Allocator> fmt::Debug for OccupiedEntry<'_, K, V, S, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedEntry")
+            .field("key", self.key())
+            .field("value", self.get())
+            .finish()
+    }
+}
+
+impl<K: fmt::Debug, V, S, A: Allocator> fmt::Debug for VacantEntry<'_, K, V, S, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VacantEntry").field(self.key()).finish()
+    }
+}
+
+/// An iterator over the entries of an `IndexMap`.
+///
+/// This `struct` is created by the [`iter`] method on [`IndexMap`].
+///
+/// [`iter`]: IndexMap::iter
+pub struct Iter<'a, K, V> {
+    iter: slice::Iter<'a, Bucket<K, V>>,
+}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Iter {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Iter<'_, K, V> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        self.iter.next().map(|b| (&b.key, &b.value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|b| (&b.key, &b.value))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+/// A mutable iterator over the entries of an `IndexMap`.
+///
+/// This `struct` is created by the [`iter_mut`] method on [`IndexMap`].
+///
+/// [`iter_mut`]: IndexMap::iter_mut
+pub struct IterMut<'a, K, V> {
+    iter: slice::IterMut<'a, Bucket<K, V>>,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(&'a K, &'a mut V)> {
+        self.iter.next().map(|b| (&b.key, &mut b.value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IterMut<'_, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|b| (&b.key, &mut b.value))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+
+impl<K, V> FusedIterator for IterMut<'_, K, V> {}
+
+/// An iterator over the keys of an `IndexMap`.
+///
+/// This `struct` is created by the [`keys`] method on [`IndexMap`].
+///
+/// [`keys`]: IndexMap::keys
+pub struct Keys<'a, K, V> {
+    iter: slice::Iter<'a, Bucket<K, V>>,
+}
+
+impl<K, V> Clone for Keys<'_, K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Keys {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<K: fmt::Debug, V> fmt::Debug for Keys<'_, K, V> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a K> {
+        self.iter.next().map(|b| &b.key)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Keys<'_, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|b| &b.key)
+    }
+}
+
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {}
+
+impl<K, V> FusedIterator for Keys<'_, K, V> {}
+
+/// An iterator over the values of an `IndexMap`.
+///
+/// This `struct` is created by the [`values`] method on [`IndexMap`].
+///
+/// [`values`]: IndexMap::values
+pub struct Values<'a, K, V> {
+    iter: slice::Iter<'a, Bucket<K, V>>,
+}
+
+impl<K, V> Clone for Values<'_, K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Values {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<K, V: fmt::Debug> fmt::Debug for Values<'_, K, V> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a V> {
+        self.iter.next().map(|b| &b.value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Values<'_, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|b| &b.value)
+    }
+}
+
+impl<K, V> ExactSizeIterator for Values<'_, K, V> {}
+
+impl<K, V> FusedIterator for Values<'_, K, V> {}
+
+/// A mutable iterator over the values of an `IndexMap`.
+///
+/// This `struct` is created by the [`values_mut`] method on [`IndexMap`].
+///
+/// [`values_mut`]: IndexMap::values_mut
+pub struct ValuesMut<'a, K, V> {
+    iter: slice::IterMut<'a, Bucket<K, V>>,
+}
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut V> {
+        self.iter.next().map(|b| &mut b.value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for ValuesMut<'_, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|b| &mut b.value)
+    }
+}
+
+impl<K, V> ExactSizeIterator for ValuesMut<'_, K, V> {}
+
+impl<K, V> FusedIterator for ValuesMut<'_, K, V> {}
+
+/// An owning iterator over the entries of an `IndexMap`.
+///
+/// This `struct` is created by the `into_iter` method on [`IndexMap`]
+/// (provided by the [`IntoIterator`] trait).
+pub struct IntoIter<K, V, A: Allocator = Global> {
+    iter: vec::IntoIter<Bucket<K, V>, A>,
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, A: Allocator> fmt::Debug for IntoIter<K, V, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries = self.iter.as_slice().iter().map(|b| (&b.key, &b.value));
+        f.debug_list().entries(entries).finish()
+    }
+}
+
+impl<K, V, A: Allocator> Iterator for IntoIter<K, V, A> {
+    type Item = (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(K, V)> {
+        self.iter.next().map(|b| (b.key, b.value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, V, A: Allocator> DoubleEndedIterator for IntoIter<K, V, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(K, V)> {
+        self.iter.next_back().map(|b| (b.key, b.value))
+    }
+}
+
+impl<K, V, A: Allocator> ExactSizeIterator for IntoIter<K, V, A> {}
+
+impl<K, V, A: Allocator> FusedIterator for IntoIter<K, V, A> {}
+
+impl<K, V, S, A: Allocator> IntoIterator for IndexMap<K, V, S, A> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V, A>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            iter: self.entries.into_iter(),
+        }
+    }
+}
+
+impl<'a, K, V, S, A: Allocator> IntoIterator for &'a IndexMap<K, V, S, A> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, S, A: Allocator> IntoIterator for &'a mut IndexMap<K, V, S, A> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<K, V, S: Default> Default for IndexMap<K, V, S> {
+    #[inline]
+    fn default() -> Self {
+        IndexMap::with_hasher(S::default())
+    }
+}
+
+impl<K, V, S, A: Allocator> Index<usize> for IndexMap<K, V, S, A> {
+    type Output = V;
+
+    /// Returns a reference to the value at the supplied index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    #[inline]
+    fn index(&self, index: usize) -> &V {
+        &self.entries[index].value
+    }
+}
+
+impl<K, V, S, A: Allocator> IndexMut<usize> for IndexMap<K, V, S, A> {
+    /// Returns a mutable reference to the value at the supplied index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut V {
+        &mut self.entries[index].value
+    }
+}
+
+impl<K, Q, V, S, A> Index<&Q> for IndexMap<K, V, S, A>
+where
+    K: Hash + Eq + Borrow<Q>,
+    Q: ?Sized + Hash + Eq,
+    S: BuildHasher,
+    A: Allocator,
+{
+    type Output = V;
+
+    /// Returns a reference to the value corresponding to the supplied key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not present in the `IndexMap`.
+    #[inline]
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, S, A: Allocator> fmt::Debug for IndexMap<K, V, S, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, S, A> PartialEq for IndexMap<K, V, S, A>
+where
+    K: Hash + Eq,
+    V: PartialEq,
+    S: BuildHasher,
+    A: Allocator,
+{
+    /// Compares the maps as maps, ignoring the order of their entries.
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
+
+impl<K, V, S, A> Eq for IndexMap<K, V, S, A>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+    A: Allocator,
+{
+}
+
+impl<K, V, S, A> TryClone for IndexMap<K, V, S, A>
+where
+    K: TryClone,
+    V: TryClone,
+    S: Clone,
+    A: Allocator + Clone,
+{
+    #[inline]
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        self.try_clone_with(V::try_clone)
+    }
+}
+
+impl<K: HeapSize, V: HeapSize, S, A: Allocator> HeapSize for IndexMap<K, V, S, A> {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        let entries: usize = self.iter().map(|(k, v)| k.heap_size() + v.heap_size()).sum();
+        self.entries.capacity() * mem::size_of::<Bucket<K, V>>()
+            + self.indices.capacity() * mem::size_of::<usize>()
+            + entries
+    }
+}
+
+impl<K, V, S> TryFromIterator<(K, V)> for IndexMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    type Error = AllocError;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Result<Self, Self::Error> {
+        let mut map = IndexMap::with_hasher(S::default());
+        map.try_extend(iter)?;
+        Ok(map)
+    }
+}
+
+impl<K, V, S, E> TryFromIterator<Result<(K, V), E>> for IndexMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+    E: From<AllocError>,
+{
+    type Error = E;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = Result<(K, V), E>>>(iter: I) -> Result<Self, Self::Error> {
+        iter::try_process(iter.into_iter(), |shunt| IndexMap::try_from_iter(shunt))
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use super::IndexMap;
+    use serde_crate::de::{Error, MapAccess, Visitor};
+    use serde_crate::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::fmt;
+    use std::hash::{BuildHasher, Hash};
+    use std::marker::PhantomData;
+
+    impl<K: Serialize, V: Serialize, S> Serialize for IndexMap<K, V, S> {
+        #[inline]
+        fn serialize<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+        where
+            T: Serializer,
+        {
+            serializer.collect_map(self)
+        }
+    }
+
+    impl<'de, K, V, S> Deserialize<'de> for IndexMap<K, V, S>
+    where
+        K: Deserialize<'de> + Hash + Eq,
+        V: Deserialize<'de>,
+        S: BuildHasher + Default,
+    {
+        #[inline]
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            struct MapVisitor<K, V, S> {
+                _marker: PhantomData<IndexMap<K, V, S>>,
+            }
+
+            impl<'de, K, V, S> Visitor<'de> for MapVisitor<K, V, S>
+            where
+                K: Deserialize<'de> + Hash + Eq,
+                V: Deserialize<'de>,
+                S: BuildHasher + Default,
+            {
+                type Value = IndexMap<K, V, S>;
+
+                #[inline]
+                fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.write_str("a map")
+                }
+
+                #[inline]
+                fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+                where
+                    A: MapAccess<'de>,
+                {
+                    let mut values = IndexMap::with_hasher(S::default());
+
+                    while let Some((key, value)) = map.next_entry()? {
+                        values.try_insert(key, value).map_err(A::Error::custom)?;
+                    }
+
+                    Ok(values)
+                }
+            }
+
+            let visitor = MapVisitor { _marker: PhantomData };
+            deserializer.deserialize_map(visitor)
+        }
+    }
+}
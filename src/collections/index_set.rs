@@ -0,0 +1,567 @@
+//! A hash set that preserves the insertion order of its elements.
+
+use crate::alloc::AllocError;
+use crate::clone::TryClone;
+use crate::collections::index_map::{self, IndexMap};
+use crate::iter::{self, TryFromIterator};
+use crate::mem::HeapSize;
+use fallacy_hash::FxBuildHasher;
+use std::alloc::{Allocator, Global};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::FusedIterator;
+use std::ops::Index;
+
+/// A hash set that preserves the insertion order of its elements.
+///
+/// It is implemented as an [`IndexMap`] with `()` values.
+pub struct IndexSet<T, S = FxBuildHasher, A: Allocator = Global> {
+    map: IndexMap<T, (), S, A>,
+}
+
+impl<T> IndexSet<T> {
+    /// Creates an empty `IndexSet`.
+    ///
+    /// The set is initially created with a capacity of 0, so it will not allocate
+    /// until it is first inserted into.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        IndexSet { map: IndexMap::new() }
+    }
+
+    /// Creates an empty `IndexSet` with at least the specified capacity.
+    #[inline]
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, AllocError> {
+        Ok(IndexSet {
+            map: IndexMap::try_with_capacity(capacity)?,
+        })
+    }
+}
+
+impl<T, S> IndexSet<T, S> {
+    /// Creates an empty `IndexSet` which will use the given hash builder to hash values.
+    #[inline]
+    pub const fn with_hasher(hash_builder: S) -> Self {
+        IndexSet {
+            map: IndexMap::with_hasher(hash_builder),
+        }
+    }
+}
+
+impl<T, S, A: Allocator + Clone> IndexSet<T, S, A> {
+    /// Creates an empty `IndexSet` which will use the given hash builder to hash values,
+    /// and the given allocator to allocate its elements.
+    #[inline]
+    pub fn with_hasher_in(hash_builder: S, alloc: A) -> Self {
+        IndexSet {
+            map: IndexMap::with_hasher_in(hash_builder, alloc),
+        }
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements to be
+    /// inserted in the set.
+    ///
+    /// If the allocation fails, the set is left unchanged.
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        self.map.try_reserve(additional)
+    }
+}
+
+impl<T, S, A: Allocator> IndexSet<T, S, A> {
+    /// Returns the number of elements the set can hold without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    /// Returns the number of elements in the set.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the set contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns a reference to the set's [`BuildHasher`].
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        self.map.hasher()
+    }
+
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        self.map.allocator()
+    }
+
+    /// Clears the set, removing all values.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Shortens the set, keeping the first `len` elements and dropping the rest.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        self.map.truncate(len);
+    }
+
+    /// An iterator visiting all elements in insertion order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self.map.keys())
+    }
+
+    /// Returns the value at the given index.
+    #[inline]
+    pub fn get_index(&self, index: usize) -> Option<&T> {
+        self.map.get_index(index).map(|(k, _)| k)
+    }
+
+    /// Returns the first value.
+    #[inline]
+    pub fn first(&self) -> Option<&T> {
+        self.map.first().map(|(k, _)| k)
+    }
+
+    /// Returns the last value.
+    #[inline]
+    pub fn last(&self) -> Option<&T> {
+        self.map.last().map(|(k, _)| k)
+    }
+
+    /// Removes the last value and returns it.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.map.pop().map(|(k, _)| k)
+    }
+
+    /// Removes the value at the given index, replacing it with the last value,
+    /// and returns it.
+    #[inline]
+    pub fn swap_remove_index(&mut self, index: usize) -> Option<T> {
+        self.map.swap_remove_index(index).map(|(k, _)| k)
+    }
+
+    /// Removes the value at the given index, shifting all the values that
+    /// follow it, and returns it.
+    #[inline]
+    pub fn shift_remove_index(&mut self, index: usize) -> Option<T> {
+        self.map.shift_remove_index(index).map(|(k, _)| k)
+    }
+
+    /// Retains only the elements specified by the predicate, in order.
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.map.retain(|k, _| f(k));
+    }
+
+    /// Sorts the set's values.
+    ///
+    /// If the allocation fails, the set is left unchanged.
+    #[inline]
+    pub fn try_sort(&mut self) -> Result<(), AllocError>
+    where
+        T: Ord,
+    {
+        self.map.try_sort_keys()
+    }
+
+    /// Sorts the set's values with a comparison function.
+    ///
+    /// If the allocation fails, the set is left unchanged.
+    #[inline]
+    pub fn try_sort_by<F>(&mut self, mut cmp: F) -> Result<(), AllocError>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.map.try_sort_by(|k1, _, k2, _| cmp(k1, k2))
+    }
+
+    /// Reverses the order of the set's values in place.
+    #[inline]
+    pub fn reverse(&mut self) {
+        self.map.reverse();
+    }
+}
+
+impl<T, S, A> IndexSet<T, S, A>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+    A: Allocator,
+{
+    /// Returns `true` if the set contains a value.
+    #[inline]
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.contains_key(value)
+    }
+
+    /// Returns a reference to the value in the set, if any, that is equal to the given value.
+    #[inline]
+    pub fn get<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.get_key_value(value).map(|(k, _)| k)
+    }
+
+    /// Returns the index of the value, if it is present in the set.
+    #[inline]
+    pub fn get_index_of<Q>(&self, value: &Q) -> Option<usize>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.get_index_of(value)
+    }
+
+    /// Removes a value from the set by swapping it with the last value.
+    /// Returns whether the value was present in the set.
+    #[inline]
+    pub fn swap_remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.swap_remove(value).is_some()
+    }
+
+    /// Removes a value from the set by shifting all the values that follow it.
+    /// Returns whether the value was present in the set.
+    #[inline]
+    pub fn shift_remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.shift_remove(value).is_some()
+    }
+}
+
+impl<T, S, A> IndexSet<T, S, A>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+    A: Allocator + Clone,
+{
+    /// Adds a value to the end of the set.
+    ///
+    /// Returns whether the value was newly inserted. If the set already
+    /// contained an equal value, the set is not changed.
+    ///
+    /// If the allocation fails, the set is left unchanged.
+    #[inline]
+    pub fn try_insert(&mut self, value: T) -> Result<bool, AllocError> {
+        Ok(self.try_insert_full(value)?.1)
+    }
+
+    /// Adds a value to the end of the set, and returns its index together
+    /// with whether it was newly inserted.
+    ///
+    /// If the allocation fails, the set is left unchanged.
+    #[inline]
+    pub fn try_insert_full(&mut self, value: T) -> Result<(usize, bool), AllocError> {
+        match self.map.try_entry(value)? {
+            index_map::Entry::Occupied(entry) => Ok((entry.index(), false)),
+            index_map::Entry::Vacant(entry) => {
+                let index = entry.index();
+                entry.insert(());
+                Ok((index, true))
+            }
+        }
+    }
+
+    /// Adds all the values of an iterator to the set.
+    ///
+    /// If the allocation fails, the values inserted so far stay in the set.
+    #[inline]
+    pub fn try_extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), AllocError> {
+        self.map.try_extend(iter.into_iter().map(|value| (value, ())))
+    }
+}
+
+/// An iterator over the items of an `IndexSet`.
+///
+/// This `struct` is created by the [`iter`] method on [`IndexSet`].
+///
+/// [`iter`]: IndexSet::iter
+pub struct Iter<'a, T>(index_map::Keys<'a, T, ()>);
+
+impl<T> Clone for Iter<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Iter(self.0.clone())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Iter<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        self.0.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// An owning iterator over the items of an `IndexSet`.
+///
+/// This `struct` is created by the `into_iter` method on [`IndexSet`]
+/// (provided by the [`IntoIterator`] trait).
+pub struct IntoIter<T, A: Allocator = Global>(index_map::IntoIter<T, (), A>);
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for IntoIter<T, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.0).finish()
+    }
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.0.next().map(|(k, _)| k)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.0.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
+
+impl<T, S, A: Allocator> IntoIterator for IndexSet<T, S, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self.map.into_iter())
+    }
+}
+
+impl<'a, T, S, A: Allocator> IntoIterator for &'a IndexSet<T, S, A> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T, S: Default> Default for IndexSet<T, S> {
+    #[inline]
+    fn default() -> Self {
+        IndexSet::with_hasher(S::default())
+    }
+}
+
+impl<T, S, A: Allocator> Index<usize> for IndexSet<T, S, A> {
+    type Output = T;
+
+    /// Returns a reference to the value at the supplied index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    #[inline]
+    fn index(&self, index: usize) -> &T {
+        self.get_index(index).expect("index out of bounds")
+    }
+}
+
+impl<T: fmt::Debug, S, A: Allocator> fmt::Debug for IndexSet<T, S, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T, S, A> PartialEq for IndexSet<T, S, A>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+    A: Allocator,
+{
+    /// Compares the sets as sets, ignoring the order of their values.
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<T, S, A> Eq for IndexSet<T, S, A>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+    A: Allocator,
+{
+}
+
+impl<T, S, A> TryClone for IndexSet<T, S, A>
+where
+    T: TryClone,
+    S: Clone,
+    A: Allocator + Clone,
+{
+    #[inline]
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        Ok(IndexSet {
+            map: self.map.try_clone_with(|_| Ok(()))?,
+        })
+    }
+}
+
+impl<T: HeapSize, S, A: Allocator> HeapSize for IndexSet<T, S, A> {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.map.heap_size()
+    }
+}
+
+impl<T, S> TryFromIterator<T> for IndexSet<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    type Error = AllocError;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, Self::Error> {
+        let mut set = IndexSet::with_hasher(S::default());
+        set.try_extend(iter)?;
+        Ok(set)
+    }
+}
+
+impl<T, S, E> TryFromIterator<Result<T, E>> for IndexSet<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher + Default,
+    E: From<AllocError>,
+{
+    type Error = E;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = Result<T, E>>>(iter: I) -> Result<Self, Self::Error> {
+        iter::try_process(iter.into_iter(), |shunt| IndexSet::try_from_iter(shunt))
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use super::IndexSet;
+    use serde_crate::de::{Error, SeqAccess, Visitor};
+    use serde_crate::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::fmt;
+    use std::hash::{BuildHasher, Hash};
+    use std::marker::PhantomData;
+
+    impl<T: Serialize, S> Serialize for IndexSet<T, S> {
+        #[inline]
+        fn serialize<Se>(&self, serializer: Se) -> Result<Se::Ok, Se::Error>
+        where
+            Se: Serializer,
+        {
+            serializer.collect_seq(self)
+        }
+    }
+
+    impl<'de, T, S> Deserialize<'de> for IndexSet<T, S>
+    where
+        T: Deserialize<'de> + Hash + Eq,
+        S: BuildHasher + Default,
+    {
+        #[inline]
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            struct SeqVisitor<T, S> {
+                _marker: PhantomData<IndexSet<T, S>>,
+            }
+
+            impl<'de, T, S> Visitor<'de> for SeqVisitor<T, S>
+            where
+                T: Deserialize<'de> + Hash + Eq,
+                S: BuildHasher + Default,
+            {
+                type Value = IndexSet<T, S>;
+
+                #[inline]
+                fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.write_str("a sequence")
+                }
+
+                #[inline]
+                fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                where
+                    A: SeqAccess<'de>,
+                {
+                    let mut values = IndexSet::with_hasher(S::default());
+
+                    while let Some(value) = seq.next_element()? {
+                        values.try_insert(value).map_err(A::Error::custom)?;
+                    }
+
+                    Ok(values)
+                }
+            }
+
+            let visitor = SeqVisitor { _marker: PhantomData };
+            deserializer.deserialize_seq(visitor)
+        }
+    }
+}
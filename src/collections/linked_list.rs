@@ -0,0 +1,1364 @@
+//! A doubly-linked list with owned nodes.
+//!
+//! Every node is allocated separately from the list's allocator, so the list
+//! can be backed by a pool allocator. Only the operations that create nodes
+//! can fail; moving nodes between lists, with [`LinkedList::append`],
+//! [`LinkedList::split_off`] or the splice and split methods of
+//! [`CursorMut`], never allocates.
+
+use crate::alloc::{AllocError, InterchangeableAllocator};
+use crate::boxed::Box;
+use crate::clone::TryClone;
+use crate::iter::{self, TryFromIterator};
+use crate::mem::HeapSize;
+use std::alloc::{Allocator, Global};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr::NonNull;
+
+/// A doubly-linked list with owned nodes.
+///
+/// Nodes moved between lists keep the allocator they were allocated from.
+/// The split operations give the new list a clone of the allocator, while
+/// moving the nodes of another list in, by [`append`] or the splice
+/// operations of [`CursorMut`], requires an [`InterchangeableAllocator`].
+/// Other allocators can use the `_unchecked` variants of those operations.
+///
+/// [`append`]: LinkedList::append
+pub struct LinkedList<T, A: Allocator = Global> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    alloc: A,
+    marker: PhantomData<Box<Node<T>, A>>,
+}
+
+type Link<T> = Option<NonNull<Node<T>>>;
+
+struct Node<T> {
+    next: Link<T>,
+    prev: Link<T>,
+    element: T,
+}
+
+unsafe impl<T: Send, A: Allocator + Send> Send for LinkedList<T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for LinkedList<T, A> {}
+
+impl<T> LinkedList<T> {
+    /// Creates an empty `LinkedList`.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        LinkedList::new_in(Global)
+    }
+}
+
+impl<T, A: Allocator> LinkedList<T, A> {
+    /// Constructs an empty `LinkedList<T, A>`.
+    #[inline]
+    pub const fn new_in(alloc: A) -> Self {
+        LinkedList {
+            head: None,
+            tail: None,
+            len: 0,
+            alloc,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Returns the length of the `LinkedList`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the `LinkedList` is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    /// Removes all elements from the `LinkedList`.
+    #[inline]
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    /// Provides a reference to the front element, or `None` if the list is empty.
+    #[inline]
+    pub fn front(&self) -> Option<&T> {
+        unsafe { self.head.map(|node| &(*node.as_ptr()).element) }
+    }
+
+    /// Provides a mutable reference to the front element, or `None` if the list is empty.
+    #[inline]
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.head.map(|node| &mut (*node.as_ptr()).element) }
+    }
+
+    /// Provides a reference to the back element, or `None` if the list is empty.
+    #[inline]
+    pub fn back(&self) -> Option<&T> {
+        unsafe { self.tail.map(|node| &(*node.as_ptr()).element) }
+    }
+
+    /// Provides a mutable reference to the back element, or `None` if the list is empty.
+    #[inline]
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.tail.map(|node| &mut (*node.as_ptr()).element) }
+    }
+
+    /// Adds an element first in the list.
+    #[inline]
+    pub fn try_push_front(&mut self, elt: T) -> Result<(), AllocError> {
+        let node = self.try_new_node(elt)?;
+        unsafe { self.push_front_node(node) };
+        Ok(())
+    }
+
+    /// Appends an element to the back of the list.
+    #[inline]
+    pub fn try_push_back(&mut self, elt: T) -> Result<(), AllocError> {
+        let node = self.try_new_node(elt)?;
+        unsafe { self.push_back_node(node) };
+        Ok(())
+    }
+
+    /// Removes the first element and returns it, or `None` if the list is empty.
+    #[inline]
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.pop_front_node().map(|node| unsafe { self.free_node(node) })
+    }
+
+    /// Removes the last element and returns it, or `None` if the list is empty.
+    #[inline]
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.pop_back_node().map(|node| unsafe { self.free_node(node) })
+    }
+
+    /// Appends the elements of an iterator to the back of the list.
+    ///
+    /// If the allocation fails, the elements pushed so far stay in the list.
+    #[inline]
+    pub fn try_extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), AllocError> {
+        for elt in iter {
+            self.try_push_back(elt)?;
+        }
+        Ok(())
+    }
+
+    /// Moves all elements from `other` to the end of the list.
+    ///
+    /// This reuses all the nodes from `other` and moves them into `self`,
+    /// so it never allocates. After this operation, `other` becomes empty.
+    ///
+    /// # Safety
+    ///
+    /// The allocator of `self` must be able to deallocate the memory allocated
+    /// by the allocator of `other`, e.g. because one is a clone of the other.
+    #[inline]
+    pub unsafe fn append_unchecked(&mut self, other: &mut Self) {
+        match self.tail {
+            None => mem::swap(&mut self.head, &mut other.head),
+            Some(tail) => {
+                if let Some(other_head) = other.head.take() {
+                    (*tail.as_ptr()).next = Some(other_head);
+                    (*other_head.as_ptr()).prev = Some(tail);
+                } else {
+                    return;
+                }
+            }
+        }
+        self.tail = other.tail.take();
+        self.len += mem::replace(&mut other.len, 0);
+    }
+
+    /// Returns `true` if the `LinkedList` contains an element equal to the given value.
+    #[inline]
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|e| e == x)
+    }
+
+    /// Retains only the elements specified by the predicate.
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let mut cursor = self.cursor_front_mut();
+        while let Some(elt) = cursor.current() {
+            if f(elt) {
+                cursor.move_next();
+            } else {
+                cursor.remove_current();
+            }
+        }
+    }
+
+    /// Provides a forward iterator.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Provides a forward iterator with mutable references.
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Provides a cursor at the front element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    #[inline]
+    pub fn cursor_front(&self) -> Cursor<'_, T, A> {
+        Cursor {
+            index: 0,
+            current: self.head,
+            list: self,
+        }
+    }
+
+    /// Provides a cursor with editing operations at the front element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    #[inline]
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T, A> {
+        CursorMut {
+            index: 0,
+            current: self.head,
+            list: self,
+        }
+    }
+
+    /// Provides a cursor at the back element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    #[inline]
+    pub fn cursor_back(&self) -> Cursor<'_, T, A> {
+        Cursor {
+            index: self.len.saturating_sub(1),
+            current: self.tail,
+            list: self,
+        }
+    }
+
+    /// Provides a cursor with editing operations at the back element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    #[inline]
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T, A> {
+        CursorMut {
+            index: self.len.saturating_sub(1),
+            current: self.tail,
+            list: self,
+        }
+    }
+
+    #[inline]
+    fn try_new_node(&self, element: T) -> Result<NonNull<Node<T>>, AllocError> {
+        let node = Node {
+            next: None,
+            prev: None,
+            element,
+        };
+        let node = Box::try_new_in(node, &self.alloc)?;
+        Ok(unsafe { NonNull::new_unchecked(Box::into_raw(node)) })
+    }
+
+    /// Frees an unlinked node, and returns its element.
+    ///
+    /// # Safety
+    ///
+    /// `node` must have been allocated by a compatible allocator, and must not
+    /// be linked into any list.
+    #[inline]
+    unsafe fn free_node(&self, node: NonNull<Node<T>>) -> T {
+        Box::from_raw_in(node.as_ptr(), &self.alloc).into_std().element
+    }
+
+    /// Adds the given node to the front of the list.
+    ///
+    /// # Safety
+    ///
+    /// `node` must not be linked into any list.
+    #[inline]
+    unsafe fn push_front_node(&mut self, node: NonNull<Node<T>>) {
+        (*node.as_ptr()).next = self.head;
+        (*node.as_ptr()).prev = None;
+        match self.head {
+            None => self.tail = Some(node),
+            Some(head) => (*head.as_ptr()).prev = Some(node),
+        }
+        self.head = Some(node);
+        self.len += 1;
+    }
+
+    /// Adds the given node to the back of the list.
+    ///
+    /// # Safety
+    ///
+    /// `node` must not be linked into any list.
+    #[inline]
+    unsafe fn push_back_node(&mut self, node: NonNull<Node<T>>) {
+        (*node.as_ptr()).next = None;
+        (*node.as_ptr()).prev = self.tail;
+        match self.tail {
+            None => self.head = Some(node),
+            Some(tail) => (*tail.as_ptr()).next = Some(node),
+        }
+        self.tail = Some(node);
+        self.len += 1;
+    }
+
+    /// Removes and returns the node at the front of the list.
+    #[inline]
+    fn pop_front_node(&mut self) -> Link<T> {
+        self.head.inspect(|node| unsafe {
+            self.head = (*node.as_ptr()).next;
+            match self.head {
+                None => self.tail = None,
+                Some(head) => (*head.as_ptr()).prev = None,
+            }
+            self.len -= 1;
+        })
+    }
+
+    /// Removes and returns the node at the back of the list.
+    #[inline]
+    fn pop_back_node(&mut self) -> Link<T> {
+        self.tail.inspect(|node| unsafe {
+            self.tail = (*node.as_ptr()).prev;
+            match self.tail {
+                None => self.head = None,
+                Some(tail) => (*tail.as_ptr()).next = None,
+            }
+            self.len -= 1;
+        })
+    }
+
+    /// Unlinks the specified node from the list.
+    ///
+    /// # Safety
+    ///
+    /// `node` must be linked into this list.
+    #[inline]
+    unsafe fn unlink_node(&mut self, node: NonNull<Node<T>>) {
+        let node = &mut *node.as_ptr();
+        match node.prev {
+            Some(prev) => (*prev.as_ptr()).next = node.next,
+            None => self.head = node.next,
+        }
+        match node.next {
+            Some(next) => (*next.as_ptr()).prev = node.prev,
+            None => self.tail = node.prev,
+        }
+        self.len -= 1;
+    }
+
+    /// Splices a chain of nodes between two adjacent nodes of the list.
+    ///
+    /// # Safety
+    ///
+    /// `existing_prev` and `existing_next` must be adjacent nodes of this
+    /// list, or the ends of it. The chain must not be linked into any list.
+    #[inline]
+    unsafe fn splice_nodes(
+        &mut self,
+        existing_prev: Link<T>,
+        existing_next: Link<T>,
+        splice_start: NonNull<Node<T>>,
+        splice_end: NonNull<Node<T>>,
+        splice_len: usize,
+    ) {
+        match existing_prev {
+            Some(prev) => (*prev.as_ptr()).next = Some(splice_start),
+            None => self.head = Some(splice_start),
+        }
+        match existing_next {
+            Some(next) => (*next.as_ptr()).prev = Some(splice_end),
+            None => self.tail = Some(splice_end),
+        }
+        (*splice_start.as_ptr()).prev = existing_prev;
+        (*splice_end.as_ptr()).next = existing_next;
+        self.len += splice_len;
+    }
+
+    /// Moves all the nodes of `list` between two adjacent nodes of this list.
+    ///
+    /// # Safety
+    ///
+    /// `existing_prev` and `existing_next` must be adjacent nodes of this
+    /// list, or the ends of it.
+    #[inline]
+    unsafe fn splice_list(&mut self, existing_prev: Link<T>, existing_next: Link<T>, mut list: Self) {
+        if let (Some(head), Some(tail)) = (list.head.take(), list.tail.take()) {
+            let len = mem::replace(&mut list.len, 0);
+            self.splice_nodes(existing_prev, existing_next, head, tail, len);
+        }
+    }
+}
+
+impl<T, A: InterchangeableAllocator> LinkedList<T, A> {
+    /// Moves all elements from `other` to the end of the list.
+    ///
+    /// This reuses all the nodes from `other` and moves them into `self`,
+    /// so it never allocates. After this operation, `other` becomes empty.
+    #[inline]
+    pub fn append(&mut self, other: &mut Self) {
+        // SAFETY: the allocators are interchangeable.
+        unsafe { self.append_unchecked(other) }
+    }
+}
+
+impl<T, A: Allocator + Clone> LinkedList<T, A> {
+    /// Splits the list into two at the given index. Returns everything after
+    /// the given index, including the index.
+    ///
+    /// This never allocates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    #[inline]
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let len = self.len;
+        assert!(at <= len, "Cannot split off at a nonexistent index");
+        if at == 0 {
+            let alloc = self.alloc.clone();
+            return mem::replace(self, LinkedList::new_in(alloc));
+        }
+        if at == len {
+            return LinkedList::new_in(self.alloc.clone());
+        }
+
+        // Walk from the nearer end to the last node of the first part.
+        let split_node = if at - 1 <= len - at {
+            let mut iter = self.iter_mut();
+            for _ in 0..at - 1 {
+                iter.next();
+            }
+            iter.head
+        } else {
+            let mut iter = self.iter_mut();
+            for _ in 0..len - at {
+                iter.next_back();
+            }
+            iter.tail
+        };
+        unsafe { self.split_off_after_node(split_node, at) }
+    }
+
+    /// Splits the list before `split_node`, and returns the first part, which
+    /// has `at` elements.
+    ///
+    /// # Safety
+    ///
+    /// `split_node` must be the node at index `at` of this list, or `None`.
+    #[inline]
+    unsafe fn split_off_before_node(&mut self, split_node: Link<T>, at: usize) -> Self {
+        let Some(split_node) = split_node else {
+            let alloc = self.alloc.clone();
+            return mem::replace(self, LinkedList::new_in(alloc));
+        };
+        let first_tail = (*split_node.as_ptr()).prev.take();
+        let first_head = match first_tail {
+            Some(tail) => {
+                (*tail.as_ptr()).next = None;
+                self.head
+            }
+            None => None,
+        };
+        self.head = Some(split_node);
+        self.len -= at;
+        LinkedList {
+            head: first_head,
+            tail: first_tail,
+            len: at,
+            alloc: self.alloc.clone(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Splits the list after `split_node`, and returns the second part. The
+    /// first part keeps `at` elements.
+    ///
+    /// # Safety
+    ///
+    /// `split_node` must be the node at index `at - 1` of this list, or `None`.
+    #[inline]
+    unsafe fn split_off_after_node(&mut self, split_node: Link<T>, at: usize) -> Self {
+        let Some(split_node) = split_node else {
+            let alloc = self.alloc.clone();
+            return mem::replace(self, LinkedList::new_in(alloc));
+        };
+        let second_head = (*split_node.as_ptr()).next.take();
+        let second_tail = match second_head {
+            Some(head) => {
+                (*head.as_ptr()).prev = None;
+                self.tail
+            }
+            None => None,
+        };
+        let second = LinkedList {
+            head: second_head,
+            tail: second_tail,
+            len: self.len - at,
+            alloc: self.alloc.clone(),
+            marker: PhantomData,
+        };
+        self.tail = Some(split_node);
+        self.len = at;
+        second
+    }
+}
+
+impl<T, A: Allocator> Drop for LinkedList<T, A> {
+    #[inline]
+    fn drop(&mut self) {
+        struct DropGuard<'a, T, A: Allocator>(&'a mut LinkedList<T, A>);
+
+        impl<T, A: Allocator> Drop for DropGuard<'_, T, A> {
+            #[inline]
+            fn drop(&mut self) {
+                // Continue the same loop we do below. This only runs when a destructor has
+                // panicked. If another one panics this will abort.
+                while self.0.pop_front().is_some() {}
+            }
+        }
+
+        let guard = DropGuard(self);
+        while guard.0.pop_front().is_some() {}
+        mem::forget(guard);
+    }
+}
+
+/// A cursor over a `LinkedList`.
+///
+/// Cursors always rest between two elements in the list, and index in a
+/// logically circular way. To accommodate this, there is a "ghost"
+/// non-element that yields `None` between the tail and head of the list.
+pub struct Cursor<'a, T, A: Allocator = Global> {
+    index: usize,
+    current: Link<T>,
+    list: &'a LinkedList<T, A>,
+}
+
+impl<T, A: Allocator> Clone for Cursor<'_, T, A> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Cursor {
+            index: self.index,
+            current: self.current,
+            list: self.list,
+        }
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for Cursor<'_, T, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Cursor").field(&self.list).field(&self.index()).finish()
+    }
+}
+
+impl<'a, T, A: Allocator> Cursor<'a, T, A> {
+    /// Returns the cursor position index within the `LinkedList`.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[inline]
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    /// Moves the cursor to the next element of the `LinkedList`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the first element of the `LinkedList`. If it is pointing to the last
+    /// element of the `LinkedList` then this will move it to the "ghost" non-element.
+    #[inline]
+    pub fn move_next(&mut self) {
+        match self.current {
+            Some(current) => unsafe {
+                self.current = (*current.as_ptr()).next;
+                self.index += 1;
+            },
+            None => {
+                self.current = self.list.head;
+                self.index = 0;
+            }
+        }
+    }
+
+    /// Moves the cursor to the previous element of the `LinkedList`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the last element of the `LinkedList`. If it is pointing to the first
+    /// element of the `LinkedList` then this will move it to the "ghost" non-element.
+    #[inline]
+    pub fn move_prev(&mut self) {
+        match self.current {
+            Some(current) => unsafe {
+                self.current = (*current.as_ptr()).prev;
+                self.index = self.index.checked_sub(1).unwrap_or(self.list.len);
+            },
+            None => {
+                self.current = self.list.tail;
+                self.index = self.list.len.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Returns a reference to the element that the cursor is currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[inline]
+    pub fn current(&self) -> Option<&'a T> {
+        unsafe { self.current.map(|current| &(*current.as_ptr()).element) }
+    }
+
+    /// Returns a reference to the next element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the first element of the `LinkedList`. If it is pointing to the last
+    /// element of the `LinkedList` then this returns `None`.
+    #[inline]
+    pub fn peek_next(&self) -> Option<&'a T> {
+        unsafe {
+            let next = match self.current {
+                None => self.list.head,
+                Some(current) => (*current.as_ptr()).next,
+            };
+            next.map(|next| &(*next.as_ptr()).element)
+        }
+    }
+
+    /// Returns a reference to the previous element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the last element of the `LinkedList`. If it is pointing to the first
+    /// element of the `LinkedList` then this returns `None`.
+    #[inline]
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        unsafe {
+            let prev = match self.current {
+                None => self.list.tail,
+                Some(current) => (*current.as_ptr()).prev,
+            };
+            prev.map(|prev| &(*prev.as_ptr()).element)
+        }
+    }
+
+    /// Provides a reference to the front element of the cursor's parent list,
+    /// or `None` if the list is empty.
+    #[inline]
+    pub fn front(&self) -> Option<&'a T> {
+        self.list.front()
+    }
+
+    /// Provides a reference to the back element of the cursor's parent list,
+    /// or `None` if the list is empty.
+    #[inline]
+    pub fn back(&self) -> Option<&'a T> {
+        self.list.back()
+    }
+}
+
+/// A cursor over a `LinkedList` with editing operations.
+///
+/// A `Cursor` is like an iterator, except that it can freely seek back-and-forth,
+/// and can safely mutate the list during iteration. Only the operations that
+/// create nodes can fail.
+pub struct CursorMut<'a, T, A: Allocator = Global> {
+    index: usize,
+    current: Link<T>,
+    list: &'a mut LinkedList<T, A>,
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for CursorMut<'_, T, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CursorMut")
+            .field(&self.list)
+            .field(&self.index())
+            .finish()
+    }
+}
+
+impl<'a, T, A: Allocator> CursorMut<'a, T, A> {
+    /// Returns the cursor position index within the `LinkedList`.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[inline]
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    /// Moves the cursor to the next element of the `LinkedList`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the first element of the `LinkedList`. If it is pointing to the last
+    /// element of the `LinkedList` then this will move it to the "ghost" non-element.
+    #[inline]
+    pub fn move_next(&mut self) {
+        match self.current {
+            Some(current) => unsafe {
+                self.current = (*current.as_ptr()).next;
+                self.index += 1;
+            },
+            None => {
+                self.current = self.list.head;
+                self.index = 0;
+            }
+        }
+    }
+
+    /// Moves the cursor to the previous element of the `LinkedList`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the last element of the `LinkedList`. If it is pointing to the first
+    /// element of the `LinkedList` then this will move it to the "ghost" non-element.
+    #[inline]
+    pub fn move_prev(&mut self) {
+        match self.current {
+            Some(current) => unsafe {
+                self.current = (*current.as_ptr()).prev;
+                self.index = self.index.checked_sub(1).unwrap_or(self.list.len);
+            },
+            None => {
+                self.current = self.list.tail;
+                self.index = self.list.len.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Returns a mutable reference to the element that the cursor is currently
+    /// pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[inline]
+    pub fn current(&mut self) -> Option<&mut T> {
+        unsafe { self.current.map(|current| &mut (*current.as_ptr()).element) }
+    }
+
+    /// Returns a mutable reference to the next element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the first element of the `LinkedList`. If it is pointing to the last
+    /// element of the `LinkedList` then this returns `None`.
+    #[inline]
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        unsafe {
+            let next = match self.current {
+                None => self.list.head,
+                Some(current) => (*current.as_ptr()).next,
+            };
+            next.map(|next| &mut (*next.as_ptr()).element)
+        }
+    }
+
+    /// Returns a mutable reference to the previous element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the last element of the `LinkedList`. If it is pointing to the first
+    /// element of the `LinkedList` then this returns `None`.
+    #[inline]
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        unsafe {
+            let prev = match self.current {
+                None => self.list.tail,
+                Some(current) => (*current.as_ptr()).prev,
+            };
+            prev.map(|prev| &mut (*prev.as_ptr()).element)
+        }
+    }
+
+    /// Returns a read-only cursor pointing to the current element.
+    ///
+    /// The lifetime of the returned `Cursor` is bound to that of the
+    /// `CursorMut`, which means it cannot outlive the `CursorMut` and that the
+    /// `CursorMut` is frozen for the lifetime of the `Cursor`.
+    #[inline]
+    pub fn as_cursor(&self) -> Cursor<'_, T, A> {
+        Cursor {
+            index: self.index,
+            current: self.current,
+            list: self.list,
+        }
+    }
+
+    /// Inserts a new element into the `LinkedList` after the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new element is
+    /// inserted at the front of the `LinkedList`.
+    #[inline]
+    pub fn try_insert_after(&mut self, item: T) -> Result<(), AllocError> {
+        let node = self.list.try_new_node(item)?;
+        unsafe {
+            let next = match self.current {
+                None => self.list.head,
+                Some(current) => (*current.as_ptr()).next,
+            };
+            self.list.splice_nodes(self.current, next, node, node, 1);
+        }
+        if self.current.is_none() {
+            // The "ghost" non-element's index has changed.
+            self.index = self.list.len;
+        }
+        Ok(())
+    }
+
+    /// Inserts a new element into the `LinkedList` before the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new element is
+    /// inserted at the end of the `LinkedList`.
+    #[inline]
+    pub fn try_insert_before(&mut self, item: T) -> Result<(), AllocError> {
+        let node = self.list.try_new_node(item)?;
+        unsafe {
+            let prev = match self.current {
+                None => self.list.tail,
+                Some(current) => (*current.as_ptr()).prev,
+            };
+            self.list.splice_nodes(prev, self.current, node, node, 1);
+        }
+        self.index += 1;
+        Ok(())
+    }
+
+    /// Removes the current element from the `LinkedList`.
+    ///
+    /// The element that was removed is returned, and the cursor is
+    /// moved to point to the next element in the `LinkedList`.
+    ///
+    /// If the cursor is currently pointing to the "ghost" non-element then no element
+    /// is removed and `None` is returned.
+    #[inline]
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.current?;
+        unsafe {
+            self.current = (*node.as_ptr()).next;
+            self.list.unlink_node(node);
+            Some(self.list.free_node(node))
+        }
+    }
+
+    /// Inserts the elements from the given `LinkedList` after the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new elements are
+    /// inserted at the start of the `LinkedList`. This never allocates.
+    ///
+    /// # Safety
+    ///
+    /// The allocator of the cursor's list must be able to deallocate the memory
+    /// allocated by the allocator of `list`, e.g. because one is a clone of the other.
+    #[inline]
+    pub unsafe fn splice_after_unchecked(&mut self, list: LinkedList<T, A>) {
+        let next = match self.current {
+            None => self.list.head,
+            Some(current) => (*current.as_ptr()).next,
+        };
+        self.list.splice_list(self.current, next, list);
+        if self.current.is_none() {
+            // The "ghost" non-element's index has changed.
+            self.index = self.list.len;
+        }
+    }
+
+    /// Inserts the elements from the given `LinkedList` before the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new elements are
+    /// inserted at the end of the `LinkedList`. This never allocates.
+    ///
+    /// # Safety
+    ///
+    /// The allocator of the cursor's list must be able to deallocate the memory
+    /// allocated by the allocator of `list`, e.g. because one is a clone of the other.
+    #[inline]
+    pub unsafe fn splice_before_unchecked(&mut self, list: LinkedList<T, A>) {
+        let len = list.len;
+        let prev = match self.current {
+            None => self.list.tail,
+            Some(current) => (*current.as_ptr()).prev,
+        };
+        self.list.splice_list(prev, self.current, list);
+        self.index += len;
+    }
+
+    /// Appends an element to the front of the cursor's parent list. The node
+    /// that the cursor points to is unchanged, even if it is the "ghost" node.
+    #[inline]
+    pub fn try_push_front(&mut self, elt: T) -> Result<(), AllocError> {
+        self.list.try_push_front(elt)?;
+        self.index += 1;
+        Ok(())
+    }
+
+    /// Appends an element to the back of the cursor's parent list. The node
+    /// that the cursor points to is unchanged, even if it is the "ghost" node.
+    #[inline]
+    pub fn try_push_back(&mut self, elt: T) -> Result<(), AllocError> {
+        self.list.try_push_back(elt)?;
+        if self.current.is_none() {
+            // The "ghost" non-element's index has changed.
+            self.index += 1;
+        }
+        Ok(())
+    }
+
+    /// Removes the first element from the cursor's parent list and returns it,
+    /// or `None` if the list is empty. The element the cursor points to remains
+    /// unchanged, unless it was pointing to the front element. In that case, it
+    /// points to the new front element.
+    #[inline]
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.list.is_empty() {
+            return None;
+        }
+        if self.list.head == self.current {
+            self.move_next();
+        }
+        // An element was removed before or at the current position.
+        self.index -= 1;
+        self.list.pop_front()
+    }
+
+    /// Removes the last element from the cursor's parent list and returns it,
+    /// or `None` if the list is empty. The element the cursor points to remains
+    /// unchanged, unless it was pointing to the back element. In that case, it
+    /// points to the "ghost" element.
+    #[inline]
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.list.is_empty() {
+            return None;
+        }
+        if self.list.tail == self.current {
+            // The index now points to the "ghost" element.
+            self.current = None;
+        } else if self.current.is_none() {
+            self.index = self.list.len - 1;
+        }
+        self.list.pop_back()
+    }
+
+    /// Provides a reference to the front element of the cursor's parent list,
+    /// or `None` if the list is empty.
+    #[inline]
+    pub fn front(&self) -> Option<&T> {
+        self.list.front()
+    }
+
+    /// Provides a mutable reference to the front element of the cursor's
+    /// parent list, or `None` if the list is empty.
+    #[inline]
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.list.front_mut()
+    }
+
+    /// Provides a reference to the back element of the cursor's parent list,
+    /// or `None` if the list is empty.
+    #[inline]
+    pub fn back(&self) -> Option<&T> {
+        self.list.back()
+    }
+
+    /// Provides a mutable reference to the back element of the cursor's
+    /// parent list, or `None` if the list is empty.
+    #[inline]
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.list.back_mut()
+    }
+}
+
+impl<'a, T, A: InterchangeableAllocator> CursorMut<'a, T, A> {
+    /// Inserts the elements from the given `LinkedList` after the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new elements are
+    /// inserted at the start of the `LinkedList`. This never allocates.
+    #[inline]
+    pub fn splice_after(&mut self, list: LinkedList<T, A>) {
+        // SAFETY: the allocators are interchangeable.
+        unsafe { self.splice_after_unchecked(list) }
+    }
+
+    /// Inserts the elements from the given `LinkedList` before the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new elements are
+    /// inserted at the end of the `LinkedList`. This never allocates.
+    #[inline]
+    pub fn splice_before(&mut self, list: LinkedList<T, A>) {
+        // SAFETY: the allocators are interchangeable.
+        unsafe { self.splice_before_unchecked(list) }
+    }
+}
+
+impl<'a, T, A: Allocator + Clone> CursorMut<'a, T, A> {
+    /// Removes the current element from the `LinkedList` without deallocating
+    /// the list node.
+    ///
+    /// The node that was removed is returned as a new `LinkedList` containing
+    /// only this node. The cursor is moved to point to the next element in the
+    /// current `LinkedList`.
+    ///
+    /// If the cursor is currently pointing to the "ghost" non-element then no
+    /// element is removed and `None` is returned.
+    #[inline]
+    pub fn remove_current_as_list(&mut self) -> Option<LinkedList<T, A>> {
+        let node = self.current?;
+        unsafe {
+            self.current = (*node.as_ptr()).next;
+            self.list.unlink_node(node);
+            (*node.as_ptr()).prev = None;
+            (*node.as_ptr()).next = None;
+        }
+        Some(LinkedList {
+            head: Some(node),
+            tail: Some(node),
+            len: 1,
+            alloc: self.list.alloc.clone(),
+            marker: PhantomData,
+        })
+    }
+
+    /// Splits the list into two after the current element. This will return
+    /// a new list consisting of everything after the cursor, with the original
+    /// list retaining everything before.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the entire
+    /// contents of the `LinkedList` are moved. This never allocates.
+    #[inline]
+    pub fn split_after(&mut self) -> LinkedList<T, A> {
+        let split_off_idx = if self.index == self.list.len { 0 } else { self.index + 1 };
+        if self.index == self.list.len {
+            // The split off list is taken from after the "ghost" non-element,
+            // which is now at index 0 of an empty list.
+            self.index = 0;
+        }
+        unsafe { self.list.split_off_after_node(self.current, split_off_idx) }
+    }
+
+    /// Splits the list into two before the current element. This will return
+    /// a new list consisting of everything before the cursor, with the
+    /// original list retaining everything after.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the entire
+    /// contents of the `LinkedList` are moved. This never allocates.
+    #[inline]
+    pub fn split_before(&mut self) -> LinkedList<T, A> {
+        let split_off_idx = self.index;
+        self.index = 0;
+        unsafe { self.list.split_off_before_node(self.current, split_off_idx) }
+    }
+}
+
+/// An iterator over the elements of a `LinkedList`.
+///
+/// This `struct` is created by [`LinkedList::iter()`].
+pub struct Iter<'a, T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    marker: PhantomData<&'a Node<T>>,
+}
+
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+
+impl<T> Clone for Iter<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Iter<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.head.map(|node| unsafe {
+            let node = &*node.as_ptr();
+            self.len -= 1;
+            self.head = node.next;
+            &node.element
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.tail.map(|node| unsafe {
+            let node = &*node.as_ptr();
+            self.len -= 1;
+            self.tail = node.prev;
+            &node.element
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// A mutable iterator over the elements of a `LinkedList`.
+///
+/// This `struct` is created by [`LinkedList::iter_mut()`].
+pub struct IterMut<'a, T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    marker: PhantomData<&'a mut Node<T>>,
+}
+
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.head.map(|node| unsafe {
+            let node = &mut *node.as_ptr();
+            self.len -= 1;
+            self.head = node.next;
+            &mut node.element
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.tail.map(|node| unsafe {
+            let node = &mut *node.as_ptr();
+            self.len -= 1;
+            self.tail = node.prev;
+            &mut node.element
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+/// An owning iterator over the elements of a `LinkedList`.
+///
+/// This `struct` is created by the `into_iter` method on [`LinkedList`]
+/// (provided by the [`IntoIterator`] trait).
+pub struct IntoIter<T, A: Allocator = Global> {
+    list: LinkedList<T, A>,
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for IntoIter<T, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.list).finish()
+    }
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> IntoIterator for LinkedList<T, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a LinkedList<T, A> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a mut LinkedList<T, A> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> Default for LinkedList<T> {
+    #[inline]
+    fn default() -> Self {
+        LinkedList::new()
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for LinkedList<T, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: PartialEq, A: Allocator> PartialEq for LinkedList<T, A> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other)
+    }
+}
+
+impl<T: Eq, A: Allocator> Eq for LinkedList<T, A> {}
+
+impl<T: PartialOrd, A: Allocator> PartialOrd for LinkedList<T, A> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other)
+    }
+}
+
+impl<T: Ord, A: Allocator> Ord for LinkedList<T, A> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other)
+    }
+}
+
+impl<T: Hash, A: Allocator> Hash for LinkedList<T, A> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for elt in self {
+            elt.hash(state);
+        }
+    }
+}
+
+impl<T: TryClone, A: Allocator + Clone> TryClone for LinkedList<T, A> {
+    #[inline]
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        let mut list = LinkedList::new_in(self.alloc.clone());
+        for elt in self {
+            list.try_push_back(elt.try_clone()?)?;
+        }
+        Ok(list)
+    }
+}
+
+impl<T: HeapSize, A: Allocator> HeapSize for LinkedList<T, A> {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.iter().map(|elt| mem::size_of::<Node<T>>() + elt.heap_size()).sum()
+    }
+}
+
+impl<T> TryFromIterator<T> for LinkedList<T> {
+    type Error = AllocError;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, Self::Error> {
+        let mut list = LinkedList::new();
+        list.try_extend(iter)?;
+        Ok(list)
+    }
+}
+
+impl<T, E> TryFromIterator<Result<T, E>> for LinkedList<T>
+where
+    E: From<AllocError>,
+{
+    type Error = E;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = Result<T, E>>>(iter: I) -> Result<Self, Self::Error> {
+        iter::try_process(iter.into_iter(), |shunt| LinkedList::try_from_iter(shunt))
+    }
+}
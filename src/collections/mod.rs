@@ -8,3 +8,36 @@ pub use hash_map::HashMap;
 
 #[doc(inline)]
 pub use hash_set::HashSet;
+
+pub mod vec_deque;
+
+#[doc(inline)]
+pub use vec_deque::VecDeque;
+
+pub mod binary_heap;
+
+#[doc(inline)]
+pub use binary_heap::{BinaryHeap, IndexedBinaryHeap};
+
+pub mod btree_map;
+pub mod btree_set;
+
+#[doc(inline)]
+pub use btree_map::BTreeMap;
+
+#[doc(inline)]
+pub use btree_set::BTreeSet;
+
+pub mod linked_list;
+
+#[doc(inline)]
+pub use linked_list::LinkedList;
+
+pub mod index_map;
+pub mod index_set;
+
+#[doc(inline)]
+pub use index_map::IndexMap;
+
+#[doc(inline)]
+pub use index_set::IndexSet;
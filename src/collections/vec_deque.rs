@@ -0,0 +1,630 @@
+//! A double-ended queue (deque) implemented with a growable ring buffer.
+
+pub use std::collections::vec_deque::{Drain, IntoIter, Iter, IterMut};
+
+use crate::alloc::AllocError;
+use crate::clone::TryClone;
+use crate::iter::{self, TryFromIterator};
+use crate::mem::HeapSize;
+use crate::vec::{GrowthPolicy, Vec};
+use std::alloc::{Allocator, Global};
+use std::cmp::Ordering;
+use std::collections::VecDeque as StdVecDeque;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::mem;
+use std::ops::{Index, IndexMut, RangeBounds};
+
+/// A double-ended queue implemented with a growable ring buffer.
+///
+/// Every operation that may grow the buffer reports an [`AllocError`]
+/// instead of aborting.
+#[repr(transparent)]
+pub struct VecDeque<T, A: Allocator = Global>(StdVecDeque<T, A>);
+
+impl<T> VecDeque<T> {
+    /// Creates an empty deque.
+    ///
+    /// The deque will not allocate until elements are pushed onto it.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        VecDeque(StdVecDeque::new())
+    }
+
+    /// Creates an empty deque with space for at least `capacity` elements.
+    #[inline]
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, AllocError> {
+        Self::try_with_capacity_in(capacity, Global)
+    }
+}
+
+impl<T, A: Allocator> VecDeque<T, A> {
+    /// Creates an empty deque with the provided allocator.
+    #[inline]
+    pub fn new_in(alloc: A) -> Self {
+        VecDeque(StdVecDeque::new_in(alloc))
+    }
+
+    /// Creates an empty deque with space for at least `capacity` elements
+    /// with the provided allocator.
+    #[inline]
+    pub fn try_with_capacity_in(capacity: usize, alloc: A) -> Result<Self, AllocError> {
+        let mut deque = VecDeque::new_in(alloc);
+        deque.try_reserve_exact(capacity)?;
+        Ok(deque)
+    }
+
+    /// Creates a `VecDeque<T, A>` from a `std::collections::VecDeque<T, A>`.
+    #[inline]
+    pub fn from_std(deque: StdVecDeque<T, A>) -> Self {
+        VecDeque(deque)
+    }
+
+    /// Converts the deque into a `std::collections::VecDeque<T, A>`.
+    #[inline]
+    pub fn into_std(self) -> StdVecDeque<T, A> {
+        self.0
+    }
+
+    /// Returns the number of elements the deque can hold without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+
+    /// Returns the number of elements in the deque.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the deque is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        self.0.allocator()
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements.
+    ///
+    /// The deque may reserve more space to avoid frequent reallocations. If
+    /// the capacity overflows, or the allocator reports a failure, an error
+    /// is returned and the deque is left unchanged.
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        self.0.try_reserve(additional)?;
+        Ok(())
+    }
+
+    /// Tries to reserve the minimum capacity for exactly `additional` more elements.
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, an error
+    /// is returned and the deque is left unchanged.
+    #[inline]
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), AllocError> {
+        self.0.try_reserve_exact(additional)?;
+        Ok(())
+    }
+
+    /// Provides a reference to the element at the given index.
+    ///
+    /// Element at index 0 is the front of the queue.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.0.get(index)
+    }
+
+    /// Provides a mutable reference to the element at the given index.
+    ///
+    /// Element at index 0 is the front of the queue.
+    #[inline]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.0.get_mut(index)
+    }
+
+    /// Provides a reference to the front element, or `None` if the deque is empty.
+    #[inline]
+    pub fn front(&self) -> Option<&T> {
+        self.0.front()
+    }
+
+    /// Provides a mutable reference to the front element, or `None` if the deque is empty.
+    #[inline]
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.0.front_mut()
+    }
+
+    /// Provides a reference to the back element, or `None` if the deque is empty.
+    #[inline]
+    pub fn back(&self) -> Option<&T> {
+        self.0.back()
+    }
+
+    /// Provides a mutable reference to the back element, or `None` if the deque is empty.
+    #[inline]
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.0.back_mut()
+    }
+
+    /// Returns a front-to-back iterator.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.0.iter()
+    }
+
+    /// Returns a front-to-back iterator that returns mutable references.
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.0.iter_mut()
+    }
+
+    /// Returns a pair of slices which contain, in order, the contents of the deque.
+    #[inline]
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        self.0.as_slices()
+    }
+
+    /// Returns a pair of mutable slices which contain, in order, the contents of the deque.
+    #[inline]
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        self.0.as_mut_slices()
+    }
+
+    /// Rearranges the internal storage of the deque so it is one contiguous
+    /// slice, which is then returned.
+    ///
+    /// This method does not allocate.
+    #[inline]
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        self.0.make_contiguous()
+    }
+
+    /// Returns `true` if the deque contains an element equal to the given value.
+    #[inline]
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.0.contains(x)
+    }
+
+    /// Swaps elements at indices `i` and `j`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    #[inline]
+    pub fn swap(&mut self, i: usize, j: usize) {
+        self.0.swap(i, j)
+    }
+
+    /// Appends an element to the back of the deque.
+    #[inline]
+    pub fn try_push_back(&mut self, value: T) -> Result<(), AllocError> {
+        self.try_reserve(1)?;
+        self.0.push_back(value);
+        Ok(())
+    }
+
+    /// Prepends an element to the front of the deque.
+    #[inline]
+    pub fn try_push_front(&mut self, value: T) -> Result<(), AllocError> {
+        self.try_reserve(1)?;
+        self.0.push_front(value);
+        Ok(())
+    }
+
+    /// Removes the last element and returns it, or `None` if the deque is empty.
+    #[inline]
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.0.pop_back()
+    }
+
+    /// Removes the first element and returns it, or `None` if the deque is empty.
+    #[inline]
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.0.pop_front()
+    }
+
+    /// Inserts an element at `index` within the deque, shifting all elements
+    /// with indices greater than or equal to `index` towards the back.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the deque's length.
+    #[inline]
+    pub fn try_insert(&mut self, index: usize, value: T) -> Result<(), AllocError> {
+        assert!(index <= self.len(), "index out of bounds");
+        self.try_reserve(1)?;
+        self.0.insert(index, value);
+        Ok(())
+    }
+
+    /// Removes and returns the element at `index` from the deque, or `None`
+    /// if `index` is out of bounds.
+    #[inline]
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        self.0.remove(index)
+    }
+
+    /// Removes an element from anywhere in the deque and returns it, replacing
+    /// it with the first element.
+    #[inline]
+    pub fn swap_remove_front(&mut self, index: usize) -> Option<T> {
+        self.0.swap_remove_front(index)
+    }
+
+    /// Removes an element from anywhere in the deque and returns it, replacing
+    /// it with the last element.
+    #[inline]
+    pub fn swap_remove_back(&mut self, index: usize) -> Option<T> {
+        self.0.swap_remove_back(index)
+    }
+
+    /// Appends the elements of an iterator to the back of the deque.
+    ///
+    /// The deque reserves the lower bound of the iterator's `size_hint` up
+    /// front. If an allocation fails, the elements appended so far are kept.
+    #[inline]
+    pub fn try_extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), AllocError> {
+        let iter = iter.into_iter();
+        self.try_reserve(iter.size_hint().0)?;
+        for value in iter {
+            self.try_push_back(value)?;
+        }
+        Ok(())
+    }
+
+    /// Moves all the elements of `other` into `self`, leaving `other` empty.
+    ///
+    /// If the allocation fails, both deques are left unchanged.
+    #[inline]
+    pub fn try_append(&mut self, other: &mut Self) -> Result<(), AllocError> {
+        self.try_reserve(other.len())?;
+        self.0.append(&mut other.0);
+        Ok(())
+    }
+
+    /// Shortens the deque, keeping the first `len` elements and dropping the rest.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        self.0.truncate(len)
+    }
+
+    /// Removes all elements from the deque.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0.clear()
+    }
+
+    /// Retains only the elements specified by the predicate.
+    #[inline]
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.0.retain(f)
+    }
+
+    /// Retains only the elements specified by the predicate, passing a
+    /// mutable reference to it.
+    #[inline]
+    pub fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        self.0.retain_mut(f)
+    }
+
+    /// Removes the specified range from the deque in bulk, returning all
+    /// removed elements as an iterator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the deque.
+    #[inline]
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T, A>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.0.drain(range)
+    }
+
+    /// Rotates the double-ended queue `n` places to the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than `len()`.
+    #[inline]
+    pub fn rotate_left(&mut self, n: usize) {
+        self.0.rotate_left(n)
+    }
+
+    /// Rotates the double-ended queue `n` places to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than `len()`.
+    #[inline]
+    pub fn rotate_right(&mut self, n: usize) {
+        self.0.rotate_right(n)
+    }
+
+    /// Resizes the deque in-place so that `len` is equal to `new_len`,
+    /// filling new slots at the back with the result of calling `f`.
+    ///
+    /// If the allocation or `f` fails, the deque is left unchanged.
+    #[inline]
+    pub fn try_resize_with<F>(&mut self, new_len: usize, mut f: F) -> Result<(), AllocError>
+    where
+        F: FnMut() -> Result<T, AllocError>,
+    {
+        let len = self.len();
+        if new_len <= len {
+            self.truncate(new_len);
+            return Ok(());
+        }
+        self.try_reserve(new_len - len)?;
+        for _ in len..new_len {
+            match f() {
+                Ok(value) => self.0.push_back(value),
+                Err(e) => {
+                    self.truncate(len);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: TryClone, A: Allocator> VecDeque<T, A> {
+    /// Resizes the deque in-place so that `len` is equal to `new_len`,
+    /// filling new slots at the back with clones of `value`.
+    ///
+    /// If the allocation or cloning `value` fails, the deque is left unchanged.
+    #[inline]
+    pub fn try_resize(&mut self, new_len: usize, value: T) -> Result<(), AllocError> {
+        let len = self.len();
+        if new_len <= len {
+            self.truncate(new_len);
+            return Ok(());
+        }
+        self.try_reserve(new_len - len)?;
+        for _ in len + 1..new_len {
+            match value.try_clone() {
+                Ok(value) => self.0.push_back(value),
+                Err(e) => {
+                    self.truncate(len);
+                    return Err(e);
+                }
+            }
+        }
+        self.0.push_back(value);
+        Ok(())
+    }
+}
+
+/// Turns a [`Vec`] into a [`VecDeque`].
+///
+/// This reuses the buffer of the vector and does not allocate.
+impl<T, A: Allocator, G: GrowthPolicy> From<Vec<T, A, G>> for VecDeque<T, A> {
+    #[inline]
+    fn from(vec: Vec<T, A, G>) -> Self {
+        VecDeque(StdVecDeque::from(vec.into_std()))
+    }
+}
+
+/// Turns a [`VecDeque`] into a [`Vec`].
+///
+/// This reuses the buffer of the deque and does not allocate. The elements
+/// are moved in place to make them contiguous, if they are not already.
+impl<T, A: Allocator> From<VecDeque<T, A>> for Vec<T, A> {
+    #[inline]
+    fn from(deque: VecDeque<T, A>) -> Self {
+        Vec::from_std(deque.0.into())
+    }
+}
+
+impl<T> Default for VecDeque<T> {
+    #[inline]
+    fn default() -> Self {
+        VecDeque::new()
+    }
+}
+
+impl<T, A: Allocator> Index<usize> for VecDeque<T, A> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &T {
+        &self.0[index]
+    }
+}
+
+impl<T, A: Allocator> IndexMut<usize> for VecDeque<T, A> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        &mut self.0[index]
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for VecDeque<T, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl<T: PartialEq, A: Allocator> PartialEq for VecDeque<T, A> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: Eq, A: Allocator> Eq for VecDeque<T, A> {}
+
+impl<T: PartialOrd, A: Allocator> PartialOrd for VecDeque<T, A> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+impl<T: Ord, A: Allocator> Ord for VecDeque<T, A> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl<T: Hash, A: Allocator> Hash for VecDeque<T, A> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl<T: TryClone, A: Allocator + Clone> TryClone for VecDeque<T, A> {
+    #[inline]
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        let mut deque = VecDeque::try_with_capacity_in(self.len(), self.allocator().clone())?;
+        for value in self {
+            deque.0.push_back(value.try_clone()?);
+        }
+        Ok(deque)
+    }
+}
+
+impl<T: HeapSize, A: Allocator> HeapSize for VecDeque<T, A> {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        let (front, back) = self.as_slices();
+        self.capacity() * mem::size_of::<T>() + front.heap_size() + back.heap_size()
+    }
+}
+
+impl<T> TryFromIterator<T> for VecDeque<T> {
+    type Error = AllocError;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, Self::Error> {
+        let mut deque = VecDeque::new();
+        deque.try_extend(iter)?;
+        Ok(deque)
+    }
+}
+
+impl<T, E> TryFromIterator<Result<T, E>> for VecDeque<T>
+where
+    E: From<AllocError>,
+{
+    type Error = E;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = Result<T, E>>>(iter: I) -> Result<Self, Self::Error> {
+        iter::try_process(iter.into_iter(), |shunt| VecDeque::try_from_iter(shunt))
+    }
+}
+
+impl<T, A: Allocator> IntoIterator for VecDeque<T, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a VecDeque<T, A> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a mut VecDeque<T, A> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use super::VecDeque;
+    use serde_crate::de::{Error, SeqAccess, Visitor};
+    use serde_crate::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::fmt;
+    use std::marker::PhantomData;
+
+    impl<T: Serialize> Serialize for VecDeque<T> {
+        #[inline]
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_seq(self)
+        }
+    }
+
+    impl<'de, T> Deserialize<'de> for VecDeque<T>
+    where
+        T: Deserialize<'de>,
+    {
+        #[inline]
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            struct VecDequeVisitor<T> {
+                _marker: PhantomData<T>,
+            }
+
+            impl<'de, T> Visitor<'de> for VecDequeVisitor<T>
+            where
+                T: Deserialize<'de>,
+            {
+                type Value = VecDeque<T>;
+
+                #[inline]
+                fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.write_str("a sequence")
+                }
+
+                #[inline]
+                fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                where
+                    A: SeqAccess<'de>,
+                {
+                    let cap = seq.size_hint().unwrap_or(8).min(4096);
+                    let mut values = VecDeque::try_with_capacity(cap).map_err(A::Error::custom)?;
+
+                    while let Some(value) = seq.next_element()? {
+                        values.try_push_back(value).map_err(A::Error::custom)?;
+                    }
+
+                    Ok(values)
+                }
+            }
+
+            let visitor = VecDequeVisitor { _marker: PhantomData };
+            deserializer.deserialize_seq(visitor)
+        }
+    }
+}
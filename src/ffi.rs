@@ -0,0 +1,356 @@
+//! Buffer types that can be passed across an FFI boundary.
+//!
+//! [`FfiVec`] and [`FfiString`] are `#[repr(C)]` views of a [`Vec`] and a
+//! [`String`]: the C side sees a `{ ptr, len, cap }` struct, and the buffer
+//! can be reclaimed on the Rust side after it comes back.
+//!
+//! With the `ffi` feature, `extern "C"` functions are exported so that C
+//! code can grow and free byte buffers by itself:
+//!
+//! ```c
+//! typedef struct { uint8_t *ptr; size_t len; size_t cap; } FfiVec;
+//! typedef struct { uint8_t *ptr; size_t len; size_t cap; } FfiString;
+//!
+//! FfiVec fallacy_ffi_vec_new(void);
+//! bool fallacy_ffi_vec_try_reserve(FfiVec *vec, size_t additional);
+//! void fallacy_ffi_vec_free(FfiVec *vec);
+//!
+//! FfiString fallacy_ffi_string_new(void);
+//! bool fallacy_ffi_string_try_reserve(FfiString *string, size_t additional);
+//! void fallacy_ffi_string_free(FfiString *string);
+//! ```
+//!
+//! C code may write into the spare capacity and then update `len`. The
+//! contents of an `FfiString` must stay valid UTF-8.
+
+use crate::alloc::AllocError;
+use crate::string::String;
+use crate::vec::Vec;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::slice;
+use std::str;
+
+/// A `#[repr(C)]` vector that owns its buffer.
+///
+/// The buffer is freed when the `FfiVec` is dropped, or can be converted
+/// back into a [`Vec`] with [`FfiVec::into_vec`].
+#[repr(C)]
+pub struct FfiVec<T> {
+    ptr: *mut T,
+    len: usize,
+    cap: usize,
+    phantom: PhantomData<T>,
+}
+
+unsafe impl<T: Send> Send for FfiVec<T> {}
+unsafe impl<T: Sync> Sync for FfiVec<T> {}
+
+impl<T> FfiVec<T> {
+    /// Constructs a new, empty `FfiVec<T>`.
+    #[inline]
+    pub fn new() -> Self {
+        FfiVec::from(Vec::new())
+    }
+
+    /// Converts the `FfiVec<T>` back into a `Vec<T>`.
+    #[inline]
+    pub fn into_vec(self) -> Vec<T> {
+        let this = ManuallyDrop::new(self);
+        unsafe { Vec::from_raw_parts(this.ptr, this.len, this.cap) }
+    }
+
+    /// Returns the number of elements in the vector.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements the vector can hold without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// Returns a raw pointer to the vector's buffer.
+    #[inline]
+    pub fn as_ptr(&self) -> *const T {
+        self.ptr
+    }
+
+    /// Returns an unsafe mutable pointer to the vector's buffer.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr
+    }
+
+    /// Extracts a slice containing the entire vector.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    /// Extracts a mutable slice of the entire vector.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements.
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        self.with_vec(|vec| vec.try_reserve(additional))
+    }
+
+    /// Runs `f` on the buffer as a `Vec<T>`.
+    ///
+    /// If `f` panics, the buffer is leaked instead of being freed twice.
+    #[inline]
+    fn with_vec<R>(&mut self, f: impl FnOnce(&mut Vec<T>) -> R) -> R {
+        let mut vec = ManuallyDrop::new(unsafe { Vec::from_raw_parts(self.ptr, self.len, self.cap) });
+        let result = f(&mut vec);
+        let (ptr, len, cap) = ManuallyDrop::into_inner(vec).into_raw_parts();
+        self.ptr = ptr;
+        self.len = len;
+        self.cap = cap;
+        result
+    }
+}
+
+impl<T> From<Vec<T>> for FfiVec<T> {
+    #[inline]
+    fn from(vec: Vec<T>) -> Self {
+        let (ptr, len, cap) = vec.into_raw_parts();
+        FfiVec {
+            ptr,
+            len,
+            cap,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> From<FfiVec<T>> for Vec<T> {
+    #[inline]
+    fn from(vec: FfiVec<T>) -> Self {
+        vec.into_vec()
+    }
+}
+
+impl<T> Default for FfiVec<T> {
+    #[inline]
+    fn default() -> Self {
+        FfiVec::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for FfiVec<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_slice(), f)
+    }
+}
+
+impl<T> Drop for FfiVec<T> {
+    #[inline]
+    fn drop(&mut self) {
+        drop(unsafe { Vec::from_raw_parts(self.ptr, self.len, self.cap) });
+    }
+}
+
+/// A `#[repr(C)]` UTF-8 string that owns its buffer.
+///
+/// The buffer is freed when the `FfiString` is dropped, or can be converted
+/// back into a [`String`] with [`FfiString::into_string`].
+#[repr(C)]
+pub struct FfiString {
+    ptr: *mut u8,
+    len: usize,
+    cap: usize,
+}
+
+unsafe impl Send for FfiString {}
+unsafe impl Sync for FfiString {}
+
+impl FfiString {
+    /// Creates a new empty `FfiString`.
+    #[inline]
+    pub fn new() -> Self {
+        FfiString::from(String::new())
+    }
+
+    /// Converts the `FfiString` back into a `String`.
+    #[inline]
+    pub fn into_string(self) -> String {
+        let this = ManuallyDrop::new(self);
+        unsafe { String::from_raw_parts(this.ptr, this.len, this.cap) }
+    }
+
+    /// Returns the length of this string, in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if this string has a length of zero.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns this string's capacity, in bytes.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// Returns a raw pointer to the string's buffer.
+    #[inline]
+    pub fn as_ptr(&self) -> *const u8 {
+        self.ptr
+    }
+
+    /// Extracts a string slice containing the entire string.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(slice::from_raw_parts(self.ptr, self.len)) }
+    }
+
+    /// Tries to reserve capacity for at least `additional` more bytes.
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        self.with_string(|string| string.try_reserve(additional))
+    }
+
+    /// Appends a given string slice onto the end of this string.
+    #[inline]
+    pub fn try_push_str(&mut self, string: &str) -> Result<(), AllocError> {
+        self.with_string(|s| s.try_push_str(string))
+    }
+
+    /// Runs `f` on the buffer as a `String`.
+    ///
+    /// If `f` panics, the buffer is leaked instead of being freed twice.
+    #[inline]
+    fn with_string<R>(&mut self, f: impl FnOnce(&mut String) -> R) -> R {
+        let mut string = ManuallyDrop::new(unsafe { String::from_raw_parts(self.ptr, self.len, self.cap) });
+        let result = f(&mut string);
+        let (ptr, len, cap) = ManuallyDrop::into_inner(string).into_raw_parts();
+        self.ptr = ptr;
+        self.len = len;
+        self.cap = cap;
+        result
+    }
+}
+
+impl From<String> for FfiString {
+    #[inline]
+    fn from(string: String) -> Self {
+        let (ptr, len, cap) = string.into_raw_parts();
+        FfiString { ptr, len, cap }
+    }
+}
+
+impl From<FfiString> for String {
+    #[inline]
+    fn from(string: FfiString) -> Self {
+        string.into_string()
+    }
+}
+
+impl Default for FfiString {
+    #[inline]
+    fn default() -> Self {
+        FfiString::new()
+    }
+}
+
+impl fmt::Debug for FfiString {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for FfiString {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl Drop for FfiString {
+    #[inline]
+    fn drop(&mut self) {
+        drop(unsafe { String::from_raw_parts(self.ptr, self.len, self.cap) });
+    }
+}
+
+/// Creates a new, empty byte vector.
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub extern "C" fn fallacy_ffi_vec_new() -> FfiVec<u8> {
+    FfiVec::new()
+}
+
+/// Tries to reserve capacity for at least `additional` more bytes.
+///
+/// Returns `false` if `vec` is null or the allocation failed, in which case
+/// `vec` is unchanged.
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub extern "C" fn fallacy_ffi_vec_try_reserve(vec: Option<&mut FfiVec<u8>>, additional: usize) -> bool {
+    match vec {
+        Some(vec) => vec.try_reserve(additional).is_ok(),
+        None => false,
+    }
+}
+
+/// Frees the buffer of a byte vector, and resets it to an empty vector.
+///
+/// Does nothing if `vec` is null.
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub extern "C" fn fallacy_ffi_vec_free(vec: Option<&mut FfiVec<u8>>) {
+    if let Some(vec) = vec {
+        drop(std::mem::take(vec));
+    }
+}
+
+/// Creates a new, empty string.
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub extern "C" fn fallacy_ffi_string_new() -> FfiString {
+    FfiString::new()
+}
+
+/// Tries to reserve capacity for at least `additional` more bytes.
+///
+/// Returns `false` if `string` is null or the allocation failed, in which
+/// case `string` is unchanged.
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub extern "C" fn fallacy_ffi_string_try_reserve(string: Option<&mut FfiString>, additional: usize) -> bool {
+    match string {
+        Some(string) => string.try_reserve(additional).is_ok(),
+        None => false,
+    }
+}
+
+/// Frees the buffer of a string, and resets it to an empty string.
+///
+/// Does nothing if `string` is null.
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub extern "C" fn fallacy_ffi_string_free(string: Option<&mut FfiString>) {
+    if let Some(string) = string {
+        drop(std::mem::take(string));
+    }
+}
@@ -0,0 +1,205 @@
+//! Composable external iteration.
+
+use crate::alloc::AllocError;
+use crate::boxed::Box;
+use crate::collections::{HashMap, HashSet};
+use crate::vec::{InPlaceIterable, Vec};
+use std::hash::{BuildHasher, Hash};
+use std::iter::SourceIter;
+
+/// Conversion from an [`Iterator`], returning an error if allocation failed.
+///
+/// By implementing `TryFromIterator` for a type, you define how it will be
+/// created from an iterator. This is common for types which describe a
+/// collection of some kind.
+///
+/// [`TryFromIterator::try_from_iter`] is rarely called explicitly, and is instead
+/// used through [`IteratorExt::try_collect`] method.
+pub trait TryFromIterator<A>: Sized {
+    /// The type returned in the event of a conversion error.
+    type Error;
+
+    /// Creates a value from an iterator.
+    fn try_from_iter<I: IntoIterator<Item = A>>(iter: I) -> Result<Self, Self::Error>;
+}
+
+/// Iterator extension.
+pub trait IteratorExt: Iterator {
+    /// Transforms an iterator into a collection, returning an error if allocation failed.
+    ///
+    /// The collection reserves the lower bound of the iterator's `size_hint` up front.
+    ///
+    /// An iterator of `Result<T, E>` items, where `E: From<AllocError>`, can be
+    /// collected into a collection of `T`. The first error produced by the iterator
+    /// stops the collection and is returned.
+    #[inline]
+    fn try_collect<B>(self) -> Result<B, B::Error>
+    where
+        B: TryFromIterator<Self::Item>,
+        Self: Sized,
+    {
+        B::try_from_iter(self)
+    }
+}
+
+impl<I: Iterator> IteratorExt for I {}
+
+impl<T> TryFromIterator<T> for Box<[T]> {
+    type Error = AllocError;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, Self::Error> {
+        Vec::try_from_iter(iter)?.try_into_boxed_slice()
+    }
+}
+
+impl<T, E> TryFromIterator<Result<T, E>> for Box<[T]>
+where
+    E: From<AllocError>,
+{
+    type Error = E;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = Result<T, E>>>(iter: I) -> Result<Self, Self::Error> {
+        try_process(iter.into_iter(), |shunt| Box::try_from_iter(shunt))
+    }
+}
+
+impl<K, V, S> TryFromIterator<(K, V)> for HashMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher + Default,
+{
+    type Error = AllocError;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Result<Self, Self::Error> {
+        let iter = iter.into_iter();
+        let mut map = HashMap::try_with_capacity_and_hasher(iter.size_hint().0, S::default())?;
+        for (k, v) in iter {
+            map.try_insert(k, v)?;
+        }
+        Ok(map)
+    }
+}
+
+impl<K, V, S, E> TryFromIterator<Result<(K, V), E>> for HashMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher + Default,
+    E: From<AllocError>,
+{
+    type Error = E;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = Result<(K, V), E>>>(iter: I) -> Result<Self, Self::Error> {
+        try_process(iter.into_iter(), |shunt| HashMap::try_from_iter(shunt))
+    }
+}
+
+impl<T, S> TryFromIterator<T> for HashSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher + Default,
+{
+    type Error = AllocError;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, Self::Error> {
+        let iter = iter.into_iter();
+        let mut set = HashSet::try_with_capacity_and_hasher(iter.size_hint().0, S::default())?;
+        for value in iter {
+            set.try_insert(value)?;
+        }
+        Ok(set)
+    }
+}
+
+impl<T, S, E> TryFromIterator<Result<T, E>> for HashSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher + Default,
+    E: From<AllocError>,
+{
+    type Error = E;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = Result<T, E>>>(iter: I) -> Result<Self, Self::Error> {
+        try_process(iter.into_iter(), |shunt| HashSet::try_from_iter(shunt))
+    }
+}
+
+/// Collects an iterator of `Result<T, E>` with `f`, stopping at the first error.
+#[inline]
+pub(crate) fn try_process<I, T, E, F, U>(iter: I, f: F) -> Result<U, E>
+where
+    I: Iterator<Item = Result<T, E>>,
+    F: for<'a> FnOnce(ResultShunt<'a, I, E>) -> Result<U, AllocError>,
+    E: From<AllocError>,
+{
+    let mut error = None;
+    let shunt = ResultShunt {
+        iter,
+        error: &mut error,
+    };
+    let value = f(shunt);
+    match error {
+        Some(e) => Err(e),
+        None => value.map_err(E::from),
+    }
+}
+
+/// An iterator adapter that produces the values of an iterator of `Result`s
+/// until an error is encountered, which is stored aside.
+pub(crate) struct ResultShunt<'a, I, E> {
+    iter: I,
+    error: &'a mut Option<E>,
+}
+
+// SAFETY: `as_inner` forwards to the wrapped iterator, so it reaches the same
+// source that `next` advances.
+unsafe impl<I: SourceIter, E> SourceIter for ResultShunt<'_, I, E> {
+    type Source = I::Source;
+
+    #[inline]
+    unsafe fn as_inner(&mut self) -> &mut I::Source {
+        self.iter.as_inner()
+    }
+}
+
+// SAFETY: `next` yields at most the one `Ok` value of each item it takes from
+// `I`, and stops taking items after the first error.
+unsafe impl<I: InPlaceIterable, E> InPlaceIterable for ResultShunt<'_, I, E> {}
+
+impl<I, T, E> Iterator for ResultShunt<'_, I, E>
+where
+    I: Iterator<Item = Result<T, E>>,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.error.is_some() {
+            return None;
+        }
+        match self.iter.next() {
+            Some(Ok(value)) => Some(value),
+            Some(Err(e)) => {
+                *self.error = Some(e);
+                None
+            }
+            None => None,
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.error.is_some() {
+            (0, Some(0))
+        } else {
+            // An error can end the iteration at any point, so the lower bound is 0.
+            let (_, upper) = self.iter.size_hint();
+            (0, upper)
+        }
+    }
+}
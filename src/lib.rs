@@ -2,16 +2,31 @@
 
 #![feature(allocator_api)]
 #![feature(can_vector)]
+#![feature(core_io_borrowed_buf)]
 #![feature(fmt_internals)]
+// `std::iter::SourceIter`, for in-place collection in `vec::into_iter`.
+#![feature(inplace_iteration)]
 #![feature(min_specialization)]
+// `#[rustc_specialization_trait]`, for in-place collection in `vec::into_iter`.
+#![feature(rustc_attrs)]
+#![feature(slice_partition_dedup)]
+#![feature(slice_range)]
+#![feature(vec_split_at_spare)]
 #![feature(unicode_internals)]
 
 extern crate core;
 
+pub mod arrayvec;
 pub mod borrow;
 pub mod collections;
+pub mod ffi;
 pub mod fmt;
+pub mod iter;
+pub mod mem;
 pub mod prelude;
+pub mod slab;
+pub mod slice;
+pub mod smallvec;
 pub mod str;
 pub mod string;
 pub mod sync;
@@ -21,6 +36,26 @@ pub mod vec;
 pub mod alloc {
     pub use fallacy_alloc::AllocError;
     pub use std::alloc::{Allocator, Global, Layout};
+
+    use std::alloc::System;
+
+    /// Allocators whose values can all deallocate the memory allocated by any
+    /// other value of the type.
+    ///
+    /// Collections moving allocated nodes between two of their values without
+    /// reallocating, like [`LinkedList::append`], require this.
+    ///
+    /// [`LinkedList::append`]: crate::collections::LinkedList::append
+    ///
+    /// # Safety
+    ///
+    /// Memory allocated by a value of the type must be valid to deallocate
+    /// through any other value of the type.
+    pub unsafe trait InterchangeableAllocator: Allocator {}
+
+    unsafe impl InterchangeableAllocator for Global {}
+    unsafe impl InterchangeableAllocator for System {}
+    unsafe impl<A: InterchangeableAllocator + ?Sized> InterchangeableAllocator for &A {}
 }
 
 /// The `TryClone` trait for types that cannot be 'implicitly copied'.
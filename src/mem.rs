@@ -0,0 +1,190 @@
+//! Basic functions for dealing with memory.
+
+use crate::boxed::Box;
+use crate::collections::{HashMap, HashSet};
+use crate::sync::Arc;
+use std::alloc::Allocator;
+use std::marker::PhantomData;
+use std::mem::{self, MaybeUninit};
+
+#[cfg(feature = "derive")]
+pub use fallacy_derive::HeapSize;
+
+/// Reports the number of bytes a value owns on the heap.
+///
+/// The size of the value itself is not included, so the total memory used
+/// by `value` is `mem::size_of_val(&value) + value.heap_size()`.
+///
+/// With the `derive` feature, `#[derive(HeapSize)]` implements this trait
+/// for structs and enums by summing up the heap size of their fields.
+pub trait HeapSize {
+    /// Returns the number of heap bytes owned by this value.
+    fn heap_size(&self) -> usize;
+}
+
+/// Types for which the all-zero byte pattern is a valid value.
+///
+/// This allows creating values of the type by requesting zeroed memory from
+/// the allocator, see [`Vec::try_zeroed`](crate::vec::Vec::try_zeroed).
+///
+/// # Safety
+///
+/// The all-zero byte pattern must be a valid value of the type.
+pub unsafe trait Zeroable {}
+
+macro_rules! impl_zeroable {
+    ($($t: ty),*) => {
+        $(unsafe impl Zeroable for $t {})*
+    }
+}
+
+impl_zeroable!(u8, u16, u32, u64, u128, usize);
+impl_zeroable!(i8, i16, i32, i64, i128, isize);
+impl_zeroable!(f32, f64, bool, char, ());
+
+unsafe impl<T: ?Sized> Zeroable for PhantomData<T> {}
+unsafe impl<T> Zeroable for MaybeUninit<T> {}
+unsafe impl<T> Zeroable for *const T {}
+unsafe impl<T> Zeroable for *mut T {}
+unsafe impl<T: Zeroable, const N: usize> Zeroable for [T; N] {}
+
+macro_rules! impl_heap_size_zero {
+    ($($t: ty),*) => {
+        $(impl HeapSize for $t {
+            #[inline(always)]
+            fn heap_size(&self) -> usize {
+                0
+            }
+        })*
+    }
+}
+
+impl_heap_size_zero!(u8, u16, u32, u64, u128, usize);
+impl_heap_size_zero!(i8, i16, i32, i64, i128, isize);
+impl_heap_size_zero!(f32, f64, bool, char, (), str);
+
+impl<T: ?Sized> HeapSize for &T {
+    #[inline(always)]
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<T: ?Sized> HeapSize for &mut T {
+    #[inline(always)]
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<T: ?Sized> HeapSize for PhantomData<T> {
+    #[inline(always)]
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<T: HeapSize> HeapSize for [T] {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.iter().map(HeapSize::heap_size).sum()
+    }
+}
+
+impl<T: HeapSize, const N: usize> HeapSize for [T; N] {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.as_slice().heap_size()
+    }
+}
+
+impl<T: HeapSize> HeapSize for Option<T> {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        match self {
+            Some(v) => v.heap_size(),
+            None => 0,
+        }
+    }
+}
+
+impl<T: HeapSize, E: HeapSize> HeapSize for Result<T, E> {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        match self {
+            Ok(v) => v.heap_size(),
+            Err(e) => e.heap_size(),
+        }
+    }
+}
+
+macro_rules! impl_heap_size_tuple {
+    ($($name: ident)+) => {
+        impl<$($name: HeapSize),+> HeapSize for ($($name,)+) {
+            #[inline]
+            #[allow(non_snake_case)]
+            fn heap_size(&self) -> usize {
+                let ($($name,)+) = self;
+                0 $(+ $name.heap_size())+
+            }
+        }
+    }
+}
+
+impl_heap_size_tuple!(A);
+impl_heap_size_tuple!(A B);
+impl_heap_size_tuple!(A B C);
+impl_heap_size_tuple!(A B C D);
+impl_heap_size_tuple!(A B C D E);
+impl_heap_size_tuple!(A B C D E F);
+
+impl<T: ?Sized + HeapSize, A: Allocator> HeapSize for Box<T, A> {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        let value: &T = self;
+        mem::size_of_val(value) + value.heap_size()
+    }
+}
+
+/// The memory of the shared value, including the reference counts, is divided
+/// evenly among the strong references, so that summing up the heap size of
+/// all the `Arc`s pointing to the same value counts it (about) once.
+impl<T: ?Sized + HeapSize> HeapSize for Arc<T> {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        let value: &T = self;
+        let shared = 2 * mem::size_of::<usize>() + mem::size_of_val(value) + value.heap_size();
+        shared / Arc::strong_count(self)
+    }
+}
+
+impl<K: HeapSize, V: HeapSize, S> HeapSize for HashMap<K, V, S> {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        let entries: usize = self.iter().map(|(k, v)| k.heap_size() + v.heap_size()).sum();
+        hash_table_size(self.capacity(), mem::size_of::<(K, V)>()) + entries
+    }
+}
+
+impl<T: HeapSize, S> HeapSize for HashSet<T, S> {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        let entries: usize = self.iter().map(HeapSize::heap_size).sum();
+        hash_table_size(self.capacity(), mem::size_of::<T>()) + entries
+    }
+}
+
+/// Estimates the size of the table allocated by a hash map with the given
+/// capacity: one slot and one control byte per bucket, plus a trailing group
+/// of control bytes.
+#[inline]
+fn hash_table_size(capacity: usize, slot_size: usize) -> usize {
+    const GROUP_WIDTH: usize = 16;
+
+    if capacity == 0 {
+        return 0;
+    }
+    // The table keeps 1/8 of the buckets empty, except for small tables.
+    let buckets = if capacity < 8 { capacity + 1 } else { capacity / 7 * 8 };
+    buckets * (slot_size + 1) + GROUP_WIDTH
+}
@@ -9,6 +9,9 @@ pub use crate::clone::TryClone;
 #[doc(no_inline)]
 pub use crate::string::{String, TryToString};
 
+#[doc(no_inline)]
+pub use crate::slice::SliceExt;
+
 #[doc(no_inline)]
 pub use crate::str::StrExt;
 
@@ -18,5 +21,11 @@ pub use crate::vec::Vec;
 #[doc(no_inline)]
 pub use crate::borrow::TryToOwned;
 
+#[doc(no_inline)]
+pub use crate::iter::{IteratorExt, TryFromIterator};
+
 #[doc(no_inline)]
 pub use crate::sync::Arc;
+
+#[doc(no_inline)]
+pub use crate::mem::HeapSize;
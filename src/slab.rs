@@ -0,0 +1,569 @@
+//! Pre-allocated storage for a uniform data type, with stable keys.
+//!
+//! A [`Slab`] hands out a `usize` key for each inserted value. The slots of
+//! removed values are linked into a free list and reused by later insertions,
+//! so the keys of the other values never change.
+
+use crate::alloc::AllocError;
+use crate::clone::TryClone;
+use crate::mem::HeapSize;
+use crate::vec::{self, Vec};
+use std::alloc::{Allocator, Global};
+use std::fmt;
+use std::iter::{Enumerate, FusedIterator};
+use std::mem;
+use std::ops::{Index, IndexMut};
+use std::slice;
+
+/// Pre-allocated storage for a uniform data type, with stable keys.
+pub struct Slab<T, A: Allocator = Global> {
+    entries: Vec<Entry<T>, A>,
+    len: usize,
+    /// The first vacant slot, or `entries.len()` if there is none.
+    next: usize,
+}
+
+enum Entry<T> {
+    /// A vacant slot, holding the next vacant slot of the free list.
+    Vacant(usize),
+    Occupied(T),
+}
+
+impl<T: TryClone> TryClone for Entry<T> {
+    #[inline]
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        Ok(match self {
+            Entry::Vacant(next) => Entry::Vacant(*next),
+            Entry::Occupied(value) => Entry::Occupied(value.try_clone()?),
+        })
+    }
+}
+
+impl<T> Slab<T> {
+    /// Constructs a new, empty `Slab`.
+    ///
+    /// The slab will not allocate until values are inserted into it.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Slab {
+            entries: Vec::new(),
+            len: 0,
+            next: 0,
+        }
+    }
+
+    /// Constructs a new, empty `Slab` with at least the specified capacity.
+    #[inline]
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, AllocError> {
+        Ok(Slab {
+            entries: Vec::try_with_capacity(capacity)?,
+            len: 0,
+            next: 0,
+        })
+    }
+}
+
+impl<T, A: Allocator> Slab<T, A> {
+    /// Constructs a new, empty `Slab` with the provided allocator.
+    #[inline]
+    pub fn new_in(alloc: A) -> Self {
+        Slab {
+            entries: Vec::new_in(alloc),
+            len: 0,
+            next: 0,
+        }
+    }
+
+    /// Constructs a new, empty `Slab` with at least the specified capacity
+    /// with the provided allocator.
+    #[inline]
+    pub fn try_with_capacity_in(capacity: usize, alloc: A) -> Result<Self, AllocError> {
+        Ok(Slab {
+            entries: Vec::try_with_capacity_in(capacity, alloc)?,
+            len: 0,
+            next: 0,
+        })
+    }
+
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        self.entries.allocator()
+    }
+
+    /// Returns the number of values the slab can store without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.entries.capacity()
+    }
+
+    /// Returns the number of stored values.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no values are stored in the slab.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Tries to reserve capacity for at least `additional` more values to be
+    /// stored without allocating.
+    ///
+    /// The slab may reserve more space to avoid frequent reallocations.
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        let vacant = self.entries.len() - self.len;
+        if additional <= vacant {
+            return Ok(());
+        }
+        self.entries.try_reserve(additional - vacant)
+    }
+
+    /// Tries to reserve the minimum capacity for at least `additional` more
+    /// values to be stored without allocating.
+    #[inline]
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), AllocError> {
+        let vacant = self.entries.len() - self.len;
+        if additional <= vacant {
+            return Ok(());
+        }
+        self.entries.try_reserve_exact(additional - vacant)
+    }
+
+    /// Clears the slab, removing all values.
+    ///
+    /// Note that this method has no effect on the allocated capacity of the slab.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.len = 0;
+        self.next = 0;
+    }
+
+    /// Returns a reference to the value associated with the given key.
+    #[inline]
+    pub fn get(&self, key: usize) -> Option<&T> {
+        match self.entries.get(key) {
+            Some(Entry::Occupied(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value associated with the given key.
+    #[inline]
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        match self.entries.get_mut(key) {
+            Some(Entry::Occupied(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if a value is associated with the given key.
+    #[inline]
+    pub fn contains(&self, key: usize) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns the key of the next vacant entry.
+    ///
+    /// This is the key that the next call to [`try_insert`] will use.
+    ///
+    /// [`try_insert`]: Slab::try_insert
+    #[inline]
+    pub fn vacant_key(&self) -> usize {
+        self.next
+    }
+
+    /// Inserts a value in the slab, returning its key.
+    ///
+    /// If the allocation fails, the slab is left unchanged.
+    #[inline]
+    pub fn try_insert(&mut self, value: T) -> Result<usize, AllocError> {
+        let key = self.next;
+        if key == self.entries.len() {
+            self.entries.try_reserve(1)?;
+        }
+        self.insert_at(key, value);
+        Ok(key)
+    }
+
+    /// Returns a handle to a vacant entry, allowing for further manipulation.
+    ///
+    /// The capacity for the entry is reserved up front, so inserting into the
+    /// entry never fails. This is useful when the value needs to know its key.
+    #[inline]
+    pub fn try_vacant_entry(&mut self) -> Result<VacantEntry<'_, T, A>, AllocError> {
+        let key = self.next;
+        if key == self.entries.len() {
+            self.entries.try_reserve(1)?;
+        }
+        Ok(VacantEntry { key, slab: self })
+    }
+
+    /// Removes and returns the value associated with the given key.
+    ///
+    /// The key is then released and may be associated with future stored values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no value is associated with `key`.
+    #[inline]
+    #[track_caller]
+    pub fn remove(&mut self, key: usize) -> T {
+        self.try_remove(key).expect("invalid key")
+    }
+
+    /// Removes and returns the value associated with the given key, if any.
+    #[inline]
+    pub fn try_remove(&mut self, key: usize) -> Option<T> {
+        let entry = self.entries.get_mut(key)?;
+        if let Entry::Vacant(_) = entry {
+            return None;
+        }
+        let Entry::Occupied(value) = mem::replace(entry, Entry::Vacant(self.next)) else {
+            unreachable!()
+        };
+        self.len -= 1;
+        self.next = key;
+        Some(value)
+    }
+
+    /// Retains only the values specified by the predicate.
+    ///
+    /// The predicate is called with the key and a mutable reference to each
+    /// value, in the order of the keys.
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, &mut T) -> bool,
+    {
+        for key in 0..self.entries.len() {
+            let keep = match &mut self.entries[key] {
+                Entry::Occupied(value) => f(key, value),
+                Entry::Vacant(_) => true,
+            };
+            if !keep {
+                self.try_remove(key);
+            }
+        }
+    }
+
+    /// Returns an iterator over the keys and values of the slab.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            entries: self.entries.iter().enumerate(),
+            len: self.len,
+        }
+    }
+
+    /// Returns an iterator over the keys and values of the slab, with mutable
+    /// references to the values.
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            entries: self.entries.iter_mut().enumerate(),
+            len: self.len,
+        }
+    }
+
+    /// Stores a value in the vacant slot `key`, which must be the head of the
+    /// free list. A new slot must have been reserved if the free list is empty.
+    #[inline]
+    fn insert_at(&mut self, key: usize, value: T) {
+        if key == self.entries.len() {
+            if self.entries.push_within_capacity(Entry::Occupied(value)).is_err() {
+                unreachable!("slot is reserved");
+            }
+            self.next = key + 1;
+        } else {
+            match mem::replace(&mut self.entries[key], Entry::Occupied(value)) {
+                Entry::Vacant(next) => self.next = next,
+                Entry::Occupied(_) => unreachable!("slot is occupied"),
+            }
+        }
+        self.len += 1;
+    }
+}
+
+/// A handle to a vacant entry in a `Slab`.
+///
+/// This `struct` is created by the [`try_vacant_entry`] method on [`Slab`].
+///
+/// [`try_vacant_entry`]: Slab::try_vacant_entry
+pub struct VacantEntry<'a, T, A: Allocator = Global> {
+    key: usize,
+    slab: &'a mut Slab<T, A>,
+}
+
+impl<'a, T, A: Allocator> VacantEntry<'a, T, A> {
+    /// Returns the key of the vacant entry.
+    #[inline]
+    pub fn key(&self) -> usize {
+        self.key
+    }
+
+    /// Inserts a value in the entry, and returns a mutable reference to it.
+    ///
+    /// This never allocates, the slot was reserved by [`Slab::try_vacant_entry`].
+    #[inline]
+    pub fn insert(self, value: T) -> &'a mut T {
+        self.slab.insert_at(self.key, value);
+        match &mut self.slab.entries[self.key] {
+            Entry::Occupied(value) => value,
+            Entry::Vacant(_) => unreachable!(),
+        }
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for VacantEntry<'_, T, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VacantEntry").field("key", &self.key).finish()
+    }
+}
+
+/// An iterator over the keys and values of a `Slab`.
+///
+/// This `struct` is created by the [`iter`] method on [`Slab`].
+///
+/// [`iter`]: Slab::iter
+pub struct Iter<'a, T> {
+    entries: Enumerate<slice::Iter<'a, Entry<T>>>,
+    len: usize,
+}
+
+impl<T> Clone for Iter<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Iter {
+            entries: self.entries.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Iter<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (usize, &'a T);
+
+    #[inline]
+    fn next(&mut self) -> Option<(usize, &'a T)> {
+        for (key, entry) in &mut self.entries {
+            if let Entry::Occupied(value) = entry {
+                self.len -= 1;
+                return Some((key, value));
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while let Some((key, entry)) = self.entries.next_back() {
+            if let Entry::Occupied(value) = entry {
+                self.len -= 1;
+                return Some((key, value));
+            }
+        }
+        None
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// A mutable iterator over the keys and values of a `Slab`.
+///
+/// This `struct` is created by the [`iter_mut`] method on [`Slab`].
+///
+/// [`iter_mut`]: Slab::iter_mut
+pub struct IterMut<'a, T> {
+    entries: Enumerate<slice::IterMut<'a, Entry<T>>>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = (usize, &'a mut T);
+
+    #[inline]
+    fn next(&mut self) -> Option<(usize, &'a mut T)> {
+        for (key, entry) in &mut self.entries {
+            if let Entry::Occupied(value) = entry {
+                self.len -= 1;
+                return Some((key, value));
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while let Some((key, entry)) = self.entries.next_back() {
+            if let Entry::Occupied(value) = entry {
+                self.len -= 1;
+                return Some((key, value));
+            }
+        }
+        None
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+/// An owning iterator over the keys and values of a `Slab`.
+///
+/// This `struct` is created by the `into_iter` method on [`Slab`]
+/// (provided by the [`IntoIterator`] trait).
+pub struct IntoIter<T, A: Allocator = Global> {
+    entries: Enumerate<vec::IntoIter<Entry<T>, A>>,
+    len: usize,
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = (usize, T);
+
+    #[inline]
+    fn next(&mut self) -> Option<(usize, T)> {
+        for (key, entry) in &mut self.entries {
+            if let Entry::Occupied(value) = entry {
+                self.len -= 1;
+                return Some((key, value));
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(usize, T)> {
+        while let Some((key, entry)) = self.entries.next_back() {
+            if let Entry::Occupied(value) = entry {
+                self.len -= 1;
+                return Some((key, value));
+            }
+        }
+        None
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> IntoIterator for Slab<T, A> {
+    type Item = (usize, T);
+    type IntoIter = IntoIter<T, A>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            entries: self.entries.into_iter().enumerate(),
+            len: self.len,
+        }
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a Slab<T, A> {
+    type Item = (usize, &'a T);
+    type IntoIter = Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a mut Slab<T, A> {
+    type Item = (usize, &'a mut T);
+    type IntoIter = IterMut<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, A: Allocator> Index<usize> for Slab<T, A> {
+    type Output = T;
+
+    #[inline]
+    #[track_caller]
+    fn index(&self, key: usize) -> &T {
+        self.get(key).expect("invalid key")
+    }
+}
+
+impl<T, A: Allocator> IndexMut<usize> for Slab<T, A> {
+    #[inline]
+    #[track_caller]
+    fn index_mut(&mut self, key: usize) -> &mut T {
+        self.get_mut(key).expect("invalid key")
+    }
+}
+
+impl<T> Default for Slab<T> {
+    #[inline]
+    fn default() -> Self {
+        Slab::new()
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for Slab<T, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T: TryClone, A: Allocator + Clone> TryClone for Slab<T, A> {
+    #[inline]
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        Ok(Slab {
+            entries: self.entries.try_clone()?,
+            len: self.len,
+            next: self.next,
+        })
+    }
+}
+
+impl<T: HeapSize, A: Allocator> HeapSize for Slab<T, A> {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        let values: usize = self.iter().map(|(_, value)| value.heap_size()).sum();
+        self.entries.capacity() * mem::size_of::<Entry<T>>() + values
+    }
+}
@@ -0,0 +1,314 @@
+//! Utilities for the slice primitive type.
+
+use crate::alloc::AllocError;
+use crate::sealed::Sealed;
+use crate::vec::Vec;
+use std::cmp::Ordering;
+use std::mem::{self, ManuallyDrop};
+use std::ptr;
+
+/// Slice extension.
+///
+/// The sorts are stable, and allocate their scratch memory before the slice
+/// is modified. If the allocation fails, an error is returned and the slice
+/// is left unchanged.
+pub trait SliceExt<T>: Sealed {
+    /// Sorts the slice.
+    ///
+    /// This sort is stable (i.e., does not reorder equal elements) and `O(n * log(n))` worst-case.
+    fn try_sort(&mut self) -> Result<(), AllocError>
+    where
+        T: Ord;
+
+    /// Sorts the slice with a comparator function.
+    ///
+    /// This sort is stable (i.e., does not reorder equal elements) and `O(n * log(n))` worst-case.
+    fn try_sort_by<F>(&mut self, compare: F) -> Result<(), AllocError>
+    where
+        F: FnMut(&T, &T) -> Ordering;
+
+    /// Sorts the slice with a key extraction function.
+    ///
+    /// This sort is stable (i.e., does not reorder equal elements) and `O(m * n * log(n))`
+    /// worst-case, where the key function is `O(m)`.
+    fn try_sort_by_key<K, F>(&mut self, f: F) -> Result<(), AllocError>
+    where
+        F: FnMut(&T) -> K,
+        K: Ord;
+
+    /// Sorts the slice with a key extraction function, calling the key function
+    /// only once per element.
+    ///
+    /// This sort is stable (i.e., does not reorder equal elements) and `O(m * n + n * log(n))`
+    /// worst-case, where the key function is `O(m)`.
+    fn try_sort_by_cached_key<K, F>(&mut self, f: F) -> Result<(), AllocError>
+    where
+        F: FnMut(&T) -> K,
+        K: Ord;
+}
+
+impl<T> Sealed for [T] {}
+
+impl<T> SliceExt<T> for [T] {
+    #[inline]
+    fn try_sort(&mut self) -> Result<(), AllocError>
+    where
+        T: Ord,
+    {
+        merge_sort(self, &mut T::lt)
+    }
+
+    #[inline]
+    fn try_sort_by<F>(&mut self, mut compare: F) -> Result<(), AllocError>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        merge_sort(self, &mut |a, b| compare(a, b) == Ordering::Less)
+    }
+
+    #[inline]
+    fn try_sort_by_key<K, F>(&mut self, mut f: F) -> Result<(), AllocError>
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        merge_sort(self, &mut |a, b| f(a).lt(&f(b)))
+    }
+
+    #[inline]
+    fn try_sort_by_cached_key<K, F>(&mut self, mut f: F) -> Result<(), AllocError>
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        let len = self.len();
+        if len < 2 {
+            return Ok(());
+        }
+
+        let mut indices: Vec<(K, usize)> = Vec::try_with_capacity(len)?;
+        for (i, v) in self.iter().enumerate() {
+            indices.try_push((f(v), i))?;
+        }
+        // The indices make all the pairs distinct, so an unstable sort of the
+        // pairs gives a stable order of the elements without allocating.
+        indices.sort_unstable();
+        for i in 0..len {
+            let mut index = indices[i].1;
+            while index < i {
+                index = indices[index].1;
+            }
+            indices[i].1 = index;
+            self.swap(i, index);
+        }
+        Ok(())
+    }
+}
+
+/// Slices of up to this length get sorted using insertion sort.
+const MAX_INSERTION: usize = 20;
+
+/// Sorts `v` using a bottom-up merge sort.
+///
+/// The merge buffer is allocated before `v` is touched, so `v` is unchanged
+/// if the allocation fails.
+fn merge_sort<T, F>(v: &mut [T], is_less: &mut F) -> Result<(), AllocError>
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    // Sorting has no meaningful behavior on zero-sized types.
+    if mem::size_of::<T>() == 0 {
+        return Ok(());
+    }
+
+    let len = v.len();
+    if len <= MAX_INSERTION {
+        insertion_sort(v, is_less);
+        return Ok(());
+    }
+
+    // The shorter of two merged runs gets copied into the buffer, so it needs
+    // room for at most `len / 2` elements.
+    let mut buf = Vec::<T>::try_with_capacity(len / 2)?;
+    let buf_ptr = buf.as_mut_ptr();
+
+    for chunk in v.chunks_mut(MAX_INSERTION) {
+        insertion_sort(chunk, is_less);
+    }
+
+    let mut width = MAX_INSERTION;
+    while width < len {
+        let mut start = 0;
+        while start + width < len {
+            let end = len.min(start + 2 * width);
+            let run = &mut v[start..end];
+            // Skip the merge if the runs are already in order.
+            if is_less(&run[width], &run[width - 1]) {
+                unsafe {
+                    merge(run, width, buf_ptr, is_less);
+                }
+            }
+            start = end;
+        }
+        width *= 2;
+    }
+    Ok(())
+}
+
+/// Sorts `v` using insertion sort, which is `O(n^2)` worst-case.
+fn insertion_sort<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    for i in 1..v.len() {
+        unsafe {
+            insert_tail(&mut v[..=i], is_less);
+        }
+    }
+}
+
+/// Inserts `v[v.len() - 1]` into the pre-sorted sequence `v[..v.len() - 1]`.
+///
+/// # Safety
+///
+/// `v` must have at least 2 elements.
+unsafe fn insert_tail<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let arr = v.as_mut_ptr();
+    let i = v.len() - 1;
+    let i_ptr = arr.add(i);
+
+    if is_less(&*i_ptr, &*i_ptr.sub(1)) {
+        // Read the last element into a stack-allocated variable. If a comparison
+        // panics, `hole` gets dropped and writes it back into the slot left in `v`.
+        let tmp = ManuallyDrop::new(ptr::read(i_ptr));
+        let mut hole = InsertionHole {
+            src: &*tmp,
+            dest: i_ptr.sub(1),
+        };
+        ptr::copy_nonoverlapping(hole.dest, i_ptr, 1);
+
+        for j in (0..i - 1).rev() {
+            let j_ptr = arr.add(j);
+            if !is_less(&*tmp, &*j_ptr) {
+                break;
+            }
+            ptr::copy_nonoverlapping(j_ptr, hole.dest, 1);
+            hole.dest = j_ptr;
+        }
+        // `hole` gets dropped and thus copies `tmp` into the remaining hole in `v`.
+    }
+}
+
+/// Merges the sorted runs `v[..mid]` and `v[mid..]`, using `buf` as temporary
+/// storage.
+///
+/// # Safety
+///
+/// Both runs must be non-empty, and `buf` must have room for the shorter one.
+unsafe fn merge<T, F>(v: &mut [T], mid: usize, buf: *mut T, is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let len = v.len();
+    let v = v.as_mut_ptr();
+    let v_mid = v.add(mid);
+    let v_end = v.add(len);
+
+    // The shorter run gets copied into `buf` and merged back into `v`. Whenever
+    // a comparison panics, `hole` gets dropped and copies what remains of the
+    // shorter run into the gap left in `v`, so `v` always holds every element
+    // exactly once.
+    let mut hole;
+
+    if mid <= len - mid {
+        // The left run is shorter, merge forwards.
+        ptr::copy_nonoverlapping(v, buf, mid);
+        hole = MergeHole {
+            start: buf,
+            end: buf.add(mid),
+            dest: v,
+        };
+
+        let left = &mut hole.start;
+        let mut right = v_mid;
+        let out = &mut hole.dest;
+
+        while *left < hole.end && right < v_end {
+            let to_copy = if is_less(&*right, &**left) {
+                get_and_increment(&mut right)
+            } else {
+                get_and_increment(left)
+            };
+            ptr::copy_nonoverlapping(to_copy, get_and_increment(out), 1);
+        }
+    } else {
+        // The right run is shorter, merge backwards.
+        ptr::copy_nonoverlapping(v_mid, buf, len - mid);
+        hole = MergeHole {
+            start: buf,
+            end: buf.add(len - mid),
+            dest: v_mid,
+        };
+
+        let left = &mut hole.dest;
+        let right = &mut hole.end;
+        let mut out = v_end;
+
+        while v < *left && buf < *right {
+            let to_copy = if is_less(&*right.sub(1), &*left.sub(1)) {
+                decrement_and_get(left)
+            } else {
+                decrement_and_get(right)
+            };
+            ptr::copy_nonoverlapping(to_copy, decrement_and_get(&mut out), 1);
+        }
+    }
+    // Finally, `hole` gets dropped. If the shorter run was not fully consumed,
+    // whatever remains of it will now be copied into the hole in `v`.
+
+    unsafe fn get_and_increment<T>(ptr: &mut *mut T) -> *mut T {
+        let old = *ptr;
+        *ptr = ptr.add(1);
+        old
+    }
+
+    unsafe fn decrement_and_get<T>(ptr: &mut *mut T) -> *mut T {
+        *ptr = ptr.sub(1);
+        *ptr
+    }
+}
+
+/// When dropped, copies from `src` into `dest`.
+struct InsertionHole<T> {
+    src: *const T,
+    dest: *mut T,
+}
+
+impl<T> Drop for InsertionHole<T> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            ptr::copy_nonoverlapping(self.src, self.dest, 1);
+        }
+    }
+}
+
+/// When dropped, copies the range `start..end` into `dest..`.
+struct MergeHole<T> {
+    start: *mut T,
+    end: *mut T,
+    dest: *mut T,
+}
+
+impl<T> Drop for MergeHole<T> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            let len = self.end.offset_from(self.start) as usize;
+            ptr::copy_nonoverlapping(self.start, self.dest, len);
+        }
+    }
+}
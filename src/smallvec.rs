@@ -0,0 +1,1662 @@
+//! A vector which stores a small number of elements inline, written
+//! `SmallVec<[T; N]>`.
+//!
+//! Up to `N` elements are stored inside the `SmallVec` itself. When more
+//! room is needed, the elements are moved into a heap buffer, which is
+//! called spilling. Like every allocation in this crate, spilling is
+//! fallible and reports an [`AllocError`] instead of aborting.
+
+use crate::alloc::AllocError;
+use crate::boxed::Box;
+use crate::clone::TryClone;
+use crate::iter::{self, TryFromIterator};
+use crate::mem::{HeapSize, Zeroable};
+use crate::sealed::Sealed;
+use crate::vec::{UninitWriter, Vec};
+use std::alloc::{Allocator, Global};
+use std::borrow::{Borrow, BorrowMut};
+use std::cmp::{self, Ordering};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io::{BorrowedBuf, BorrowedCursor};
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::mem::{self, ManuallyDrop, MaybeUninit};
+use std::ops::{self, Deref, DerefMut, Index, IndexMut, RangeBounds};
+use std::ptr::{self, NonNull};
+use std::slice::{self, SliceIndex};
+
+/// Types that can be used as the inline storage of a [`SmallVec`].
+///
+/// This trait is implemented for arrays `[T; N]`.
+pub trait Array: Sealed {
+    /// The type of the array's elements.
+    type Item;
+    /// The number of elements the array can hold.
+    const CAPACITY: usize;
+}
+
+impl<T, const N: usize> Sealed for [T; N] {}
+
+impl<T, const N: usize> Array for [T; N] {
+    type Item = T;
+    const CAPACITY: usize = N;
+}
+
+/// A vector which stores up to `A::CAPACITY` elements inline, and spills
+/// to a heap buffer allocated with `Al` when it grows beyond that.
+pub struct SmallVec<A: Array, Al: Allocator = Global> {
+    data: Data<A>,
+    alloc: Al,
+}
+
+enum Data<A: Array> {
+    Inline {
+        len: usize,
+        buf: MaybeUninit<A>,
+    },
+    Heap {
+        ptr: NonNull<A::Item>,
+        len: usize,
+        cap: usize,
+    },
+}
+
+unsafe impl<A: Array, Al: Allocator + Send> Send for SmallVec<A, Al> where A::Item: Send {}
+unsafe impl<A: Array, Al: Allocator + Sync> Sync for SmallVec<A, Al> where A::Item: Sync {}
+
+impl<A: Array> SmallVec<A> {
+    /// Constructs a new, empty `SmallVec`.
+    ///
+    /// The vector will not allocate until more than `A::CAPACITY` elements
+    /// are pushed onto it.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        SmallVec::new_in(Global)
+    }
+
+    /// Constructs a new, empty `SmallVec` with at least the specified capacity.
+    ///
+    /// If `capacity` is not greater than `A::CAPACITY`, the vector will not
+    /// allocate.
+    #[inline]
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, AllocError> {
+        SmallVec::try_with_capacity_in(capacity, Global)
+    }
+
+    /// Constructs a new `SmallVec` of length `n`, where each element is the
+    /// result of calling `f` with the element's index.
+    #[inline]
+    pub fn try_from_fn<F>(n: usize, f: F) -> Result<Self, AllocError>
+    where
+        F: FnMut(usize) -> Result<A::Item, AllocError>,
+    {
+        SmallVec::try_from_fn_in(n, f, Global)
+    }
+
+    /// Constructs a new `SmallVec` holding the elements of `buf` inline.
+    #[inline]
+    pub fn from_buf(buf: A) -> Self {
+        SmallVec {
+            data: Data::Inline {
+                len: A::CAPACITY,
+                buf: MaybeUninit::new(buf),
+            },
+            alloc: Global,
+        }
+    }
+}
+
+impl<A: Array> SmallVec<A>
+where
+    A::Item: TryClone,
+{
+    /// Constructs a new `SmallVec` of length `n`, with each element filled
+    /// with a clone of `elem`.
+    #[inline]
+    pub fn try_from_elem(elem: A::Item, n: usize) -> Result<Self, AllocError> {
+        SmallVec::try_from_elem_in(elem, n, Global)
+    }
+}
+
+impl<A: Array> SmallVec<A>
+where
+    A::Item: Zeroable,
+{
+    /// Constructs a new `SmallVec` of length `n`, with all elements zeroed.
+    #[inline]
+    pub fn try_zeroed(n: usize) -> Result<Self, AllocError> {
+        SmallVec::try_zeroed_in(n, Global)
+    }
+}
+
+impl<A: Array, Al: Allocator> SmallVec<A, Al> {
+    /// Constructs a new, empty `SmallVec` with the provided allocator.
+    #[inline]
+    pub const fn new_in(alloc: Al) -> Self {
+        SmallVec {
+            data: Data::Inline {
+                len: 0,
+                buf: MaybeUninit::uninit(),
+            },
+            alloc,
+        }
+    }
+
+    /// Constructs a new, empty `SmallVec` with at least the specified capacity
+    /// with the provided allocator.
+    #[inline]
+    pub fn try_with_capacity_in(capacity: usize, alloc: Al) -> Result<Self, AllocError> {
+        let mut vec = SmallVec::new_in(alloc);
+        vec.try_reserve_exact(capacity)?;
+        Ok(vec)
+    }
+
+    /// Constructs a new `SmallVec` of length `n` with the provided allocator,
+    /// where each element is the result of calling `f` with the element's index.
+    #[inline]
+    pub fn try_from_fn_in<F>(n: usize, mut f: F, alloc: Al) -> Result<Self, AllocError>
+    where
+        F: FnMut(usize) -> Result<A::Item, AllocError>,
+    {
+        let mut vec = SmallVec::try_with_capacity_in(n, alloc)?;
+        for index in 0..n {
+            let value = f(index)?;
+            unsafe { vec.push_unchecked(value) };
+        }
+        Ok(vec)
+    }
+
+    /// Constructs a `SmallVec` from a [`Vec`], taking over its buffer without
+    /// copying the elements.
+    #[inline]
+    pub fn from_vec(vec: Vec<A::Item, Al>) -> Self {
+        let (ptr, len, cap, alloc) = vec.into_raw_parts_with_alloc();
+        if cap == 0 {
+            return SmallVec::new_in(alloc);
+        }
+        SmallVec {
+            // SAFETY: the pointer of a vector is never null.
+            data: Data::Heap {
+                ptr: unsafe { NonNull::new_unchecked(ptr) },
+                len,
+                cap,
+            },
+            alloc,
+        }
+    }
+
+    /// Converts the `SmallVec` into a [`Vec`].
+    ///
+    /// If the vector has spilled, its buffer is reused without copying.
+    /// Otherwise a buffer is allocated and the elements are moved into it.
+    ///
+    /// If the allocation fails, the `SmallVec` is given back with the error.
+    #[inline]
+    pub fn into_vec(mut self) -> Result<Vec<A::Item, Al>, (Self, AllocError)> {
+        if !self.spilled() {
+            if let Err(e) = self.try_spill(self.len()) {
+                return Err((self, e));
+            }
+        }
+        let this = ManuallyDrop::new(self);
+        match this.data {
+            Data::Heap { ptr, len, cap } => unsafe {
+                let alloc = ptr::read(&this.alloc);
+                Ok(Vec::from_raw_parts_in(ptr.as_ptr(), len, cap, alloc))
+            },
+            Data::Inline { .. } => unreachable!("the vector has spilled"),
+        }
+    }
+
+    /// Converts the vector into [`Box<[T]>`][owned slice].
+    ///
+    /// The elements are moved into a heap buffer with exactly the right
+    /// capacity, which reuses the buffer of a spilled vector if it has no
+    /// excess capacity.
+    ///
+    /// [owned slice]: Box
+    #[inline]
+    pub fn try_into_boxed_slice(self) -> Result<Box<[A::Item], Al>, AllocError> {
+        self.into_vec().map_err(|(_, e)| e)?.try_into_boxed_slice()
+    }
+
+    /// Moves the elements into a heap buffer with exactly the right capacity,
+    /// then consumes and leaks it, returning a mutable reference to the contents.
+    ///
+    /// The allocation of the returned slice can be reclaimed by `Box::from_raw_in`.
+    #[inline]
+    pub fn try_leak<'a>(self) -> Result<&'a mut [A::Item], AllocError>
+    where
+        Al: 'a,
+    {
+        self.into_vec().map_err(|(_, e)| e)?.try_leak()
+    }
+
+    /// Returns the number of elements the vector can store inline.
+    #[inline]
+    pub const fn inline_size(&self) -> usize {
+        A::CAPACITY
+    }
+
+    /// Returns `true` if the elements have been moved to a heap buffer.
+    #[inline]
+    pub fn spilled(&self) -> bool {
+        matches!(self.data, Data::Heap { .. })
+    }
+
+    /// Returns the number of elements the vector can hold without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        match self.data {
+            Data::Inline { .. } => A::CAPACITY,
+            Data::Heap { cap, .. } => cap,
+        }
+    }
+
+    /// Returns the number of elements in the vector.
+    #[inline]
+    pub fn len(&self) -> usize {
+        match self.data {
+            Data::Inline { len, .. } => len,
+            Data::Heap { len, .. } => len,
+        }
+    }
+
+    /// Returns `true` if the vector contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(&self) -> &Al {
+        &self.alloc
+    }
+
+    /// Returns a raw pointer to the vector's buffer.
+    #[inline]
+    pub fn as_ptr(&self) -> *const A::Item {
+        match &self.data {
+            Data::Inline { buf, .. } => buf.as_ptr().cast(),
+            Data::Heap { ptr, .. } => ptr.as_ptr(),
+        }
+    }
+
+    /// Returns an unsafe mutable pointer to the vector's buffer.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut A::Item {
+        match &mut self.data {
+            Data::Inline { buf, .. } => buf.as_mut_ptr().cast(),
+            Data::Heap { ptr, .. } => ptr.as_ptr(),
+        }
+    }
+
+    /// Extracts a slice containing the entire vector.
+    #[inline]
+    pub fn as_slice(&self) -> &[A::Item] {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len()) }
+    }
+
+    /// Extracts a mutable slice of the entire vector.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [A::Item] {
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len()) }
+    }
+
+    /// Forces the length of the vector to `new_len`.
+    ///
+    /// # Safety
+    ///
+    /// - `new_len` must be less than or equal to [`capacity()`].
+    /// - The elements at `old_len..new_len` must be initialized.
+    ///
+    /// [`capacity()`]: SmallVec::capacity
+    #[inline]
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        debug_assert!(new_len <= self.capacity());
+        match &mut self.data {
+            Data::Inline { len, .. } => *len = new_len,
+            Data::Heap { len, .. } => *len = new_len,
+        }
+    }
+
+    /// Returns the remaining spare capacity of the vector as a slice of
+    /// `MaybeUninit<T>`.
+    ///
+    /// The returned slice can be used to fill the vector with data before
+    /// marking the data as initialized using the [`set_len`] method.
+    ///
+    /// [`set_len`]: SmallVec::set_len
+    #[inline]
+    pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<A::Item>] {
+        let len = self.len();
+        let spare = self.capacity() - len;
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr().add(len).cast(), spare) }
+    }
+
+    /// Reserves capacity for at least `n` more elements, and lets `f` write
+    /// them into the spare capacity.
+    ///
+    /// `f` is given an [`UninitWriter`] over the first `n` slots of the spare
+    /// capacity. The elements written through it are appended to the vector,
+    /// and the result of `f` is returned.
+    ///
+    /// If `f` panics, the elements written so far are dropped.
+    #[inline]
+    pub fn try_extend_uninit<F, R>(&mut self, n: usize, f: F) -> Result<R, AllocError>
+    where
+        F: FnOnce(&mut UninitWriter<'_, A::Item>) -> R,
+    {
+        self.try_reserve(n)?;
+        let mut writer = UninitWriter::new(&mut self.spare_capacity_mut()[..n]);
+        let res = f(&mut writer);
+        let count = writer.into_len();
+        // SAFETY: the writer guarantees that the first `count` slots are initialized.
+        unsafe {
+            self.set_len(self.len() + count);
+        }
+        Ok(res)
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements.
+    ///
+    /// If the elements are stored inline and do not fit anymore, they are
+    /// moved to a heap buffer. If the allocation fails, the vector is left
+    /// unchanged.
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        let len = self.len();
+        if self.capacity() - len >= additional {
+            return Ok(());
+        }
+        match self.data {
+            Data::Inline { .. } => {
+                let required = len.saturating_add(additional);
+                self.try_spill(cmp::max(required, A::CAPACITY * 2))
+            }
+            Data::Heap { .. } => unsafe { self.with_heap(|vec| vec.try_reserve(additional)) },
+        }
+    }
+
+    /// Tries to reserve the minimum capacity for exactly `additional` more elements.
+    ///
+    /// If the elements are stored inline and do not fit anymore, they are
+    /// moved to a heap buffer. If the allocation fails, the vector is left
+    /// unchanged.
+    #[inline]
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), AllocError> {
+        let len = self.len();
+        if self.capacity() - len >= additional {
+            return Ok(());
+        }
+        match self.data {
+            Data::Inline { .. } => self.try_spill(len.saturating_add(additional)),
+            Data::Heap { .. } => unsafe { self.with_heap(|vec| vec.try_reserve_exact(additional)) },
+        }
+    }
+
+    /// Shrinks the capacity of the vector as much as possible.
+    ///
+    /// If the elements fit inline, they are moved back inline and the heap
+    /// buffer is freed, which never fails.
+    #[inline]
+    pub fn try_shrink_to_fit(&mut self) -> Result<(), AllocError> {
+        match self.data {
+            Data::Inline { .. } => Ok(()),
+            Data::Heap { len, .. } if len <= A::CAPACITY => {
+                self.unspill();
+                Ok(())
+            }
+            Data::Heap { .. } => unsafe { self.with_heap(|vec| vec.try_shrink_to_fit()) },
+        }
+    }
+
+    /// Shrinks the capacity of the vector with a lower bound.
+    ///
+    /// The capacity will remain at least as large as both the length
+    /// and the supplied value. If both fit inline, the elements are moved
+    /// back inline and the heap buffer is freed, which never fails.
+    ///
+    /// If the allocator reports a failure, the vector is left unchanged.
+    #[inline]
+    pub fn try_shrink_to(&mut self, min_capacity: usize) -> Result<(), AllocError> {
+        match self.data {
+            Data::Inline { .. } => Ok(()),
+            Data::Heap { len, .. } if cmp::max(len, min_capacity) <= A::CAPACITY => {
+                self.unspill();
+                Ok(())
+            }
+            Data::Heap { .. } => unsafe { self.with_heap(|vec| vec.try_shrink_to(min_capacity)) },
+        }
+    }
+
+    /// Appends an element to the back of the vector.
+    #[inline]
+    pub fn try_push(&mut self, value: A::Item) -> Result<(), AllocError> {
+        let len = self.len();
+        if len == self.capacity() {
+            self.try_reserve(1)?;
+        }
+        unsafe {
+            ptr::write(self.as_mut_ptr().add(len), value);
+            self.set_len(len + 1);
+        }
+        Ok(())
+    }
+
+    /// Appends an element if there is sufficient spare capacity, otherwise an
+    /// error is returned with the element.
+    #[inline]
+    pub fn push_within_capacity(&mut self, value: A::Item) -> Result<(), A::Item> {
+        let len = self.len();
+        if len == self.capacity() {
+            return Err(value);
+        }
+        unsafe {
+            ptr::write(self.as_mut_ptr().add(len), value);
+            self.set_len(len + 1);
+        }
+        Ok(())
+    }
+
+    /// Appends an element to the back of the vector without checking the capacity.
+    ///
+    /// # Safety
+    ///
+    /// The vector must have spare capacity.
+    #[inline]
+    unsafe fn push_unchecked(&mut self, value: A::Item) {
+        let len = self.len();
+        debug_assert!(len < self.capacity());
+        ptr::write(self.as_mut_ptr().add(len), value);
+        self.set_len(len + 1);
+    }
+
+    /// Removes the last element from the vector and returns it, or [`None`] if it
+    /// is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<A::Item> {
+        let len = self.len();
+        if len == 0 {
+            return None;
+        }
+        unsafe {
+            self.set_len(len - 1);
+            Some(ptr::read(self.as_ptr().add(len - 1)))
+        }
+    }
+
+    /// Inserts an element at position `index` within the vector, shifting all
+    /// elements after it to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    #[inline]
+    pub fn try_insert(&mut self, index: usize, element: A::Item) -> Result<(), AllocError> {
+        let len = self.len();
+        if index > len {
+            panic!("insertion index (is {}) should be <= len (is {})", index, len);
+        }
+        if len == self.capacity() {
+            self.try_reserve(1)?;
+        }
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            ptr::copy(p, p.add(1), len - index);
+            ptr::write(p, element);
+            self.set_len(len + 1);
+        }
+        Ok(())
+    }
+
+    /// Removes and returns the element at position `index` within the vector,
+    /// shifting all elements after it to the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    pub fn remove(&mut self, index: usize) -> A::Item {
+        let len = self.len();
+        if index >= len {
+            panic!("removal index (is {}) should be < len (is {})", index, len);
+        }
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            let value = ptr::read(p);
+            ptr::copy(p.add(1), p, len - index - 1);
+            self.set_len(len - 1);
+            value
+        }
+    }
+
+    /// Removes an element from the vector and returns it.
+    ///
+    /// The removed element is replaced by the last element of the vector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    pub fn swap_remove(&mut self, index: usize) -> A::Item {
+        let len = self.len();
+        if index >= len {
+            panic!("swap_remove index (is {}) should be < len (is {})", index, len);
+        }
+        unsafe {
+            let base = self.as_mut_ptr();
+            let value = ptr::read(base.add(index));
+            ptr::copy(base.add(len - 1), base.add(index), 1);
+            self.set_len(len - 1);
+            value
+        }
+    }
+
+    /// Shortens the vector, keeping the first `len` elements and dropping
+    /// the rest.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        let old_len = self.len();
+        if len >= old_len {
+            return;
+        }
+        unsafe {
+            self.set_len(len);
+            let tail = ptr::slice_from_raw_parts_mut(self.as_mut_ptr().add(len), old_len - len);
+            ptr::drop_in_place(tail);
+        }
+    }
+
+    /// Clears the vector, removing all values.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Retains only the elements specified by the predicate.
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&A::Item) -> bool,
+    {
+        self.retain_mut(|elem| f(elem));
+    }
+
+    /// Retains only the elements specified by the predicate, passing a mutable
+    /// reference to it.
+    #[inline]
+    pub fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut A::Item) -> bool,
+    {
+        let len = self.len();
+        let mut del = 0;
+        {
+            let v = self.as_mut_slice();
+            for i in 0..len {
+                if !f(&mut v[i]) {
+                    del += 1;
+                } else if del > 0 {
+                    v.swap(i - del, i);
+                }
+            }
+        }
+        self.truncate(len - del);
+    }
+
+    /// Removes all but the first of consecutive elements in the vector satisfying a given equality
+    /// relation.
+    ///
+    /// The `same_bucket` function is passed references to two elements from the vector and
+    /// must determine if the elements compare equal. The elements are passed in opposite order
+    /// from their order in the slice, so if `same_bucket(a, b)` returns `true`, `a` is removed.
+    ///
+    /// If the vector is sorted, this removes all duplicates.
+    #[inline]
+    pub fn dedup_by<F>(&mut self, same_bucket: F)
+    where
+        F: FnMut(&mut A::Item, &mut A::Item) -> bool,
+    {
+        let len = self.as_mut_slice().partition_dedup_by(same_bucket).0.len();
+        self.truncate(len);
+    }
+
+    /// Removes all but the first of consecutive elements in the vector that resolve to the same
+    /// key.
+    ///
+    /// If the vector is sorted, this removes all duplicates.
+    #[inline]
+    pub fn dedup_by_key<F, K>(&mut self, mut key: F)
+    where
+        F: FnMut(&mut A::Item) -> K,
+        K: PartialEq,
+    {
+        self.dedup_by(|a, b| key(a) == key(b))
+    }
+
+    /// Moves all the elements of `other` into `self`, leaving `other` empty.
+    ///
+    /// If the allocation fails, both vectors are left unchanged.
+    #[inline]
+    pub fn try_append(&mut self, other: &mut Self) -> Result<(), AllocError> {
+        let count = other.len();
+        self.try_reserve(count)?;
+        unsafe {
+            let len = self.len();
+            ptr::copy_nonoverlapping(other.as_ptr(), self.as_mut_ptr().add(len), count);
+            other.set_len(0);
+            self.set_len(len + count);
+        }
+        Ok(())
+    }
+
+    /// Splits the collection into two at the given index.
+    ///
+    /// Returns a new vector containing the elements in the range `[at, len)`,
+    /// which is stored inline if it fits. After the call, the original vector
+    /// will be left containing the elements `[0, at)` with its previous
+    /// capacity unchanged.
+    ///
+    /// If the allocation fails, the vector is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    #[inline]
+    pub fn try_split_off(&mut self, at: usize) -> Result<Self, AllocError>
+    where
+        Al: Clone,
+    {
+        let len = self.len();
+        if at > len {
+            panic!("`at` split index (is {}) should be <= len (is {})", at, len);
+        }
+
+        let other_len = len - at;
+        let mut other = SmallVec::try_with_capacity_in(other_len, self.alloc.clone())?;
+
+        unsafe {
+            self.set_len(at);
+            ptr::copy_nonoverlapping(self.as_ptr().add(at), other.as_mut_ptr(), other_len);
+            other.set_len(other_len);
+        }
+
+        Ok(other)
+    }
+
+    /// Copies and appends all elements in a slice to the vector.
+    ///
+    /// If the allocation fails, the vector is left unchanged.
+    #[inline]
+    pub fn try_copy_from_slice(&mut self, other: &[A::Item]) -> Result<(), AllocError>
+    where
+        A::Item: Copy,
+    {
+        let count = other.len();
+        self.try_reserve(count)?;
+        unsafe {
+            let len = self.len();
+            ptr::copy_nonoverlapping(other.as_ptr(), self.as_mut_ptr().add(len), count);
+            self.set_len(len + count);
+        }
+        Ok(())
+    }
+
+    /// Creates a draining iterator that removes the specified range in the vector
+    /// and yields the removed items.
+    ///
+    /// When the iterator **is** dropped, all elements in the range are removed
+    /// from the vector, even if the iterator was not fully consumed. If the
+    /// iterator **is not** dropped (with `mem::forget` for example), it is
+    /// unspecified how many elements are removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the vector.
+    #[inline]
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, A, Al>
+    where
+        R: RangeBounds<usize>,
+    {
+        let len = self.len();
+        let ops::Range { start, end } = slice::range(range, ..len);
+        unsafe {
+            // The drained range and the tail are not part of the vector until
+            // the iterator is dropped, so leaking it leaks them as well.
+            self.set_len(start);
+            let mut vec = NonNull::from(self);
+            // The element pointer is derived from `vec`, so that creating
+            // `vec` does not invalidate it.
+            let ptr = NonNull::new_unchecked(vec.as_mut().as_mut_ptr().add(start));
+            Drain {
+                ptr,
+                remaining: end - start,
+                tail_start: end,
+                tail_len: len - end,
+                vec,
+                phantom: PhantomData,
+            }
+        }
+    }
+
+    /// Replaces the specified range in the vector with the given `replace_with`
+    /// iterator. The removed elements are dropped.
+    ///
+    /// The vector reserves space for the lower bound of the iterator's `size_hint`
+    /// up front, which may spill it. If the reservation fails, the vector is left
+    /// unchanged.
+    ///
+    /// If the iterator yields more elements than its lower bound and growing the
+    /// vector for them fails, an error is returned. The range has then been
+    /// replaced by the elements yielded before the one that needed the room, and
+    /// the tail of the vector is kept after them. The element that needed the
+    /// room is dropped, and the rest of the iterator is not consumed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the vector.
+    #[inline]
+    pub fn try_splice<R, I>(&mut self, range: R, replace_with: I) -> Result<(), AllocError>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = A::Item>,
+    {
+        let len = self.len();
+        let ops::Range { start, end } = slice::range(range, ..len);
+        let mut replace_with = replace_with.into_iter();
+
+        if let Some(additional) = replace_with.size_hint().0.checked_sub(end - start) {
+            self.try_reserve(additional)?;
+        }
+
+        unsafe {
+            let mut splice = Splice {
+                vec: self,
+                tail_start: end,
+                tail_len: len - end,
+            };
+            splice.vec.set_len(start);
+            let removed = ptr::slice_from_raw_parts_mut(splice.vec.as_mut_ptr().add(start), end - start);
+            ptr::drop_in_place(removed);
+
+            while splice.fill(&mut replace_with) {
+                let value = match replace_with.next() {
+                    Some(value) => value,
+                    None => break,
+                };
+                splice.try_move_tail(replace_with.size_hint().0.saturating_add(1))?;
+                let len = splice.vec.len();
+                ptr::write(splice.vec.as_mut_ptr().add(len), value);
+                splice.vec.set_len(len + 1);
+            }
+
+            // tail moved back by guard
+        }
+
+        Ok(())
+    }
+
+    /// Resizes the vector in-place so that `len` is equal to `new_len`,
+    /// filling new slots with the result of calling `f`.
+    ///
+    /// If `f` fails, the elements generated so far are kept in the vector.
+    #[inline]
+    pub fn try_resize_with<F>(&mut self, new_len: usize, mut f: F) -> Result<(), AllocError>
+    where
+        F: FnMut() -> Result<A::Item, AllocError>,
+    {
+        let len = self.len();
+        if new_len <= len {
+            self.truncate(new_len);
+            return Ok(());
+        }
+        self.try_reserve(new_len - len)?;
+        for _ in len..new_len {
+            let value = f()?;
+            unsafe { self.push_unchecked(value) };
+        }
+        Ok(())
+    }
+
+    /// Resizes the vector in-place so that `len` is equal to `new_len`, like
+    /// [`try_resize_with`], but leaves the vector unchanged on error.
+    ///
+    /// [`try_resize_with`]: SmallVec::try_resize_with
+    #[inline]
+    pub fn try_resize_with_atomic<F>(&mut self, new_len: usize, f: F) -> Result<(), AllocError>
+    where
+        F: FnMut() -> Result<A::Item, AllocError>,
+    {
+        let len = self.len();
+        let res = self.try_resize_with(new_len, f);
+        if res.is_err() {
+            self.truncate(len);
+        }
+        res
+    }
+
+    /// Maps all elements of the vector with `f` and collects the results into
+    /// a `SmallVec<B, Al>`.
+    ///
+    /// If the vector has spilled, this behaves like [`Vec::try_map_in_place`]:
+    /// the heap buffer is reused if the layouts of the elements match.
+    /// Otherwise the results are stored inline if they fit, and a heap buffer
+    /// is allocated for them if they do not.
+    #[inline]
+    pub fn try_map_in_place<B, F>(self, mut f: F) -> Result<SmallVec<B, Al>, AllocError>
+    where
+        B: Array,
+        F: FnMut(A::Item) -> B::Item,
+    {
+        if self.spilled() {
+            let vec = match self.into_vec() {
+                Ok(vec) => vec,
+                Err(_) => unreachable!("the vector has spilled"),
+            };
+            return Ok(SmallVec::from_vec(vec.try_map_in_place(f)?));
+        }
+
+        let this = ManuallyDrop::new(self);
+        // SAFETY: the elements are inline, so they do not depend on the allocator
+        // and can be moved into a vector using another one.
+        let (data, alloc) = unsafe { (ptr::read(&this.data), ptr::read(&this.alloc)) };
+        let src = SmallVec::<A> { data, alloc: Global };
+
+        let mut vec = SmallVec::try_with_capacity_in(src.len(), alloc)?;
+        for item in src {
+            unsafe { vec.push_unchecked(f(item)) };
+        }
+        Ok(vec)
+    }
+
+    /// Moves the elements from the inline buffer to a new heap buffer with
+    /// capacity for exactly `cap` elements.
+    #[inline]
+    fn try_spill(&mut self, cap: usize) -> Result<(), AllocError> {
+        debug_assert!(!self.spilled());
+        let len = self.len();
+        let mut vec = Vec::try_with_capacity_in(cap, &self.alloc)?;
+        unsafe {
+            ptr::copy_nonoverlapping(self.as_ptr(), vec.as_mut_ptr(), len);
+            vec.set_len(len);
+            let (ptr, len, cap, _) = vec.into_raw_parts_with_alloc();
+            self.data = Data::Heap {
+                ptr: NonNull::new_unchecked(ptr),
+                len,
+                cap,
+            };
+        }
+        Ok(())
+    }
+
+    /// Moves the elements from the heap buffer back inline, and frees the buffer.
+    #[inline]
+    fn unspill(&mut self) {
+        if let Data::Heap { ptr, len, cap } = self.data {
+            debug_assert!(len <= A::CAPACITY);
+            let mut buf = MaybeUninit::<A>::uninit();
+            unsafe {
+                ptr::copy_nonoverlapping(ptr.as_ptr(), buf.as_mut_ptr().cast(), len);
+                drop(Vec::from_raw_parts_in(ptr.as_ptr(), 0, cap, &self.alloc));
+            }
+            self.data = Data::Inline { len, buf };
+        }
+    }
+
+    /// Runs `f` on the heap buffer as a `Vec`.
+    ///
+    /// # Safety
+    ///
+    /// The vector must have spilled.
+    #[inline]
+    unsafe fn with_heap<R>(&mut self, f: impl FnOnce(&mut Vec<A::Item, &Al>) -> R) -> R {
+        let alloc = &self.alloc;
+        match &mut self.data {
+            Data::Heap { ptr, len, cap } => {
+                // If `f` panics, the buffer is leaked instead of being freed twice.
+                let mut vec = ManuallyDrop::new(Vec::from_raw_parts_in(ptr.as_ptr(), *len, *cap, alloc));
+                let res = f(&mut vec);
+                let (new_ptr, new_len, new_cap, _) = ManuallyDrop::into_inner(vec).into_raw_parts_with_alloc();
+                *ptr = NonNull::new_unchecked(new_ptr);
+                *len = new_len;
+                *cap = new_cap;
+                res
+            }
+            Data::Inline { .. } => unreachable!("the vector has not spilled"),
+        }
+    }
+}
+
+impl<A: Array, Al: Allocator> SmallVec<A, Al>
+where
+    A::Item: TryClone,
+{
+    /// Constructs a new `SmallVec` of length `n` with the provided allocator,
+    /// with each element filled with a clone of `elem`.
+    #[inline]
+    pub fn try_from_elem_in(elem: A::Item, n: usize, alloc: Al) -> Result<Self, AllocError> {
+        let mut vec = SmallVec::try_with_capacity_in(n, alloc)?;
+        vec.try_resize(n, elem)?;
+        Ok(vec)
+    }
+
+    /// Clones the vector into a new vector allocated with the provided allocator.
+    #[inline]
+    pub fn try_clone_in<Bl: Allocator>(&self, alloc: Bl) -> Result<SmallVec<A, Bl>, AllocError> {
+        let mut vec = SmallVec::try_with_capacity_in(self.len(), alloc)?;
+        vec.try_extend_from_slice(self)?;
+        Ok(vec)
+    }
+
+    /// Clones and appends all elements in a slice to the vector.
+    ///
+    /// If cloning an element fails, the elements cloned so far are kept in the vector.
+    #[inline]
+    pub fn try_extend_from_slice(&mut self, other: &[A::Item]) -> Result<(), AllocError> {
+        self.try_reserve(other.len())?;
+        for value in other {
+            let value = value.try_clone()?;
+            unsafe { self.push_unchecked(value) };
+        }
+        Ok(())
+    }
+
+    /// Clones elements from `src` range to the end of the vector.
+    ///
+    /// If the allocation or the cloning of any element fails, the vector is
+    /// left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the vector.
+    #[inline]
+    pub fn try_extend_from_within<R>(&mut self, src: R) -> Result<(), AllocError>
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = slice::range(src, ..self.len());
+        self.try_reserve(range.len())?;
+
+        let len = self.len();
+        for i in range {
+            match self[i].try_clone() {
+                Ok(value) => unsafe { self.push_unchecked(value) },
+                Err(e) => {
+                    self.truncate(len);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Resizes the vector in-place so that `len` is equal to `new_len`,
+    /// filling new slots with clones of `value`.
+    ///
+    /// If cloning `value` fails, the clones made so far are kept in the vector.
+    #[inline]
+    pub fn try_resize(&mut self, new_len: usize, value: A::Item) -> Result<(), AllocError> {
+        let len = self.len();
+        if new_len <= len {
+            self.truncate(new_len);
+            return Ok(());
+        }
+        self.try_reserve(new_len - len)?;
+        for _ in len + 1..new_len {
+            let value = value.try_clone()?;
+            unsafe { self.push_unchecked(value) };
+        }
+        unsafe { self.push_unchecked(value) };
+        Ok(())
+    }
+
+    /// Resizes the vector in-place so that `len` is equal to `new_len`, like
+    /// [`try_resize`], but leaves the vector unchanged on error.
+    ///
+    /// [`try_resize`]: SmallVec::try_resize
+    #[inline]
+    pub fn try_resize_atomic(&mut self, new_len: usize, value: A::Item) -> Result<(), AllocError> {
+        let len = self.len();
+        let res = self.try_resize(new_len, value);
+        if res.is_err() {
+            self.truncate(len);
+        }
+        res
+    }
+
+    /// Clones and appends all elements in a slice to the vector, like
+    /// [`try_extend_from_slice`], but leaves the vector unchanged on error.
+    ///
+    /// [`try_extend_from_slice`]: SmallVec::try_extend_from_slice
+    #[inline]
+    pub fn try_extend_from_slice_atomic(&mut self, other: &[A::Item]) -> Result<(), AllocError> {
+        let len = self.len();
+        let res = self.try_extend_from_slice(other);
+        if res.is_err() {
+            self.truncate(len);
+        }
+        res
+    }
+}
+
+impl<A: Array, Al: Allocator> SmallVec<A, Al>
+where
+    A::Item: Zeroable,
+{
+    /// Constructs a new `SmallVec` of length `n` with the provided allocator,
+    /// with all elements zeroed.
+    ///
+    /// The elements are stored inline if they fit, otherwise the memory is
+    /// requested from the allocator as zeroed memory.
+    #[inline]
+    pub fn try_zeroed_in(n: usize, alloc: Al) -> Result<Self, AllocError> {
+        if n > A::CAPACITY {
+            return Ok(SmallVec::from_vec(Vec::try_zeroed_in(n, alloc)?));
+        }
+        Ok(SmallVec {
+            // SAFETY: `A::Item` is `Zeroable`, so the zeroed elements are initialized.
+            data: Data::Inline {
+                len: n,
+                buf: MaybeUninit::zeroed(),
+            },
+            alloc,
+        })
+    }
+}
+
+impl<A: Array<Item = u8>, Al: Allocator> SmallVec<A, Al> {
+    /// Reserves capacity for at least `n` more bytes, and lets `f` write them
+    /// into the spare capacity through a [`BorrowedCursor`].
+    ///
+    /// The bytes `f` has filled through the cursor are appended to the vector.
+    /// The spare capacity is not zeroed first, so this can be used with
+    /// [`Read::read_buf`](std::io::Read::read_buf) to read directly into the vector.
+    #[inline]
+    pub fn try_extend_borrowed_buf<F, R>(&mut self, n: usize, f: F) -> Result<R, AllocError>
+    where
+        F: FnOnce(BorrowedCursor<'_>) -> R,
+    {
+        self.try_reserve(n)?;
+        let mut buf = BorrowedBuf::from(&mut self.spare_capacity_mut()[..n]);
+        let res = f(buf.unfilled());
+        let filled = buf.len();
+        // SAFETY: `BorrowedBuf` guarantees that the filled part is initialized.
+        unsafe {
+            self.set_len(self.len() + filled);
+        }
+        Ok(res)
+    }
+}
+
+impl<A: Array, Al: Allocator> SmallVec<A, Al>
+where
+    A::Item: PartialEq,
+{
+    /// Removes consecutive repeated elements in the vector according to the
+    /// [`PartialEq`] trait implementation.
+    ///
+    /// If the vector is sorted, this removes all duplicates.
+    #[inline]
+    pub fn dedup(&mut self) {
+        self.dedup_by(|a, b| a == b)
+    }
+}
+
+impl<A: Array, Al: Allocator> Drop for SmallVec<A, Al> {
+    #[inline]
+    fn drop(&mut self) {
+        match self.data {
+            Data::Inline { len, .. } => unsafe {
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.as_mut_ptr(), len));
+            },
+            Data::Heap { ptr, len, cap } => unsafe {
+                drop(Vec::from_raw_parts_in(ptr.as_ptr(), len, cap, &self.alloc));
+            },
+        }
+    }
+}
+
+impl<A: Array> Default for SmallVec<A> {
+    #[inline]
+    fn default() -> Self {
+        SmallVec::new()
+    }
+}
+
+impl<A: Array, Al: Allocator> From<Vec<A::Item, Al>> for SmallVec<A, Al> {
+    #[inline]
+    fn from(vec: Vec<A::Item, Al>) -> Self {
+        SmallVec::from_vec(vec)
+    }
+}
+
+impl<A: Array, Al: Allocator> Deref for SmallVec<A, Al> {
+    type Target = [A::Item];
+
+    #[inline]
+    fn deref(&self) -> &[A::Item] {
+        self.as_slice()
+    }
+}
+
+impl<A: Array, Al: Allocator> DerefMut for SmallVec<A, Al> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [A::Item] {
+        self.as_mut_slice()
+    }
+}
+
+impl<A: Array, I: SliceIndex<[A::Item]>, Al: Allocator> Index<I> for SmallVec<A, Al> {
+    type Output = I::Output;
+
+    #[inline]
+    fn index(&self, index: I) -> &Self::Output {
+        Index::index(self.as_slice(), index)
+    }
+}
+
+impl<A: Array, I: SliceIndex<[A::Item]>, Al: Allocator> IndexMut<I> for SmallVec<A, Al> {
+    #[inline]
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        IndexMut::index_mut(self.as_mut_slice(), index)
+    }
+}
+
+impl<A: Array, Al: Allocator> AsRef<[A::Item]> for SmallVec<A, Al> {
+    #[inline]
+    fn as_ref(&self) -> &[A::Item] {
+        self
+    }
+}
+
+impl<A: Array, Al: Allocator> AsMut<[A::Item]> for SmallVec<A, Al> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [A::Item] {
+        self
+    }
+}
+
+impl<A: Array, Al: Allocator> Borrow<[A::Item]> for SmallVec<A, Al> {
+    #[inline]
+    fn borrow(&self) -> &[A::Item] {
+        self
+    }
+}
+
+impl<A: Array, Al: Allocator> BorrowMut<[A::Item]> for SmallVec<A, Al> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut [A::Item] {
+        self
+    }
+}
+
+impl<A: Array, Al: Allocator> fmt::Debug for SmallVec<A, Al>
+where
+    A::Item: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_slice(), f)
+    }
+}
+
+impl<A: Array, B: Array, Al: Allocator, Bl: Allocator> PartialEq<SmallVec<B, Bl>> for SmallVec<A, Al>
+where
+    A::Item: PartialEq<B::Item>,
+{
+    #[inline]
+    fn eq(&self, other: &SmallVec<B, Bl>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<A: Array, Al: Allocator> Eq for SmallVec<A, Al> where A::Item: Eq {}
+
+impl<A: Array, Al: Allocator> PartialOrd for SmallVec<A, Al>
+where
+    A::Item: PartialOrd,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        PartialOrd::partial_cmp(self.as_slice(), other.as_slice())
+    }
+}
+
+impl<A: Array, Al: Allocator> Ord for SmallVec<A, Al>
+where
+    A::Item: Ord,
+{
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        Ord::cmp(self.as_slice(), other.as_slice())
+    }
+}
+
+impl<A: Array, Al: Allocator> Hash for SmallVec<A, Al>
+where
+    A::Item: Hash,
+{
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(self.as_slice(), state)
+    }
+}
+
+impl<A: Array, Al: Allocator + Clone> TryClone for SmallVec<A, Al>
+where
+    A::Item: TryClone,
+{
+    #[inline]
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        let mut vec = SmallVec::try_with_capacity_in(self.len(), self.alloc.clone())?;
+        vec.try_extend_from_slice(self)?;
+        Ok(vec)
+    }
+
+    #[inline]
+    fn try_clone_from(&mut self, source: &Self) -> Result<(), AllocError> {
+        self.truncate(source.len());
+
+        let (init, tail) = source.split_at(self.len());
+        for (dst, src) in self.iter_mut().zip(init) {
+            dst.try_clone_from(src)?;
+        }
+        self.try_extend_from_slice(tail)
+    }
+}
+
+impl<A: Array, Al: Allocator> HeapSize for SmallVec<A, Al>
+where
+    A::Item: HeapSize,
+{
+    #[inline]
+    fn heap_size(&self) -> usize {
+        let buf = match self.data {
+            Data::Inline { .. } => 0,
+            Data::Heap { cap, .. } => cap * mem::size_of::<A::Item>(),
+        };
+        buf + self.as_slice().heap_size()
+    }
+}
+
+impl<T, const N: usize> TryFromIterator<T> for SmallVec<[T; N]> {
+    type Error = AllocError;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, Self::Error> {
+        let iter = iter.into_iter();
+        let mut vec = SmallVec::try_with_capacity(iter.size_hint().0)?;
+        for value in iter {
+            vec.try_push(value)?;
+        }
+        Ok(vec)
+    }
+}
+
+impl<T, E, const N: usize> TryFromIterator<Result<T, E>> for SmallVec<[T; N]>
+where
+    E: From<AllocError>,
+{
+    type Error = E;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = Result<T, E>>>(iter: I) -> Result<Self, Self::Error> {
+        iter::try_process(iter.into_iter(), |shunt| SmallVec::try_from_iter(shunt))
+    }
+}
+
+impl<A: Array, Al: Allocator> IntoIterator for SmallVec<A, Al> {
+    type Item = A::Item;
+    type IntoIter = IntoIter<A, Al>;
+
+    #[inline]
+    fn into_iter(mut self) -> Self::IntoIter {
+        let end = self.len();
+        // The elements are moved out by the iterator, and the ones left are
+        // dropped by the iterator.
+        unsafe {
+            self.set_len(0);
+        }
+        IntoIter {
+            vec: self,
+            start: 0,
+            end,
+        }
+    }
+}
+
+impl<'a, A: Array, Al: Allocator> IntoIterator for &'a SmallVec<A, Al> {
+    type Item = &'a A::Item;
+    type IntoIter = slice::Iter<'a, A::Item>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, A: Array, Al: Allocator> IntoIterator for &'a mut SmallVec<A, Al> {
+    type Item = &'a mut A::Item;
+    type IntoIter = slice::IterMut<'a, A::Item>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// An iterator that moves out of a [`SmallVec`].
+///
+/// This `struct` is created by the `into_iter` method on [`SmallVec`]
+/// (provided by the [`IntoIterator`] trait).
+pub struct IntoIter<A: Array, Al: Allocator = Global> {
+    vec: SmallVec<A, Al>,
+    start: usize,
+    end: usize,
+}
+
+impl<A: Array, Al: Allocator> IntoIter<A, Al> {
+    /// Returns the remaining items of this iterator as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[A::Item] {
+        unsafe { slice::from_raw_parts(self.vec.as_ptr().add(self.start), self.end - self.start) }
+    }
+
+    /// Returns the remaining items of this iterator as a mutable slice.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [A::Item] {
+        unsafe { slice::from_raw_parts_mut(self.vec.as_mut_ptr().add(self.start), self.end - self.start) }
+    }
+}
+
+impl<A: Array, Al: Allocator> fmt::Debug for IntoIter<A, Al>
+where
+    A::Item: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
+    }
+}
+
+impl<A: Array, Al: Allocator> Iterator for IntoIter<A, Al> {
+    type Item = A::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<A::Item> {
+        if self.start == self.end {
+            None
+        } else {
+            let item = unsafe { ptr::read(self.vec.as_ptr().add(self.start)) };
+            self.start += 1;
+            Some(item)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<A: Array, Al: Allocator> DoubleEndedIterator for IntoIter<A, Al> {
+    #[inline]
+    fn next_back(&mut self) -> Option<A::Item> {
+        if self.start == self.end {
+            None
+        } else {
+            self.end -= 1;
+            Some(unsafe { ptr::read(self.vec.as_ptr().add(self.end)) })
+        }
+    }
+}
+
+impl<A: Array, Al: Allocator> ExactSizeIterator for IntoIter<A, Al> {}
+
+impl<A: Array, Al: Allocator> FusedIterator for IntoIter<A, Al> {}
+
+impl<A: Array, Al: Allocator> Drop for IntoIter<A, Al> {
+    #[inline]
+    fn drop(&mut self) {
+        // destroy the remaining elements, the buffer is freed by the vector
+        unsafe {
+            ptr::drop_in_place(self.as_mut_slice());
+        }
+    }
+}
+
+/// A draining iterator for [`SmallVec`].
+///
+/// This `struct` is created by [`SmallVec::drain`].
+pub struct Drain<'a, A: Array, Al: Allocator = Global> {
+    /// The next item to yield.
+    ptr: NonNull<A::Item>,
+    remaining: usize,
+    tail_start: usize,
+    tail_len: usize,
+    vec: NonNull<SmallVec<A, Al>>,
+    phantom: PhantomData<&'a mut SmallVec<A, Al>>,
+}
+
+unsafe impl<A: Array, Al: Allocator + Send> Send for Drain<'_, A, Al> where A::Item: Send {}
+unsafe impl<A: Array, Al: Allocator + Sync> Sync for Drain<'_, A, Al> where A::Item: Sync {}
+
+impl<A: Array, Al: Allocator> Drain<'_, A, Al> {
+    /// Returns the remaining items of this iterator as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[A::Item] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.remaining) }
+    }
+}
+
+impl<A: Array, Al: Allocator> fmt::Debug for Drain<'_, A, Al>
+where
+    A::Item: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Drain").field(&self.as_slice()).finish()
+    }
+}
+
+impl<A: Array, Al: Allocator> Iterator for Drain<'_, A, Al> {
+    type Item = A::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<A::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        unsafe {
+            let item = ptr::read(self.ptr.as_ptr());
+            self.ptr = self.ptr.add(1);
+            self.remaining -= 1;
+            Some(item)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<A: Array, Al: Allocator> DoubleEndedIterator for Drain<'_, A, Al> {
+    #[inline]
+    fn next_back(&mut self) -> Option<A::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        unsafe { Some(ptr::read(self.ptr.as_ptr().add(self.remaining))) }
+    }
+}
+
+impl<A: Array, Al: Allocator> ExactSizeIterator for Drain<'_, A, Al> {}
+
+impl<A: Array, Al: Allocator> FusedIterator for Drain<'_, A, Al> {}
+
+impl<A: Array, Al: Allocator> Drop for Drain<'_, A, Al> {
+    #[inline]
+    fn drop(&mut self) {
+        /// Moves the tail back, even if dropping an item panics.
+        struct DropGuard<'r, 'a, A: Array, Al: Allocator>(&'r mut Drain<'a, A, Al>);
+
+        impl<A: Array, Al: Allocator> Drop for DropGuard<'_, '_, A, Al> {
+            #[inline]
+            fn drop(&mut self) {
+                unsafe {
+                    let vec = self.0.vec.as_mut();
+                    let start = vec.len();
+                    if self.0.tail_start != start {
+                        let ptr = vec.as_mut_ptr();
+                        ptr::copy(ptr.add(self.0.tail_start), ptr.add(start), self.0.tail_len);
+                    }
+                    vec.set_len(start + self.0.tail_len);
+                }
+            }
+        }
+
+        let remaining = ptr::slice_from_raw_parts_mut(self.ptr.as_ptr(), mem::replace(&mut self.remaining, 0));
+        let _guard = DropGuard(self);
+        unsafe {
+            ptr::drop_in_place(remaining);
+        }
+    }
+}
+
+/// Moves the tail of the vector back after the replaced range on drop,
+/// including when the iterator panics or growing the vector fails.
+struct Splice<'a, A: Array, Al: Allocator> {
+    vec: &'a mut SmallVec<A, Al>,
+    tail_start: usize,
+    tail_len: usize,
+}
+
+impl<A: Array, Al: Allocator> Splice<'_, A, Al> {
+    /// Fills the gap with items from `replace_with`.
+    /// Returns `true` if the gap was filled, `false` if the iterator ran out.
+    #[inline]
+    unsafe fn fill<I: Iterator<Item = A::Item>>(&mut self, replace_with: &mut I) -> bool {
+        while self.vec.len() < self.tail_start {
+            match replace_with.next() {
+                Some(value) => {
+                    let len = self.vec.len();
+                    ptr::write(self.vec.as_mut_ptr().add(len), value);
+                    self.vec.set_len(len + 1);
+                }
+                None => return false,
+            }
+        }
+        true
+    }
+
+    /// Makes room for inserting `additional` more elements before the tail.
+    ///
+    /// The gap must be filled. The tail is counted in the length while
+    /// reserving, so that it is moved along when the vector spills.
+    #[inline]
+    unsafe fn try_move_tail(&mut self, additional: usize) -> Result<(), AllocError> {
+        let len = self.vec.len();
+        debug_assert_eq!(len, self.tail_start);
+        self.vec.set_len(len + self.tail_len);
+        let res = self.vec.try_reserve(additional);
+        self.vec.set_len(len);
+        res?;
+
+        let new_tail_start = self.tail_start + additional;
+        let ptr = self.vec.as_mut_ptr();
+        ptr::copy(ptr.add(self.tail_start), ptr.add(new_tail_start), self.tail_len);
+        self.tail_start = new_tail_start;
+        Ok(())
+    }
+}
+
+impl<A: Array, Al: Allocator> Drop for Splice<'_, A, Al> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            let start = self.vec.len();
+            if self.tail_start != start {
+                let ptr = self.vec.as_mut_ptr();
+                ptr::copy(ptr.add(self.tail_start), ptr.add(start), self.tail_len);
+            }
+            self.vec.set_len(start + self.tail_len);
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use super::{Array, SmallVec};
+    use serde_crate::de::{Error, SeqAccess, Visitor};
+    use serde_crate::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::fmt;
+    use std::marker::PhantomData;
+
+    impl<A: Array> Serialize for SmallVec<A>
+    where
+        A::Item: Serialize,
+    {
+        #[inline]
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_seq(self)
+        }
+    }
+
+    impl<'de, A: Array> Deserialize<'de> for SmallVec<A>
+    where
+        A::Item: Deserialize<'de>,
+    {
+        #[inline]
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            struct SmallVecVisitor<A> {
+                _marker: PhantomData<A>,
+            }
+
+            impl<'de, A: Array> Visitor<'de> for SmallVecVisitor<A>
+            where
+                A::Item: Deserialize<'de>,
+            {
+                type Value = SmallVec<A>;
+
+                #[inline]
+                fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.write_str("a sequence")
+                }
+
+                #[inline]
+                fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+                where
+                    S: SeqAccess<'de>,
+                {
+                    let cap = seq.size_hint().unwrap_or(0).min(4096);
+                    let mut values = SmallVec::try_with_capacity(cap).map_err(S::Error::custom)?;
+
+                    while let Some(value) = seq.next_element()? {
+                        values.try_push(value).map_err(S::Error::custom)?;
+                    }
+
+                    Ok(values)
+                }
+            }
+
+            let visitor = SmallVecVisitor { _marker: PhantomData };
+            deserializer.deserialize_seq(visitor)
+        }
+    }
+}
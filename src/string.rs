@@ -4,10 +4,16 @@ pub use std::string::FromUtf8Error;
 
 use crate::alloc::AllocError;
 use crate::borrow::{Cow, TryToOwned};
+use crate::boxed::Box;
 use crate::clone::TryClone;
-use crate::vec::Vec;
+use crate::iter::{self, TryFromIterator};
+use crate::mem::HeapSize;
+use crate::vec::{Doubling, GrowthPolicy, Vec};
+use std::alloc::Global;
 use std::borrow::Borrow;
+use std::cmp;
 use std::fmt;
+use std::marker::PhantomData;
 use std::mem;
 use std::ops;
 use std::str;
@@ -18,15 +24,17 @@ use std::string::String as StdString;
 /// The `String` type is the most common string type that has ownership over the
 /// contents of the string. It has a close relationship with its borrowed
 /// counterpart, the primitive `str`.
-#[derive(Ord, PartialOrd, Eq, PartialEq)]
+///
+/// The growth policy `G` decides how much the capacity grows when the string
+/// runs out of space, see [`GrowthPolicy`].
 #[repr(transparent)]
-pub struct String(StdString);
+pub struct String<G: GrowthPolicy = Doubling>(StdString, PhantomData<G>);
 
 impl String {
     /// Creates a new empty `String`.
     #[inline]
     pub const fn new() -> String {
-        String(StdString::new())
+        String(StdString::new(), PhantomData)
     }
 
     /// Creates a new empty `String` with a particular capacity.
@@ -34,19 +42,81 @@ impl String {
     pub fn try_with_capacity(capacity: usize) -> Result<String, AllocError> {
         let mut s = StdString::new();
         s.try_reserve(capacity)?;
-        Ok(String(s))
+        Ok(String(s, PhantomData))
+    }
+
+    /// Creates a new `String` from a pointer, a length and a capacity.
+    ///
+    /// # Safety
+    ///
+    /// This is highly unsafe, due to the number of invariants that aren't
+    /// checked, see [`std::string::String::from_raw_parts`]. The parts are
+    /// usually obtained from [`String::into_raw_parts`].
+    #[inline]
+    pub unsafe fn from_raw_parts(buf: *mut u8, length: usize, capacity: usize) -> String {
+        String(StdString::from_raw_parts(buf, length, capacity), PhantomData)
     }
 
     #[inline]
     pub fn from_std(s: StdString) -> Self {
-        String(s)
+        String(s, PhantomData)
     }
+}
 
+impl<G: GrowthPolicy> String<G> {
     #[inline]
     pub fn into_std(self) -> StdString {
         self.0
     }
 
+    /// Decomposes a `String` into its raw components.
+    ///
+    /// Returns the raw pointer to the underlying data, the length of
+    /// the string (in bytes), and the allocated capacity of the data
+    /// (in bytes). After calling this function, the caller is responsible
+    /// for the memory previously managed by the `String`. The only way to do
+    /// this is to convert the raw pointer, length, and capacity back into a
+    /// `String` with the [`from_raw_parts`] function, allowing the destructor
+    /// to perform the cleanup.
+    ///
+    /// [`from_raw_parts`]: String::from_raw_parts
+    #[inline]
+    pub fn into_raw_parts(self) -> (*mut u8, usize, usize) {
+        self.into_bytes().into_raw_parts()
+    }
+
+    /// Consumes and leaks the `String`, returning a mutable reference to the contents,
+    /// `&'a mut str`.
+    ///
+    /// This function is mainly useful for data that lives for the remainder of
+    /// the program's life. Dropping the returned reference will cause a memory
+    /// leak.
+    #[inline]
+    pub fn leak<'a>(self) -> &'a mut str {
+        self.0.leak()
+    }
+
+    /// Shrinks the capacity of the string to its length, then consumes and
+    /// leaks it, returning a mutable reference to the contents.
+    ///
+    /// Unlike [`leak`], the allocation of the returned string slice is exactly
+    /// the size of the slice, so it can be reclaimed by `Box::from_raw`.
+    ///
+    /// [`leak`]: String::leak
+    #[inline]
+    pub fn try_leak<'a>(mut self) -> Result<&'a mut str, AllocError> {
+        self.try_shrink_to_fit()?;
+        Ok(self.leak())
+    }
+
+    /// Converts the string into a string with the growth policy `P`.
+    ///
+    /// This does not touch the buffer of the string.
+    #[inline]
+    pub fn with_growth_policy<P: GrowthPolicy>(self) -> String<P> {
+        String(self.0, PhantomData)
+    }
+
     /// Converts a vector of bytes to a `String`.
     ///
     /// A string ([`String`]) is made of bytes ([`u8`]), and a vector of bytes
@@ -73,8 +143,8 @@ impl String {
     /// Returns [`Err`] if the slice is not UTF-8 with a description as to why the
     /// provided bytes are not UTF-8. The vector you moved in is also included.
     #[inline]
-    pub fn from_utf8(vec: Vec<u8>) -> Result<String, FromUtf8Error> {
-        Ok(String(StdString::from_utf8(vec.into_std())?))
+    pub fn from_utf8(vec: Vec<u8, Global, G>) -> Result<String<G>, FromUtf8Error> {
+        Ok(String(StdString::from_utf8(vec.into_std())?, PhantomData))
     }
 
     /// Converts a vector of bytes to a `String` without checking that the
@@ -92,8 +162,8 @@ impl String {
     /// the standard library assumes that `String`s are valid UTF-8.
     #[must_use]
     #[inline]
-    pub unsafe fn from_utf8_unchecked(bytes: Vec<u8>) -> String {
-        String(StdString::from_utf8_unchecked(bytes.into_std()))
+    pub unsafe fn from_utf8_unchecked(bytes: Vec<u8, Global, G>) -> String<G> {
+        String(StdString::from_utf8_unchecked(bytes.into_std()), PhantomData)
     }
 
     /// Converts a `String` into a byte vector.
@@ -101,8 +171,8 @@ impl String {
     /// This consumes the `String`, so we do not need to copy its contents.
     #[inline]
     #[must_use = "`self` will be dropped if the result is not used"]
-    pub fn into_bytes(self) -> Vec<u8> {
-        Vec::from_std(self.0.into_bytes())
+    pub fn into_bytes(self) -> Vec<u8, Global, G> {
+        Vec::from_std(self.0.into_bytes()).with_growth_policy()
     }
 
     /// Returns this `String`'s capacity, in bytes.
@@ -152,7 +222,7 @@ impl String {
     /// safety, as the rest of the standard library assumes that `String`s are
     /// valid UTF-8.
     #[inline]
-    pub unsafe fn as_mut_vec(&mut self) -> &mut Vec<u8> {
+    pub unsafe fn as_mut_vec(&mut self) -> &mut Vec<u8, Global, G> {
         // Vec has the same memory layout as StdVec
         std::mem::transmute(self.0.as_mut_vec())
     }
@@ -160,11 +230,41 @@ impl String {
     /// Appends a given string slice onto the end of this `String`.
     #[inline]
     pub fn try_push_str(&mut self, string: &str) -> Result<(), AllocError> {
-        self.0.try_reserve(string.len())?;
+        self.try_reserve(string.len())?;
         self.0.push_str(string);
         Ok(())
     }
 
+    /// Appends a given string slice onto the end of this `String` if there is
+    /// sufficient spare capacity, otherwise an error is returned with the slice.
+    ///
+    /// Unlike [`try_push_str`], this method never allocates.
+    ///
+    /// [`try_push_str`]: String::try_push_str
+    #[inline]
+    pub fn push_str_within_capacity<'a>(&mut self, string: &'a str) -> Result<(), &'a str> {
+        if self.capacity() - self.len() < string.len() {
+            return Err(string);
+        }
+        self.0.push_str(string);
+        Ok(())
+    }
+
+    /// Appends the given [`char`] to the end of this `String` if there is
+    /// sufficient spare capacity, otherwise an error is returned with the `char`.
+    ///
+    /// Unlike [`try_push`], this method never allocates.
+    ///
+    /// [`try_push`]: String::try_push
+    #[inline]
+    pub fn push_within_capacity(&mut self, ch: char) -> Result<(), char> {
+        if self.capacity() - self.len() < ch.len_utf8() {
+            return Err(ch);
+        }
+        self.0.push(ch);
+        Ok(())
+    }
+
     /// Tries to reserve capacity for at least `additional` more elements to be inserted
     /// in the given `String`. The collection may reserve more space to avoid
     /// frequent reallocations. After calling `reserve`, capacity will be
@@ -177,8 +277,7 @@ impl String {
     /// is returned.
     #[inline]
     pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
-        self.0.try_reserve(additional)?;
-        Ok(())
+        unsafe { self.as_mut_vec().try_reserve(additional) }
     }
 
     /// Tries to reserve the minimum capacity for exactly `additional` more elements to
@@ -202,6 +301,38 @@ impl String {
         Ok(())
     }
 
+    /// Shrinks the capacity of this `String` to match its length.
+    ///
+    /// If the allocator reports a failure, the string is left unchanged.
+    #[inline]
+    pub fn try_shrink_to_fit(&mut self) -> Result<(), AllocError> {
+        unsafe { self.as_mut_vec().try_shrink_to_fit() }
+    }
+
+    /// Shrinks the capacity of this `String` with a lower bound.
+    ///
+    /// The capacity will remain at least as large as both the length
+    /// and the supplied value.
+    ///
+    /// If the current capacity is less than the lower limit, this is a no-op.
+    /// If the allocator reports a failure, the string is left unchanged.
+    #[inline]
+    pub fn try_shrink_to(&mut self, min_capacity: usize) -> Result<(), AllocError> {
+        unsafe { self.as_mut_vec().try_shrink_to(min_capacity) }
+    }
+
+    /// Converts this `String` into a `Box<str>`.
+    ///
+    /// If the string has excess capacity, its contents will be moved into a
+    /// newly-allocated buffer with exactly the right capacity.
+    #[inline]
+    pub fn try_into_boxed_str(self) -> Result<Box<str>, AllocError> {
+        let slice = self.into_bytes().try_into_boxed_slice()?;
+        Ok(Box::from_std(unsafe {
+            str::from_boxed_utf8_unchecked(slice.into_std())
+        }))
+    }
+
     /// Appends the given [`char`] to the end of this `String`.
     #[inline]
     pub fn try_push(&mut self, ch: char) -> Result<(), AllocError> {
@@ -252,7 +383,7 @@ impl String {
 macro_rules! impl_eq {
     ($lhs:ty, $rhs: ty) => {
         #[allow(unused_lifetimes)]
-        impl<'a, 'b> PartialEq<$rhs> for $lhs {
+        impl<'a, 'b, G: GrowthPolicy> PartialEq<$rhs> for $lhs {
             #[inline]
             fn eq(&self, other: &$rhs) -> bool {
                 PartialEq::eq(&self[..], &other[..])
@@ -260,7 +391,7 @@ macro_rules! impl_eq {
         }
 
         #[allow(unused_lifetimes)]
-        impl<'a, 'b> PartialEq<$lhs> for $rhs {
+        impl<'a, 'b, G: GrowthPolicy> PartialEq<$lhs> for $rhs {
             #[inline]
             fn eq(&self, other: &$lhs) -> bool {
                 PartialEq::eq(&self[..], &other[..])
@@ -269,10 +400,10 @@ macro_rules! impl_eq {
     };
 }
 
-impl_eq! { String, str }
-impl_eq! { String, &'a str }
+impl_eq! { String<G>, str }
+impl_eq! { String<G>, &'a str }
 
-impl ops::Index<ops::Range<usize>> for String {
+impl<G: GrowthPolicy> ops::Index<ops::Range<usize>> for String<G> {
     type Output = str;
 
     #[inline]
@@ -281,7 +412,7 @@ impl ops::Index<ops::Range<usize>> for String {
     }
 }
 
-impl ops::Index<ops::RangeTo<usize>> for String {
+impl<G: GrowthPolicy> ops::Index<ops::RangeTo<usize>> for String<G> {
     type Output = str;
 
     #[inline]
@@ -290,7 +421,7 @@ impl ops::Index<ops::RangeTo<usize>> for String {
     }
 }
 
-impl ops::Index<ops::RangeFrom<usize>> for String {
+impl<G: GrowthPolicy> ops::Index<ops::RangeFrom<usize>> for String<G> {
     type Output = str;
 
     #[inline]
@@ -299,7 +430,7 @@ impl ops::Index<ops::RangeFrom<usize>> for String {
     }
 }
 
-impl ops::Index<ops::RangeFull> for String {
+impl<G: GrowthPolicy> ops::Index<ops::RangeFull> for String<G> {
     type Output = str;
 
     #[inline]
@@ -308,7 +439,7 @@ impl ops::Index<ops::RangeFull> for String {
     }
 }
 
-impl ops::Index<ops::RangeInclusive<usize>> for String {
+impl<G: GrowthPolicy> ops::Index<ops::RangeInclusive<usize>> for String<G> {
     type Output = str;
 
     #[inline]
@@ -317,7 +448,7 @@ impl ops::Index<ops::RangeInclusive<usize>> for String {
     }
 }
 
-impl ops::Index<ops::RangeToInclusive<usize>> for String {
+impl<G: GrowthPolicy> ops::Index<ops::RangeToInclusive<usize>> for String<G> {
     type Output = str;
 
     #[inline]
@@ -326,49 +457,49 @@ impl ops::Index<ops::RangeToInclusive<usize>> for String {
     }
 }
 
-impl ops::IndexMut<ops::Range<usize>> for String {
+impl<G: GrowthPolicy> ops::IndexMut<ops::Range<usize>> for String<G> {
     #[inline]
     fn index_mut(&mut self, index: ops::Range<usize>) -> &mut str {
         &mut self[..][index]
     }
 }
 
-impl ops::IndexMut<ops::RangeTo<usize>> for String {
+impl<G: GrowthPolicy> ops::IndexMut<ops::RangeTo<usize>> for String<G> {
     #[inline]
     fn index_mut(&mut self, index: ops::RangeTo<usize>) -> &mut str {
         &mut self[..][index]
     }
 }
 
-impl ops::IndexMut<ops::RangeFrom<usize>> for String {
+impl<G: GrowthPolicy> ops::IndexMut<ops::RangeFrom<usize>> for String<G> {
     #[inline]
     fn index_mut(&mut self, index: ops::RangeFrom<usize>) -> &mut str {
         &mut self[..][index]
     }
 }
 
-impl ops::IndexMut<ops::RangeFull> for String {
+impl<G: GrowthPolicy> ops::IndexMut<ops::RangeFull> for String<G> {
     #[inline]
     fn index_mut(&mut self, _index: ops::RangeFull) -> &mut str {
         self.as_mut_str()
     }
 }
 
-impl ops::IndexMut<ops::RangeInclusive<usize>> for String {
+impl<G: GrowthPolicy> ops::IndexMut<ops::RangeInclusive<usize>> for String<G> {
     #[inline]
     fn index_mut(&mut self, index: ops::RangeInclusive<usize>) -> &mut str {
         self.0.index_mut(index)
     }
 }
 
-impl ops::IndexMut<ops::RangeToInclusive<usize>> for String {
+impl<G: GrowthPolicy> ops::IndexMut<ops::RangeToInclusive<usize>> for String<G> {
     #[inline]
     fn index_mut(&mut self, index: ops::RangeToInclusive<usize>) -> &mut str {
         self.0.index_mut(index)
     }
 }
 
-impl ops::Deref for String {
+impl<G: GrowthPolicy> ops::Deref for String<G> {
     type Target = str;
 
     #[inline]
@@ -377,28 +508,58 @@ impl ops::Deref for String {
     }
 }
 
-impl ops::DerefMut for String {
+impl<G: GrowthPolicy> ops::DerefMut for String<G> {
     #[inline]
     fn deref_mut(&mut self) -> &mut str {
         self.0.deref_mut()
     }
 }
 
-impl fmt::Display for String {
+impl<G: GrowthPolicy> PartialEq for String<G> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<G: GrowthPolicy> Eq for String<G> {}
+
+impl<G: GrowthPolicy> PartialOrd for String<G> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<G: GrowthPolicy> Ord for String<G> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        Ord::cmp(&self.0, &other.0)
+    }
+}
+
+impl<G: GrowthPolicy> fmt::Display for String<G> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Display::fmt(&self.0, f)
     }
 }
 
-impl fmt::Debug for String {
+impl<G: GrowthPolicy> HeapSize for String<G> {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl<G: GrowthPolicy> fmt::Debug for String<G> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Debug::fmt(&self.0, f)
     }
 }
 
-impl fmt::Write for String {
+impl<G: GrowthPolicy> fmt::Write for String<G> {
     #[inline]
     fn write_str(&mut self, s: &str) -> fmt::Result {
         self.try_push_str(s).map_err(|_| fmt::Error)
@@ -410,17 +571,17 @@ impl fmt::Write for String {
     }
 }
 
-impl Borrow<str> for String {
+impl<G: GrowthPolicy> Borrow<str> for String<G> {
     #[inline]
     fn borrow(&self) -> &str {
         self
     }
 }
 
-impl TryClone for String {
+impl<G: GrowthPolicy> TryClone for String<G> {
     #[inline]
     fn try_clone(&self) -> Result<Self, AllocError> {
-        let mut s = String::new();
+        let mut s = String::try_with_capacity(self.len())?.with_growth_policy();
         s.try_push_str(self)?;
         Ok(s)
     }
@@ -433,21 +594,21 @@ impl TryClone for String {
     }
 }
 
-impl AsRef<str> for String {
+impl<G: GrowthPolicy> AsRef<str> for String<G> {
     #[inline]
     fn as_ref(&self) -> &str {
         self
     }
 }
 
-impl AsMut<str> for String {
+impl<G: GrowthPolicy> AsMut<str> for String<G> {
     #[inline]
     fn as_mut(&mut self) -> &mut str {
         self
     }
 }
 
-impl AsRef<[u8]> for String {
+impl<G: GrowthPolicy> AsRef<[u8]> for String<G> {
     #[inline]
     fn as_ref(&self) -> &[u8] {
         self.as_bytes()
@@ -511,6 +672,64 @@ impl<'a> From<&'a String> for Cow<'a, str> {
     }
 }
 
+impl TryFromIterator<char> for String {
+    type Error = AllocError;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = char>>(iter: I) -> Result<Self, Self::Error> {
+        let iter = iter.into_iter();
+        let mut s = String::try_with_capacity(iter.size_hint().0)?;
+        for ch in iter {
+            s.try_push(ch)?;
+        }
+        Ok(s)
+    }
+}
+
+impl<'a> TryFromIterator<&'a str> for String {
+    type Error = AllocError;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Result<Self, Self::Error> {
+        let mut s = String::new();
+        for string in iter {
+            s.try_push_str(string)?;
+        }
+        Ok(s)
+    }
+}
+
+impl TryFromIterator<String> for String {
+    type Error = AllocError;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = String>>(iter: I) -> Result<Self, Self::Error> {
+        let mut iter = iter.into_iter();
+        match iter.next() {
+            None => Ok(String::new()),
+            Some(mut buf) => {
+                for string in iter {
+                    buf.try_push_str(&string)?;
+                }
+                Ok(buf)
+            }
+        }
+    }
+}
+
+impl<T, E> TryFromIterator<Result<T, E>> for String
+where
+    String: TryFromIterator<T, Error = AllocError>,
+    E: From<AllocError>,
+{
+    type Error = E;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = Result<T, E>>>(iter: I) -> Result<Self, Self::Error> {
+        iter::try_process(iter.into_iter(), |shunt| String::try_from_iter(shunt))
+    }
+}
+
 /// A trait for converting a value to a `String`.
 pub trait TryToString {
     /// Converts the given value to a `String`.
@@ -0,0 +1,102 @@
+use std::cmp;
+
+/// A strategy deciding how much the capacity of a buffer grows when it runs
+/// out of space.
+///
+/// The policy is consulted by the amortized growth paths of [`Vec`] and
+/// [`String`], i.e. `try_reserve` and the methods built on it, such as `try_push`.
+/// `try_reserve_exact` always allocates exactly the requested capacity.
+///
+/// [`Vec`]: super::Vec
+/// [`String`]: crate::string::String
+pub trait GrowthPolicy {
+    /// Returns the new capacity of a buffer holding `capacity` elements of
+    /// `elem_size` bytes each, which needs room for at least `required` elements.
+    ///
+    /// A returned capacity less than `required` is treated as `required`.
+    fn grow(capacity: usize, required: usize, elem_size: usize) -> usize;
+}
+
+/// Doubles the capacity, the same strategy as `std::vec::Vec`.
+///
+/// This is the default growth policy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Doubling;
+
+impl GrowthPolicy for Doubling {
+    #[inline]
+    fn grow(capacity: usize, required: usize, elem_size: usize) -> usize {
+        cmp::max(
+            cmp::max(capacity.saturating_mul(2), required),
+            min_non_zero_cap(elem_size),
+        )
+    }
+}
+
+/// Grows the capacity to exactly the required size.
+///
+/// This minimizes memory usage, at the cost of reallocating on every growth.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Exact;
+
+impl GrowthPolicy for Exact {
+    #[inline]
+    fn grow(_capacity: usize, required: usize, _elem_size: usize) -> usize {
+        required
+    }
+}
+
+/// Grows the capacity by a fixed number of `N` elements.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FixedIncrement<const N: usize>;
+
+impl<const N: usize> GrowthPolicy for FixedIncrement<N> {
+    #[inline]
+    fn grow(capacity: usize, required: usize, _elem_size: usize) -> usize {
+        cmp::max(capacity.saturating_add(N), required)
+    }
+}
+
+/// Doubles the capacity until the buffer reaches `THRESHOLD` bytes, then grows
+/// it by the ratio `NUM / DEN`, 1.5 by default.
+///
+/// This keeps large buffers from doubling into an allocation that is likely
+/// to fail.
+///
+/// The ratio must be greater than 1, i.e. `DEN` must be non-zero and `NUM`
+/// greater than `DEN`. This is checked at compile time when the policy is used.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CappedRatio<const THRESHOLD: usize, const NUM: usize = 3, const DEN: usize = 2>;
+
+impl<const THRESHOLD: usize, const NUM: usize, const DEN: usize> GrowthPolicy for CappedRatio<THRESHOLD, NUM, DEN> {
+    #[inline]
+    fn grow(capacity: usize, required: usize, elem_size: usize) -> usize {
+        const { assert!(DEN != 0 && NUM > DEN, "the growth ratio must be greater than 1") };
+
+        if capacity.saturating_mul(elem_size) < THRESHOLD {
+            Doubling::grow(capacity, required, elem_size)
+        } else {
+            let cap = match capacity.checked_mul(NUM) {
+                Some(n) => n / DEN,
+                None => capacity / DEN * NUM,
+            };
+            cmp::max(cap, required)
+        }
+    }
+}
+
+/// Tiny vectors are dumb, skip to:
+/// - 8 if the element size is 1, because any heap allocators is likely
+///   to round up a request of less than 8 bytes to at least 8 bytes.
+/// - 4 if elements are moderate-sized (<= 1 KiB).
+/// - 1 otherwise, to avoid wasting too much space for very short vectors.
+#[inline]
+const fn min_non_zero_cap(elem_size: usize) -> usize {
+    if elem_size == 1 {
+        8
+    } else if elem_size <= 1024 {
+        4
+    } else {
+        1
+    }
+}
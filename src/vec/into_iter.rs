@@ -0,0 +1,356 @@
+//! The owning iterator of `Vec`, and the in-place collection built on it.
+//!
+//! In-place collection relies on rustc-internal, permanently unstable parts of
+//! std: pipelines are recognized through `std::iter::SourceIter` (the
+//! `inplace_iteration` feature), and `AsIntoIter` and `InPlaceIterable` are
+//! marked `#[rustc_specialization_trait]` (the `rustc_attrs` feature) so that
+//! `Vec::try_from_iter` can specialize on them. `IntoIter::try_map_in_place`
+//! does not depend on either.
+
+use super::{GrowthPolicy, Vec};
+use crate::alloc::AllocError;
+use std::alloc::{Allocator, Global};
+use std::fmt;
+use std::iter::{
+    Enumerate, Filter, FilterMap, FusedIterator, Inspect, Map, MapWhile, Scan, Skip, SkipWhile, SourceIter, Take,
+    TakeWhile,
+};
+use std::marker::PhantomData;
+use std::mem::{self, ManuallyDrop};
+use std::ptr::{self, NonNull};
+use std::slice;
+use std::vec::Vec as StdVec;
+
+/// An iterator that moves out of a vector.
+///
+/// This `struct` is created by the `into_iter` method on [`Vec`]
+/// (provided by the [`IntoIterator`] trait).
+pub struct IntoIter<T, A: Allocator = Global> {
+    buf: NonNull<T>,
+    cap: usize,
+    alloc: ManuallyDrop<A>,
+    start: usize,
+    end: usize,
+    phantom: PhantomData<T>,
+}
+
+unsafe impl<T: Send, A: Allocator + Send> Send for IntoIter<T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for IntoIter<T, A> {}
+
+impl<T, A: Allocator> IntoIter<T, A> {
+    #[inline]
+    pub(super) fn new<G: GrowthPolicy>(vec: Vec<T, A, G>) -> Self {
+        let (ptr, len, cap, alloc) = vec.into_std().into_raw_parts_with_alloc();
+        IntoIter {
+            // SAFETY: the pointer of a vector is never null.
+            buf: unsafe { NonNull::new_unchecked(ptr) },
+            cap,
+            alloc: ManuallyDrop::new(alloc),
+            start: 0,
+            end: len,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the remaining items of this iterator as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.buf.as_ptr().add(self.start), self.len()) }
+    }
+
+    /// Returns the remaining items of this iterator as a mutable slice.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.buf.as_ptr().add(self.start), self.len()) }
+    }
+
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Maps the remaining items of this iterator with `f` and collects the
+    /// results into a `Vec<U, A>`.
+    ///
+    /// If `T` and `U` have the same size and alignment, the results are written
+    /// into the source buffer and no allocation happens. Otherwise a new buffer
+    /// is allocated for the results, and an error is returned if the allocation
+    /// fails.
+    #[inline]
+    pub fn try_map_in_place<U, F>(mut self, mut f: F) -> Result<Vec<U, A>, AllocError>
+    where
+        F: FnMut(T) -> U,
+    {
+        if mem::size_of::<T>() != mem::size_of::<U>() || mem::align_of::<T>() != mem::align_of::<U>() {
+            let mut dst = Vec::<U, &A>::try_with_capacity_in(self.len(), &*self.alloc)?;
+            while self.start < self.end {
+                unsafe {
+                    let item = ptr::read(self.buf.as_ptr().add(self.start));
+                    self.start += 1;
+                    let len = dst.len();
+                    ptr::write(dst.as_mut_ptr().add(len), f(item));
+                    dst.set_len(len + 1);
+                }
+            }
+
+            let (ptr, len, cap, _) = dst.into_std().into_raw_parts_with_alloc();
+            let mut this = ManuallyDrop::new(self);
+            unsafe {
+                this.free_buffer();
+                let alloc = ManuallyDrop::take(&mut this.alloc);
+                return Ok(Vec::from_std(StdVec::from_raw_parts_in(ptr, len, cap, alloc)));
+            }
+        }
+
+        let mut sink = InPlaceDrop {
+            iter: self,
+            dst: 0,
+            phantom: PhantomData::<U>,
+        };
+        while let Some(item) = sink.iter.next_in_place() {
+            let value = f(item);
+            unsafe {
+                ptr::write(sink.iter.buf.as_ptr().cast::<U>().add(sink.dst), value);
+            }
+            sink.dst += 1;
+        }
+
+        let mut sink = ManuallyDrop::new(sink);
+        // All items of the source have been moved out, take over its buffer.
+        unsafe {
+            let alloc = ManuallyDrop::take(&mut sink.iter.alloc);
+            let buf = sink.iter.buf.as_ptr().cast::<U>();
+            Ok(Vec::from_std(StdVec::from_raw_parts_in(
+                buf,
+                sink.dst,
+                sink.iter.cap,
+                alloc,
+            )))
+        }
+    }
+
+    #[inline]
+    fn next_in_place(&mut self) -> Option<T> {
+        if self.start == self.end {
+            None
+        } else {
+            let item = unsafe { ptr::read(self.buf.as_ptr().add(self.start)) };
+            self.start += 1;
+            Some(item)
+        }
+    }
+
+    /// Deallocates the buffer, without dropping the remaining items.
+    #[inline]
+    unsafe fn free_buffer(&self) {
+        drop(StdVec::from_raw_parts_in(self.buf.as_ptr(), 0, self.cap, &*self.alloc));
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for IntoIter<T, A> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T, A: Allocator> AsRef<[T]> for IntoIter<T, A> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.next_in_place()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.len()
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.start == self.end {
+            None
+        } else {
+            self.end -= 1;
+            Some(unsafe { ptr::read(self.buf.as_ptr().add(self.end)) })
+        }
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> Drop for IntoIter<T, A> {
+    #[inline]
+    fn drop(&mut self) {
+        struct DropGuard<'a, T, A: Allocator>(&'a mut IntoIter<T, A>);
+
+        impl<T, A: Allocator> Drop for DropGuard<'_, T, A> {
+            #[inline]
+            fn drop(&mut self) {
+                unsafe {
+                    self.0.free_buffer();
+                    ManuallyDrop::drop(&mut self.0.alloc);
+                }
+            }
+        }
+
+        let guard = DropGuard(self);
+        // destroy the remaining elements
+        unsafe {
+            ptr::drop_in_place(guard.0.as_mut_slice());
+        }
+        // now `guard` will be dropped and do the rest
+    }
+}
+
+// SAFETY: `IntoIter` is its own source, so `as_inner` hands out the iterator
+// that is actually being advanced, and no adapter sits in between.
+unsafe impl<T, A: Allocator> SourceIter for IntoIter<T, A> {
+    type Source = Self;
+
+    #[inline]
+    unsafe fn as_inner(&mut self) -> &mut Self {
+        self
+    }
+}
+
+/// Gives access to the `IntoIter` at the source of an iterator pipeline.
+#[rustc_specialization_trait]
+pub(super) trait AsIntoIter {
+    type Item;
+
+    fn as_into_iter(&mut self) -> &mut IntoIter<Self::Item>;
+}
+
+impl<T> AsIntoIter for IntoIter<T> {
+    type Item = T;
+
+    #[inline]
+    fn as_into_iter(&mut self) -> &mut IntoIter<T> {
+        self
+    }
+}
+
+/// Iterators over an `IntoIter` whose items can be collected into its buffer.
+///
+/// # Safety
+///
+/// The iterator must take at least one item from its source for every item
+/// it yields, so that the yielded items never overwrite the items that have
+/// not been taken yet.
+#[rustc_specialization_trait]
+pub(crate) unsafe trait InPlaceIterable: SourceIter {}
+
+// SAFETY: every item is read out of the buffer and `start` is advanced past
+// it before the item is yielded, so exactly one item is taken per item yielded.
+unsafe impl<T, A: Allocator> InPlaceIterable for IntoIter<T, A> {}
+
+macro_rules! impl_in_place_iterable {
+    ($($t: ident<I $(, $p: ident)?>),*) => {
+        $(unsafe impl<I: InPlaceIterable $(, $p)?> InPlaceIterable for $t<I $(, $p)?> {})*
+    }
+}
+
+// SAFETY: these adapters yield one item for every item they take from `I`,
+// `Skip` additionally takes and drops the skipped items up front.
+impl_in_place_iterable!(Enumerate<I>, Inspect<I, F>, Map<I, F>, Skip<I>, Take<I>);
+
+// SAFETY: these adapters take items from `I` until one passes the predicate,
+// or stop, so they yield at most one item for every item they take.
+impl_in_place_iterable!(Filter<I, P>, FilterMap<I, F>, MapWhile<I, P>, SkipWhile<I, P>, TakeWhile<I, P>);
+
+// SAFETY: `Scan` takes one item from `I` for every call of its closure, and
+// yields at most the one value that call returns.
+unsafe impl<I: InPlaceIterable, St, F> InPlaceIterable for Scan<I, St, F> {}
+
+/// Collects the items of `iter` into the buffer of its source `IntoIter`.
+///
+/// # Safety
+///
+/// `T` must have the same size and alignment as the items of the source.
+#[inline]
+pub(super) unsafe fn collect_in_place<T, I>(mut iter: I) -> Vec<T>
+where
+    I: Iterator<Item = T> + InPlaceIterable,
+    I::Source: AsIntoIter,
+{
+    let (buf, cap) = {
+        let src = iter.as_inner().as_into_iter();
+        (src.buf.cast::<T>(), src.cap)
+    };
+
+    let mut sink = InPlaceDstDrop { buf, len: 0 };
+    while let Some(item) = iter.next() {
+        debug_assert!(sink.len < iter.as_inner().as_into_iter().start);
+        ptr::write(buf.as_ptr().add(sink.len), item);
+        sink.len += 1;
+    }
+    let len = sink.len;
+    mem::forget(sink);
+
+    // Take over the buffer, and leave the source empty so that it neither
+    // drops its remaining items nor deallocates the buffer.
+    let src = iter.as_inner().as_into_iter();
+    let remaining = ptr::slice_from_raw_parts_mut(src.buf.as_ptr().add(src.start), src.end - src.start);
+    src.buf = NonNull::dangling();
+    src.cap = 0;
+    src.start = 0;
+    src.end = 0;
+
+    let vec = Vec::from_std(StdVec::from_raw_parts(buf.as_ptr(), len, cap));
+    ptr::drop_in_place(remaining);
+    vec
+}
+
+/// Drops the items collected by `collect_in_place` on unwinding, the items
+/// that have not been taken and the buffer are dropped by the source.
+struct InPlaceDstDrop<T> {
+    buf: NonNull<T>,
+    len: usize,
+}
+
+impl<T> Drop for InPlaceDstDrop<T> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.buf.as_ptr(), self.len));
+        }
+    }
+}
+
+/// Drops the already mapped items on unwinding, the remaining source items
+/// and the buffer are dropped by the source iterator.
+struct InPlaceDrop<T, U, A: Allocator> {
+    iter: IntoIter<T, A>,
+    dst: usize,
+    phantom: PhantomData<U>,
+}
+
+impl<T, U, A: Allocator> Drop for InPlaceDrop<T, U, A> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            let mapped = ptr::slice_from_raw_parts_mut(self.iter.buf.as_ptr().cast::<U>(), self.dst);
+            ptr::drop_in_place(mapped);
+        }
+    }
+}
@@ -0,0 +1,2020 @@
+//! A contiguous growable array type with heap-allocated contents, written
+//! `Vec<T>`.
+//!
+//! Vectors have *O*(1) indexing, amortized *O*(1) push (to the end) and
+//! *O*(1) pop (from the end).
+//!
+//! Vectors ensure they never allocate more than `isize::MAX` bytes.
+//!
+//! # Error guarantees
+//!
+//! When a fallible method of [`Vec`] returns an error, the vector is always
+//! left in a valid state, but the methods differ in what that state is:
+//!
+//! - Methods with the *strong* guarantee leave the vector unchanged on
+//!   error: [`try_reserve`], [`try_reserve_exact`], [`try_shrink_to_fit`],
+//!   [`try_shrink_to`], [`try_push`], [`try_insert`], [`try_append`],
+//!   [`try_split_off`], [`try_copy_from_slice`], [`try_extend_from_within`],
+//!   the `try_sort` family, and the `_atomic` methods.
+//! - Methods with the *basic* guarantee may leave the vector partially
+//!   modified: [`try_extend_from_slice`], [`try_resize`] and
+//!   [`try_resize_with`] keep the elements appended before the failure, and
+//!   [`try_splice`] keeps the elements yielded before the failure. Use
+//!   [`try_extend_from_slice_atomic`], [`try_resize_atomic`] and
+//!   [`try_resize_with_atomic`] to roll back to the original length instead.
+//!
+//! The capacity of the vector is not restored on error, and methods taking
+//! the vector by value drop it on error.
+//!
+//! # In-place collection
+//!
+//! [`IntoIter::try_map_in_place`] always reuses the source buffer when the
+//! source and target element layouts match. Collecting a pipeline of
+//! `into_iter()` adapters with `try_collect` reuses it too, but only on a
+//! best-effort basis: it relies on the rustc-internal `SourceIter` and
+//! specialization-trait machinery of std, which is permanently unstable and
+//! may change with any nightly.
+//!
+//! [`try_reserve`]: Vec::try_reserve
+//! [`try_reserve_exact`]: Vec::try_reserve_exact
+//! [`try_shrink_to_fit`]: Vec::try_shrink_to_fit
+//! [`try_shrink_to`]: Vec::try_shrink_to
+//! [`try_push`]: Vec::try_push
+//! [`try_insert`]: Vec::try_insert
+//! [`try_append`]: Vec::try_append
+//! [`try_split_off`]: Vec::try_split_off
+//! [`try_copy_from_slice`]: Vec::try_copy_from_slice
+//! [`try_extend_from_within`]: Vec::try_extend_from_within
+//! [`try_extend_from_slice`]: Vec::try_extend_from_slice
+//! [`try_resize`]: Vec::try_resize
+//! [`try_resize_with`]: Vec::try_resize_with
+//! [`try_splice`]: Vec::try_splice
+//! [`try_extend_from_slice_atomic`]: Vec::try_extend_from_slice_atomic
+//! [`try_resize_atomic`]: Vec::try_resize_atomic
+//! [`try_resize_with_atomic`]: Vec::try_resize_with_atomic
+
+pub use std::vec::ExtractIf;
+
+pub use drain::Drain;
+pub use growth::{CappedRatio, Doubling, Exact, FixedIncrement, GrowthPolicy};
+pub use into_iter::IntoIter;
+pub use uninit::UninitWriter;
+
+pub(crate) use into_iter::InPlaceIterable;
+
+mod drain;
+mod growth;
+mod into_iter;
+mod uninit;
+
+use crate::alloc::AllocError;
+use crate::boxed::Box;
+use crate::clone::TryClone;
+use crate::iter::{self, TryFromIterator};
+use crate::mem::{HeapSize, Zeroable};
+use crate::slice::SliceExt;
+use into_iter::AsIntoIter;
+use std::alloc::{Allocator, Global, Layout};
+use std::borrow::{Borrow, BorrowMut};
+use std::cmp;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io::{self, BorrowedBuf, BorrowedCursor};
+use std::iter::SourceIter;
+use std::marker::PhantomData;
+use std::mem::{self, MaybeUninit};
+use std::ops::{self, Deref, DerefMut, Index, IndexMut, RangeBounds};
+use std::ptr::{self, NonNull};
+use std::slice::{self, SliceIndex};
+use std::vec::Vec as StdVec;
+
+/// A contiguous growable array type, written as `Vec<T>`, short for 'vector'.
+///
+/// The growth policy `G` decides how much the capacity grows when the vector
+/// runs out of space, see [`GrowthPolicy`].
+#[repr(transparent)]
+pub struct Vec<T, A: Allocator = Global, G: GrowthPolicy = Doubling>(StdVec<T, A>, PhantomData<G>);
+
+impl<T> Vec<T> {
+    /// Constructs a new, empty `Vec<T>`.
+    ///
+    /// The vector will not allocate until elements are pushed onto it.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Vec(StdVec::new(), PhantomData)
+    }
+
+    /// Constructs a new, empty `Vec<T>` with the specified capacity.
+    ///
+    /// The vector will be able to hold exactly `capacity` elements without
+    /// reallocating. If `capacity` is 0, the vector will not allocate.
+    #[inline]
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, AllocError> {
+        Self::try_with_capacity_in(capacity, Global)
+    }
+
+    /// Constructs a new `Vec<T>` of length `n`, where each element is the
+    /// result of calling `f` with the element's index.
+    #[inline]
+    pub fn try_from_fn<F>(n: usize, f: F) -> Result<Self, AllocError>
+    where
+        F: FnMut(usize) -> Result<T, AllocError>,
+    {
+        Self::try_from_fn_in(n, f, Global)
+    }
+
+    /// Creates a `Vec<T>` directly from a pointer, a length, and a capacity.
+    ///
+    /// # Safety
+    ///
+    /// This is highly unsafe, due to the number of invariants that aren't
+    /// checked, see [`std::vec::Vec::from_raw_parts`]. The parts are usually
+    /// obtained from [`Vec::into_raw_parts`].
+    #[inline]
+    pub unsafe fn from_raw_parts(ptr: *mut T, length: usize, capacity: usize) -> Self {
+        Vec(StdVec::from_raw_parts(ptr, length, capacity), PhantomData)
+    }
+}
+
+impl<T: TryClone> Vec<T> {
+    /// Constructs a new `Vec<T>` of length `n`, with each element filled
+    /// with a clone of `elem`.
+    #[inline]
+    pub fn try_from_elem(elem: T, n: usize) -> Result<Self, AllocError> {
+        Self::try_from_elem_in(elem, n, Global)
+    }
+}
+
+impl<T, A: Allocator> Vec<T, A> {
+    /// Constructs a new, empty `Vec<T, A>`.
+    ///
+    /// The vector will not allocate until elements are pushed onto it.
+    #[inline]
+    pub const fn new_in(alloc: A) -> Self {
+        Vec(StdVec::new_in(alloc), PhantomData)
+    }
+
+    /// Constructs a new, empty `Vec<T, A>` with the specified capacity with the provided
+    /// allocator.
+    ///
+    /// The vector will be able to hold exactly `capacity` elements without
+    /// reallocating. If `capacity` is 0, the vector will not allocate.
+    #[inline]
+    pub fn try_with_capacity_in(capacity: usize, alloc: A) -> Result<Self, AllocError> {
+        let mut vec = Vec::new_in(alloc);
+        vec.try_reserve(capacity)?;
+        Ok(vec)
+    }
+
+    /// Constructs a new `Vec<T, A>` of length `n` with the provided allocator,
+    /// where each element is the result of calling `f` with the element's index.
+    #[inline]
+    pub fn try_from_fn_in<F>(n: usize, mut f: F, alloc: A) -> Result<Self, AllocError>
+    where
+        F: FnMut(usize) -> Result<T, AllocError>,
+    {
+        let mut vec = Vec::try_with_capacity_in(n, alloc)?;
+        let mut index = 0;
+        vec.try_extend_with(
+            n,
+            ExtendFunc(|| {
+                let value = f(index)?;
+                index += 1;
+                Ok(value)
+            }),
+        )?;
+        Ok(vec)
+    }
+
+    /// Creates a `Vec<T, A>` directly from a pointer, a length, a capacity,
+    /// and an allocator.
+    ///
+    /// # Safety
+    ///
+    /// This is highly unsafe, due to the number of invariants that aren't
+    /// checked, see [`std::vec::Vec::from_raw_parts_in`]. The parts are usually
+    /// obtained from [`Vec::into_raw_parts_with_alloc`].
+    #[inline]
+    pub unsafe fn from_raw_parts_in(ptr: *mut T, length: usize, capacity: usize, alloc: A) -> Self {
+        Vec(StdVec::from_raw_parts_in(ptr, length, capacity, alloc), PhantomData)
+    }
+
+    #[inline]
+    pub fn from_std(v: StdVec<T, A>) -> Self {
+        Vec(v, PhantomData)
+    }
+}
+
+impl<T, G: GrowthPolicy> Vec<T, Global, G> {
+    /// Decomposes a `Vec<T>` into its raw components.
+    ///
+    /// Returns the raw pointer to the underlying data, the length of
+    /// the vector (in elements), and the allocated capacity of the
+    /// data (in elements). After calling this function, the caller is
+    /// responsible for the memory previously managed by the `Vec`. The
+    /// only way to do this is to convert the raw pointer, length, and capacity
+    /// back into a `Vec` with the [`from_raw_parts`] function, allowing
+    /// the destructor to perform the cleanup.
+    ///
+    /// [`from_raw_parts`]: Vec::from_raw_parts
+    #[inline]
+    pub fn into_raw_parts(self) -> (*mut T, usize, usize) {
+        let (ptr, len, cap, _) = self.0.into_raw_parts_with_alloc();
+        (ptr, len, cap)
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
+    /// Tries to reserve capacity for at least `additional` more elements to be inserted
+    /// in the given `Vec<T>`. The collection may reserve more space to avoid
+    /// frequent reallocations. After calling `try_reserve`, capacity will be
+    /// greater than or equal to `self.len() + additional`. Does nothing if
+    /// capacity is already sufficient.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        let len = self.len();
+        if self.capacity() - len >= additional {
+            return Ok(());
+        }
+
+        let required = len.saturating_add(additional);
+        let cap = cmp::max(G::grow(self.capacity(), required, mem::size_of::<T>()), required);
+        self.0.try_reserve_exact(cap - len)?;
+        Ok(())
+    }
+
+    /// Tries to reserve the minimum capacity for exactly `additional`
+    /// elements to be inserted in the given `Vec<T>`. After calling
+    /// `try_reserve_exact`, capacity will be greater than or equal to
+    /// `self.len() + additional` if it returns `Ok(())`.
+    /// Does nothing if the capacity is already sufficient.
+    ///
+    /// Note that the allocator may give the collection more space than it
+    /// requests. Therefore, capacity can not be relied upon to be precisely
+    /// minimal.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    #[inline]
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), AllocError> {
+        self.0.try_reserve_exact(additional)?;
+        Ok(())
+    }
+
+    #[inline]
+    pub fn into_std(self) -> StdVec<T, A> {
+        self.0
+    }
+
+    /// Converts the vector into a vector with the growth policy `P`.
+    ///
+    /// This does not touch the buffer of the vector.
+    #[inline]
+    pub fn with_growth_policy<P: GrowthPolicy>(self) -> Vec<T, A, P> {
+        Vec(self.0, PhantomData)
+    }
+
+    /// Returns the number of elements the vector can hold without
+    /// reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+
+    /// Shrinks the capacity of the vector as much as possible.
+    ///
+    /// It will drop down as close as possible to the length but the allocator
+    /// may still inform the vector that there is space for a few more elements.
+    ///
+    /// If the allocator reports a failure, the vector is left unchanged.
+    #[inline]
+    pub fn try_shrink_to_fit(&mut self) -> Result<(), AllocError> {
+        if self.capacity() > self.len() {
+            self.try_shrink(self.len())?;
+        }
+        Ok(())
+    }
+
+    /// Shrinks the capacity of the vector with a lower bound.
+    ///
+    /// The capacity will remain at least as large as both the length
+    /// and the supplied value.
+    ///
+    /// If the current capacity is less than the lower limit, this is a no-op.
+    /// If the allocator reports a failure, the vector is left unchanged.
+    #[inline]
+    pub fn try_shrink_to(&mut self, min_capacity: usize) -> Result<(), AllocError> {
+        if self.capacity() > min_capacity {
+            self.try_shrink(cmp::max(self.len(), min_capacity))?;
+        }
+        Ok(())
+    }
+
+    /// Converts the vector into [`Box<[T]>`][owned slice].
+    ///
+    /// If the vector has excess capacity, its items will be moved into a
+    /// newly-allocated buffer with exactly the right capacity.
+    ///
+    /// [owned slice]: Box
+    #[inline]
+    pub fn try_into_boxed_slice(mut self) -> Result<Box<[T], A>, AllocError> {
+        self.try_shrink_to_fit()?;
+        // The capacity is equal to the length now, so it will not reallocate.
+        Ok(Box::from_std(self.0.into_boxed_slice()))
+    }
+
+    /// Shortens the vector, keeping the first `len` elements and dropping
+    /// the rest.
+    ///
+    /// If `len` is greater than the vector's current length, this has no
+    /// effect.
+    ///
+    /// Note that this method has no effect on the allocated capacity
+    /// of the vector.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        self.0.truncate(len)
+    }
+
+    /// Extracts a slice containing the entire vector.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self.0.as_slice()
+    }
+
+    /// Extracts a mutable slice of the entire vector.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.0.as_mut_slice()
+    }
+
+    /// Returns a raw pointer to the vector's buffer.
+    #[inline]
+    pub fn as_ptr(&self) -> *const T {
+        self.0.as_ptr()
+    }
+
+    /// Returns an unsafe mutable pointer to the vector's buffer.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.0.as_mut_ptr()
+    }
+
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        self.0.allocator()
+    }
+
+    /// Forces the length of the vector to `new_len`.
+    ///
+    /// # Safety
+    ///
+    /// - `new_len` must be less than or equal to `capacity()`.
+    /// - The elements at `old_len..new_len` must be initialized.
+    #[inline]
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        self.0.set_len(new_len);
+    }
+
+    /// Removes an element from the vector and returns it.
+    ///
+    /// The removed element is replaced by the last element of the vector.
+    ///
+    /// This does not preserve ordering, but is *O*(1).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        self.0.swap_remove(index)
+    }
+
+    /// Removes and returns the element at position `index` within the vector,
+    /// shifting all elements after it to the left.
+    ///
+    /// Note: Because this shifts over the remaining elements, it has a
+    /// worst-case performance of O(n). If you don't need the order of elements
+    /// to be preserved, use `swap_remove` instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    pub fn remove(&mut self, index: usize) -> T {
+        self.0.remove(index)
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all elements `e` for which `f(&e)` returns `false`.
+    /// This method operates in place, visiting each element exactly once in the
+    /// original order, and preserves the order of the retained elements.
+    #[inline]
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.0.retain(f)
+    }
+
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it.
+    ///
+    /// In other words, remove all elements `e` such that `f(&mut e)` returns `false`.
+    /// This method operates in place, visiting each element exactly once in the
+    /// original order, and preserves the order of the retained elements.
+    #[inline]
+    pub fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        self.0.retain_mut(f)
+    }
+
+    /// Removes all but the first of consecutive elements in the vector that resolve to the same
+    /// key.
+    ///
+    /// If the vector is sorted, this removes all duplicates.
+    #[inline]
+    pub fn dedup_by_key<F, K>(&mut self, key: F)
+    where
+        F: FnMut(&mut T) -> K,
+        K: PartialEq,
+    {
+        self.0.dedup_by_key(key)
+    }
+
+    /// Removes all but the first of consecutive elements in the vector satisfying a given equality
+    /// relation.
+    ///
+    /// The `same_bucket` function is passed references to two elements from the vector and
+    /// must determine if the elements compare equal. The elements are passed in opposite order
+    /// from their order in the slice, so if `same_bucket(a, b)` returns `true`, `a` is removed.
+    ///
+    /// If the vector is sorted, this removes all duplicates.
+    #[inline]
+    pub fn dedup_by<F>(&mut self, same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        self.0.dedup_by(same_bucket)
+    }
+
+    /// Appends an element to the back of a collection.
+    #[inline]
+    pub fn try_push(&mut self, value: T) -> Result<(), AllocError> {
+        if self.len() == self.capacity() {
+            self.try_reserve(1)?;
+        }
+        self.0.push(value);
+        Ok(())
+    }
+
+    /// Inserts an element at position `index` within the vector, shifting all
+    /// elements after it to the right.
+    ///
+    /// If the allocation fails, the vector is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    #[inline]
+    pub fn try_insert(&mut self, index: usize, element: T) -> Result<(), AllocError> {
+        let len = self.len();
+        if index > len {
+            panic!("insertion index (is {}) should be <= len (is {})", index, len);
+        }
+        if len == self.capacity() {
+            self.try_reserve(1)?;
+        }
+        self.0.insert(index, element);
+        Ok(())
+    }
+
+    /// Appends an element if there is sufficient spare capacity, otherwise an
+    /// error is returned with the element.
+    ///
+    /// Unlike [`try_push`], this method never allocates.
+    ///
+    /// [`try_push`]: Vec::try_push
+    #[inline]
+    pub fn push_within_capacity(&mut self, value: T) -> Result<(), T> {
+        let len = self.len();
+        if len == self.capacity() {
+            return Err(value);
+        }
+        unsafe {
+            ptr::write(self.as_mut_ptr().add(len), value);
+            self.set_len(len + 1);
+        }
+        Ok(())
+    }
+
+    /// Inserts an element at position `index` within the vector if there is
+    /// sufficient spare capacity, otherwise an error is returned with the element.
+    ///
+    /// Unlike [`try_insert`], this method never allocates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    ///
+    /// [`try_insert`]: Vec::try_insert
+    #[inline]
+    pub fn insert_within_capacity(&mut self, index: usize, element: T) -> Result<(), T> {
+        let len = self.len();
+        if index > len {
+            panic!("insertion index (is {}) should be <= len (is {})", index, len);
+        }
+        if len == self.capacity() {
+            return Err(element);
+        }
+        self.0.insert(index, element);
+        Ok(())
+    }
+
+    /// Copies and appends all elements in a slice to the `Vec` if there is
+    /// sufficient spare capacity for all of them, otherwise nothing is appended
+    /// and an error is returned with the slice.
+    ///
+    /// Unlike [`try_copy_from_slice`], this method never allocates.
+    ///
+    /// [`try_copy_from_slice`]: Vec::try_copy_from_slice
+    #[inline]
+    pub fn extend_from_slice_within_capacity<'a>(&mut self, other: &'a [T]) -> Result<(), &'a [T]>
+    where
+        T: Copy,
+    {
+        let len = self.len();
+        let count = other.len();
+        if self.capacity() - len < count {
+            return Err(other);
+        }
+        unsafe {
+            ptr::copy_nonoverlapping(other.as_ptr(), self.as_mut_ptr().add(len), count);
+            self.set_len(len + count);
+        }
+        Ok(())
+    }
+
+    /// Moves all the elements of `other` into `self`, leaving `other` empty.
+    ///
+    /// If the allocation fails, both vectors are left unchanged.
+    #[inline]
+    pub fn try_append(&mut self, other: &mut Self) -> Result<(), AllocError> {
+        self.try_reserve(other.len())?;
+        self.0.append(&mut other.0);
+        Ok(())
+    }
+
+    /// Removes the last element from a vector and returns it, or [`None`] if it
+    /// is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.0.pop()
+    }
+
+    /// Removes and returns the last element from a vector if the predicate
+    /// returns `true`, or [`None`] if the predicate returns false or the vector
+    /// is empty (the predicate will not be called in that case).
+    #[inline]
+    pub fn pop_if(&mut self, predicate: impl FnOnce(&mut T) -> bool) -> Option<T> {
+        self.0.pop_if(predicate)
+    }
+
+    /// Creates a draining iterator that removes the specified range in the vector
+    /// and yields the removed items.
+    ///
+    /// When the iterator **is** dropped, all elements in the range are removed
+    /// from the vector, even if the iterator was not fully consumed. If the
+    /// iterator **is not** dropped (with `mem::forget` for example), it is
+    /// unspecified how many elements are removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the vector.
+    #[inline]
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T, A>
+    where
+        R: RangeBounds<usize>,
+    {
+        Drain(self.0.drain(range))
+    }
+
+    /// Replaces the specified range in the vector with the given `replace_with`
+    /// iterator. The removed elements are dropped.
+    ///
+    /// The vector reserves space for the lower bound of the iterator's `size_hint`
+    /// up front. If the reservation fails, the vector is left unchanged.
+    ///
+    /// If the iterator yields more elements than its lower bound and growing the
+    /// vector for them fails, an error is returned. The range has then been
+    /// replaced by the elements yielded before the one that needed the room, and
+    /// the tail of the vector is kept after them. The element that needed the
+    /// room is dropped, and the rest of the iterator is not consumed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the vector.
+    #[inline]
+    pub fn try_splice<R, I>(&mut self, range: R, replace_with: I) -> Result<(), AllocError>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        let len = self.len();
+        let ops::Range { start, end } = slice::range(range, ..len);
+        let mut replace_with = replace_with.into_iter();
+
+        if let Some(additional) = replace_with.size_hint().0.checked_sub(end - start) {
+            self.try_reserve(additional)?;
+        }
+
+        unsafe {
+            let mut splice = Splice {
+                vec: self,
+                tail_start: end,
+                tail_len: len - end,
+            };
+            splice.vec.set_len(start);
+            let removed = ptr::slice_from_raw_parts_mut(splice.vec.as_mut_ptr().add(start), end - start);
+            ptr::drop_in_place(removed);
+
+            while splice.fill(&mut replace_with) {
+                let value = match replace_with.next() {
+                    Some(value) => value,
+                    None => break,
+                };
+                splice.try_move_tail(replace_with.size_hint().0.saturating_add(1))?;
+                let len = splice.vec.len();
+                ptr::write(splice.vec.as_mut_ptr().add(len), value);
+                splice.vec.set_len(len + 1);
+            }
+
+            // tail moved back by guard
+        }
+
+        Ok(())
+    }
+
+    /// Creates an iterator which uses a closure to determine if an element in the range
+    /// should be removed.
+    ///
+    /// If the closure returns `true`, the element is removed from the vector
+    /// and yielded. If the closure returns `false`, or panics, the element
+    /// remains in the vector and will not be yielded.
+    ///
+    /// If the returned `ExtractIf` is not exhausted, e.g. because it is dropped
+    /// without iterating or the iteration short-circuits, then the remaining
+    /// elements will be retained.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the vector.
+    #[inline]
+    pub fn extract_if<F, R>(&mut self, range: R, filter: F) -> ExtractIf<'_, T, F, A>
+    where
+        F: FnMut(&mut T) -> bool,
+        R: RangeBounds<usize>,
+    {
+        self.0.extract_if(range, filter)
+    }
+
+    /// Clears the vector, removing all values.
+    ///
+    /// Note that this method has no effect on the allocated capacity
+    /// of the vector.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Returns the number of elements in the vector, also referred to
+    /// as its 'length'.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the vector contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Consumes and leaks the `Vec`, returning a mutable reference to the contents,
+    /// `&'a mut [T]`.
+    ///
+    /// This function is mainly useful for data that lives for the remainder of
+    /// the program's life. Dropping the returned reference will cause a memory
+    /// leak.
+    #[inline]
+    pub fn leak<'a>(self) -> &'a mut [T]
+    where
+        A: 'a,
+    {
+        self.0.leak()
+    }
+
+    /// Shrinks the capacity of the vector to its length, then consumes and
+    /// leaks it, returning a mutable reference to the contents.
+    ///
+    /// Unlike [`leak`], the allocation of the returned slice is exactly the
+    /// size of the slice, so it can be reclaimed by `Box::from_raw_in`.
+    ///
+    /// [`leak`]: Vec::leak
+    #[inline]
+    pub fn try_leak<'a>(mut self) -> Result<&'a mut [T], AllocError>
+    where
+        A: 'a,
+    {
+        self.try_shrink_to_fit()?;
+        Ok(self.leak())
+    }
+
+    /// Decomposes a `Vec<T, A>` into its raw components.
+    ///
+    /// Returns the raw pointer to the underlying data, the length of the vector
+    /// (in elements), the allocated capacity of the data (in elements), and the
+    /// allocator. After calling this function, the caller is responsible for the
+    /// memory previously managed by the `Vec`. The only way to do this is to
+    /// convert the raw pointer, length, and capacity back into a `Vec` with the
+    /// [`from_raw_parts_in`] function, allowing the destructor to perform the
+    /// cleanup.
+    ///
+    /// [`from_raw_parts_in`]: Vec::from_raw_parts_in
+    #[inline]
+    pub fn into_raw_parts_with_alloc(self) -> (*mut T, usize, usize, A) {
+        self.0.into_raw_parts_with_alloc()
+    }
+
+    /// Returns the remaining spare capacity of the vector as a slice of
+    /// `MaybeUninit<T>`.
+    ///
+    /// The returned slice can be used to fill the vector with data (e.g. by
+    /// reading from a file) before marking the data as initialized using the
+    /// [`set_len`] method.
+    ///
+    /// [`set_len`]: Vec::set_len
+    #[inline]
+    pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        self.0.spare_capacity_mut()
+    }
+
+    /// Returns vector content as a slice of `T`, along with the remaining spare
+    /// capacity of the vector as a slice of `MaybeUninit<T>`.
+    ///
+    /// The returned spare capacity slice can be used to fill the vector with data
+    /// (e.g. by reading from a file) before marking the data as initialized using
+    /// the [`set_len`] method.
+    ///
+    /// [`set_len`]: Vec::set_len
+    #[inline]
+    pub fn split_at_spare_mut(&mut self) -> (&mut [T], &mut [MaybeUninit<T>]) {
+        self.0.split_at_spare_mut()
+    }
+
+    /// Reserves capacity for at least `n` more elements, and lets `f` write
+    /// them into the spare capacity.
+    ///
+    /// `f` is given an [`UninitWriter`] over the first `n` slots of the spare
+    /// capacity. The elements written through it are appended to the vector,
+    /// and the result of `f` is returned.
+    ///
+    /// If `f` panics, the elements written so far are dropped.
+    ///
+    /// For bytes, [`try_extend_borrowed_buf`] lets [`Read::read_buf`] write
+    /// into the spare capacity directly.
+    ///
+    /// [`try_extend_borrowed_buf`]: Vec::try_extend_borrowed_buf
+    /// [`Read::read_buf`]: io::Read::read_buf
+    #[inline]
+    pub fn try_extend_uninit<F, R>(&mut self, n: usize, f: F) -> Result<R, AllocError>
+    where
+        F: FnOnce(&mut UninitWriter<'_, T>) -> R,
+    {
+        self.try_reserve(n)?;
+        let mut writer = UninitWriter::new(&mut self.spare_capacity_mut()[..n]);
+        let res = f(&mut writer);
+        let count = writer.into_len();
+        // SAFETY: the writer guarantees that the first `count` slots are initialized.
+        unsafe {
+            self.set_len(self.len() + count);
+        }
+        Ok(res)
+    }
+
+    /// Resizes the `Vec` in-place so that `len` is equal to `new_len`.
+    ///
+    /// If `new_len` is greater than `len`, the `Vec` is extended by the
+    /// difference, with each additional slot filled with the result of
+    /// calling the closure `f`. The return values from `f` will end up
+    /// in the `Vec` in the order they have been generated.
+    ///
+    /// If `new_len` is less than `len`, the `Vec` is simply truncated.
+    ///
+    /// If `f` fails, the elements generated so far are kept in the `Vec`.
+    #[inline]
+    pub fn try_resize_with<F>(&mut self, new_len: usize, f: F) -> Result<(), AllocError>
+    where
+        F: FnMut() -> Result<T, AllocError>,
+    {
+        let len = self.len();
+        if new_len > len {
+            self.try_extend_with(new_len - len, ExtendFunc(f))
+        } else {
+            self.0.truncate(new_len);
+            Ok(())
+        }
+    }
+
+    /// Resizes the `Vec` in-place so that `len` is equal to `new_len`, like
+    /// [`try_resize_with`], but leaves the `Vec` unchanged on error.
+    ///
+    /// [`try_resize_with`]: Vec::try_resize_with
+    #[inline]
+    pub fn try_resize_with_atomic<F>(&mut self, new_len: usize, f: F) -> Result<(), AllocError>
+    where
+        F: FnMut() -> Result<T, AllocError>,
+    {
+        let len = self.len();
+        let res = self.try_resize_with(new_len, f);
+        if res.is_err() {
+            self.truncate(len);
+        }
+        res
+    }
+
+    /// Splits the collection into two at the given index.
+    ///
+    /// Returns a newly allocated vector containing the elements in the range
+    /// `[at, len)`. After the call, the original vector will be left containing
+    /// the elements `[0, at)` with its previous capacity unchanged.
+    ///
+    /// If the allocation fails, the vector is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    #[inline]
+    pub fn try_split_off(&mut self, at: usize) -> Result<Self, AllocError>
+    where
+        A: Clone,
+    {
+        let len = self.len();
+        if at > len {
+            panic!("`at` split index (is {}) should be <= len (is {})", at, len);
+        }
+
+        let other_len = len - at;
+        let mut other = Vec::try_with_capacity_in(other_len, self.allocator().clone())?.with_growth_policy();
+
+        unsafe {
+            self.set_len(at);
+            ptr::copy_nonoverlapping(self.as_ptr().add(at), other.as_mut_ptr(), other_len);
+            other.set_len(other_len);
+        }
+
+        Ok(other)
+    }
+
+    /// Maps all elements of the vector with `f` and collects the results into
+    /// a `Vec<U, A>`.
+    ///
+    /// If `T` and `U` have the same size and alignment, the results are written
+    /// into the source buffer and no allocation happens. Otherwise a new buffer
+    /// is allocated for the results, and an error is returned if the allocation
+    /// fails.
+    #[inline]
+    pub fn try_map_in_place<U, F>(self, f: F) -> Result<Vec<U, A, G>, AllocError>
+    where
+        F: FnMut(T) -> U,
+    {
+        let vec = self.into_iter().try_map_in_place(f)?;
+        Ok(vec.with_growth_policy())
+    }
+
+    /// Copy and appends all elements in a slice to the `Vec`.
+    #[inline]
+    pub fn try_copy_from_slice(&mut self, other: &[T]) -> Result<(), AllocError>
+    where
+        T: Copy,
+    {
+        let count = other.len();
+        self.try_reserve(count)?;
+
+        unsafe {
+            let ptr = self.as_mut_ptr().add(self.len());
+            ptr::copy_nonoverlapping(other.as_ptr(), ptr, count);
+            self.set_len(self.len() + count);
+        }
+
+        Ok(())
+    }
+
+    /// Sorts the vector.
+    ///
+    /// See [`SliceExt::try_sort`].
+    #[inline]
+    pub fn try_sort(&mut self) -> Result<(), AllocError>
+    where
+        T: Ord,
+    {
+        self.as_mut_slice().try_sort()
+    }
+
+    /// Sorts the vector with a comparator function.
+    ///
+    /// See [`SliceExt::try_sort_by`].
+    #[inline]
+    pub fn try_sort_by<F>(&mut self, compare: F) -> Result<(), AllocError>
+    where
+        F: FnMut(&T, &T) -> cmp::Ordering,
+    {
+        self.as_mut_slice().try_sort_by(compare)
+    }
+
+    /// Sorts the vector with a key extraction function.
+    ///
+    /// See [`SliceExt::try_sort_by_key`].
+    #[inline]
+    pub fn try_sort_by_key<K, F>(&mut self, f: F) -> Result<(), AllocError>
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        self.as_mut_slice().try_sort_by_key(f)
+    }
+
+    /// Sorts the vector with a key extraction function, calling the key
+    /// function only once per element.
+    ///
+    /// See [`SliceExt::try_sort_by_cached_key`].
+    #[inline]
+    pub fn try_sort_by_cached_key<K, F>(&mut self, f: F) -> Result<(), AllocError>
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        self.as_mut_slice().try_sort_by_cached_key(f)
+    }
+}
+
+impl<T: TryClone, A: Allocator> Vec<T, A> {
+    /// Constructs a new `Vec<T, A>` of length `n` with the provided allocator,
+    /// with each element filled with a clone of `elem`.
+    #[inline]
+    pub fn try_from_elem_in(elem: T, n: usize, alloc: A) -> Result<Self, AllocError> {
+        let mut vec = Vec::new_in(alloc).with_growth_policy::<Exact>();
+        vec.spec_extend_element(n, elem)?;
+        Ok(vec.with_growth_policy())
+    }
+}
+
+impl<T: Zeroable> Vec<T> {
+    /// Constructs a new `Vec<T>` of length `n`, with all elements zeroed.
+    ///
+    /// The memory is requested from the allocator as zeroed memory, so no
+    /// bytes need to be written if the allocator hands out zeroed pages.
+    #[inline]
+    pub fn try_zeroed(n: usize) -> Result<Self, AllocError> {
+        Self::try_zeroed_in(n, Global)
+    }
+}
+
+impl<T: Zeroable, A: Allocator> Vec<T, A> {
+    /// Constructs a new `Vec<T, A>` of length `n` with the provided allocator,
+    /// with all elements zeroed.
+    #[inline]
+    pub fn try_zeroed_in(n: usize, alloc: A) -> Result<Self, AllocError> {
+        let mut vec = Vec::new_in(alloc);
+        // SAFETY: `T` is `Zeroable`.
+        unsafe {
+            vec.try_extend_zeroed(n, n)?;
+        }
+        Ok(vec)
+    }
+}
+
+impl<T: TryClone, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
+    /// Clones the vector into a new vector allocated with the provided allocator.
+    ///
+    /// This can be used to copy data out of an arena into the global heap.
+    #[inline]
+    pub fn try_clone_in<B: Allocator>(&self, alloc: B) -> Result<Vec<T, B, G>, AllocError> {
+        let mut vec = Vec::try_with_capacity_in(self.len(), alloc)?.with_growth_policy();
+        vec.try_extend_from_slice(self)?;
+        Ok(vec)
+    }
+
+    /// Resizes the `Vec` in-place so that `len` is equal to `new_len`.
+    ///
+    /// If `new_len` is greater than `len`, the `Vec` is extended by the
+    /// difference, with each additional slot filled with `value`.
+    /// If `new_len` is less than `len`, the `Vec` is simply truncated.
+    ///
+    /// For `Copy` types, the slots are filled without cloning, and if `value`
+    /// is a zero primitive, they are zeroed in bulk.
+    ///
+    /// If cloning `value` fails, the clones made so far are kept in the `Vec`.
+    #[inline]
+    pub fn try_resize(&mut self, new_len: usize, value: T) -> Result<(), AllocError> {
+        let len = self.len();
+
+        if new_len > len {
+            self.spec_extend_element(new_len - len, value)
+        } else {
+            self.truncate(new_len);
+            Ok(())
+        }
+    }
+
+    /// Clones and appends all elements in a slice to the `Vec`.
+    ///
+    /// Iterates over the slice `other`, clones each element, and then appends
+    /// it to this `Vec`. The `other` slice is traversed in-order.
+    ///
+    /// For `Copy` types, the elements are copied with a single `memcpy`.
+    ///
+    /// If cloning an element fails, the elements cloned so far are kept in the `Vec`.
+    #[inline]
+    pub fn try_extend_from_slice(&mut self, other: &[T]) -> Result<(), AllocError> {
+        self.spec_extend_from_slice(other)
+    }
+
+    /// Resizes the `Vec` in-place so that `len` is equal to `new_len`, like
+    /// [`try_resize`], but leaves the `Vec` unchanged on error.
+    ///
+    /// [`try_resize`]: Vec::try_resize
+    #[inline]
+    pub fn try_resize_atomic(&mut self, new_len: usize, value: T) -> Result<(), AllocError> {
+        let len = self.len();
+        let res = self.try_resize(new_len, value);
+        if res.is_err() {
+            self.truncate(len);
+        }
+        res
+    }
+
+    /// Clones and appends all elements in a slice to the `Vec`, like
+    /// [`try_extend_from_slice`], but leaves the `Vec` unchanged on error.
+    ///
+    /// [`try_extend_from_slice`]: Vec::try_extend_from_slice
+    #[inline]
+    pub fn try_extend_from_slice_atomic(&mut self, other: &[T]) -> Result<(), AllocError> {
+        let len = self.len();
+        let res = self.try_extend_from_slice(other);
+        if res.is_err() {
+            self.truncate(len);
+        }
+        res
+    }
+
+    /// Clones elements from `src` range to the end of the vector.
+    ///
+    /// If the allocation or the cloning of any element fails, the vector is
+    /// left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the vector.
+    #[inline]
+    pub fn try_extend_from_within<R>(&mut self, src: R) -> Result<(), AllocError>
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = slice::range(src, ..self.len());
+        self.try_reserve(range.len())?;
+
+        let len = self.len();
+        let res = unsafe {
+            let src = self.as_ptr();
+            let mut ptr = self.as_mut_ptr().add(len);
+            let mut local_len = SetLenOnDrop::new(self);
+
+            range.into_iter().try_for_each(|i| {
+                ptr::write(ptr, (*src.add(i)).try_clone()?);
+                ptr = ptr.offset(1);
+                local_len.increment_len(1);
+                Ok(())
+            })
+        };
+
+        if res.is_err() {
+            self.truncate(len);
+        }
+        res
+    }
+}
+
+impl<T: PartialEq, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
+    /// Removes consecutive repeated elements in the vector according to the
+    /// [`PartialEq`] trait implementation.
+    ///
+    /// If the vector is sorted, this removes all duplicates.
+    #[inline]
+    pub fn dedup(&mut self) {
+        self.0.dedup()
+    }
+}
+
+impl<A: Allocator, G: GrowthPolicy> Vec<u8, A, G> {
+    /// Reserves capacity for at least `n` more bytes, and lets `f` write them
+    /// into the spare capacity through a [`BorrowedCursor`].
+    ///
+    /// The bytes `f` has filled through the cursor are appended to the vector.
+    /// The spare capacity is not zeroed first, so this can be used with
+    /// [`Read::read_buf`](io::Read::read_buf) to read directly into the vector.
+    #[inline]
+    pub fn try_extend_borrowed_buf<F, R>(&mut self, n: usize, f: F) -> Result<R, AllocError>
+    where
+        F: FnOnce(BorrowedCursor<'_>) -> R,
+    {
+        self.try_reserve(n)?;
+        let mut buf = BorrowedBuf::from(&mut self.spare_capacity_mut()[..n]);
+        let res = f(buf.unfilled());
+        let filled = buf.len();
+        // SAFETY: `BorrowedBuf` guarantees that the filled part is initialized.
+        unsafe {
+            self.set_len(self.len() + filled);
+        }
+        Ok(res)
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy, const N: usize> Vec<[T; N], A, G> {
+    /// Takes a `Vec<[T; N]>` and flattens it into a `Vec<T>`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the resulting vector would overflow a `usize`.
+    #[inline]
+    pub fn into_flattened(self) -> Vec<T, A, G> {
+        Vec(self.0.into_flattened(), PhantomData)
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
+    /// Appends `n` zeroed elements.
+    ///
+    /// If the vector is empty and a new buffer is needed, the buffer is
+    /// allocated with at least `cap` elements as zeroed memory.
+    ///
+    /// # Safety
+    ///
+    /// The all-zero byte pattern must be a valid value of `T`.
+    unsafe fn try_extend_zeroed(&mut self, n: usize, cap: usize) -> Result<(), AllocError> {
+        let len = self.len();
+        if len == 0 && n > self.capacity() && mem::size_of::<T>() != 0 {
+            debug_assert!(cap >= n);
+            let layout = Layout::array::<T>(cap).unwrap_or_else(|_| capacity_overflow());
+            let new_ptr = self
+                .allocator()
+                .allocate_zeroed(layout)
+                .map_err(|_| AllocError::new(layout))?;
+
+            let (ptr, _, old_cap, alloc) = ptr::read(&self.0).into_raw_parts_with_alloc();
+            drop(StdVec::from_raw_parts_in(ptr, 0, old_cap, &alloc));
+            ptr::write(
+                &mut self.0,
+                StdVec::from_raw_parts_in(new_ptr.cast().as_ptr(), n, cap, alloc),
+            );
+            return Ok(());
+        }
+
+        self.try_reserve(n)?;
+        ptr::write_bytes(self.as_mut_ptr().add(len), 0, n);
+        self.set_len(len + n);
+        Ok(())
+    }
+
+    /// Shrinks the buffer down to `cap` elements.
+    fn try_shrink(&mut self, cap: usize) -> Result<(), AllocError> {
+        debug_assert!(cap >= self.len() && cap <= self.capacity());
+
+        if mem::size_of::<T>() == 0 || self.capacity() == 0 {
+            return Ok(());
+        }
+
+        unsafe {
+            let ptr = NonNull::new_unchecked(self.as_mut_ptr()).cast();
+            // The current layout is valid because the buffer has been allocated with it.
+            let old_layout = Layout::array::<T>(self.capacity()).unwrap_unchecked();
+            let new_ptr = if cap == 0 {
+                self.allocator().deallocate(ptr, old_layout);
+                NonNull::dangling()
+            } else {
+                let new_layout = Layout::array::<T>(cap).unwrap_unchecked();
+                self.allocator()
+                    .shrink(ptr, old_layout, new_layout)
+                    .map_err(|_| AllocError::new(new_layout))?
+                    .cast()
+            };
+
+            let (_, len, _, alloc) = ptr::read(&self.0).into_raw_parts_with_alloc();
+            ptr::write(
+                &mut self.0,
+                StdVec::from_raw_parts_in(new_ptr.as_ptr(), len, cap, alloc),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Extend the vector by `n` values, using the given generator.
+    fn try_extend_with<E: ExtendWith<T>>(&mut self, n: usize, mut value: E) -> Result<(), AllocError> {
+        self.try_reserve(n)?;
+
+        unsafe {
+            let mut ptr = self.as_mut_ptr().add(self.len());
+            // Use SetLenOnDrop to work around bug where compiler
+            // might not realize the store through `ptr` through self.set_len()
+            // don't alias.
+            let mut local_len = SetLenOnDrop::new(self);
+
+            // Write all elements except the last one
+            for _ in 1..n {
+                ptr::write(ptr, value.next()?);
+                ptr = ptr.offset(1);
+                // Increment the length in every step in case next() panics
+                local_len.increment_len(1);
+            }
+
+            if n > 0 {
+                // We can write the last element directly without cloning needlessly
+                ptr::write(ptr, value.last()?);
+                local_len.increment_len(1);
+            }
+
+            // len set by scope guard
+        }
+
+        Ok(())
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Deref for Vec<T, A, G> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        self.0.deref()
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> DerefMut for Vec<T, A, G> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.0.deref_mut()
+    }
+}
+
+impl<T, I: SliceIndex<[T]>, A: Allocator, G: GrowthPolicy> Index<I> for Vec<T, A, G> {
+    type Output = I::Output;
+
+    #[inline]
+    fn index(&self, index: I) -> &Self::Output {
+        self.0.index(index)
+    }
+}
+
+impl<T, I: SliceIndex<[T]>, A: Allocator, G: GrowthPolicy> IndexMut<I> for Vec<T, A, G> {
+    #[inline]
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        self.0.index_mut(index)
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> IntoIterator for Vec<T, A, G> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    #[inline]
+    fn into_iter(self) -> IntoIter<T, A> {
+        IntoIter::new(self)
+    }
+}
+
+impl<'a, T, A: Allocator, G: GrowthPolicy> IntoIterator for &'a Vec<T, A, G> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> core::slice::Iter<'a, T> {
+        self.0.iter()
+    }
+}
+
+impl<'a, T, A: Allocator, G: GrowthPolicy> IntoIterator for &'a mut Vec<T, A, G> {
+    type Item = &'a mut T;
+    type IntoIter = core::slice::IterMut<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> core::slice::IterMut<'a, T> {
+        self.0.iter_mut()
+    }
+}
+
+impl<T, const N: usize> TryFrom<[T; N]> for Vec<T> {
+    type Error = AllocError;
+
+    #[inline]
+    fn try_from(arr: [T; N]) -> Result<Self, Self::Error> {
+        let mut vec = Vec::try_with_capacity(N)?;
+        unsafe {
+            ptr::copy_nonoverlapping(arr.as_ptr(), vec.as_mut_ptr(), N);
+            vec.set_len(N);
+        }
+        mem::forget(arr);
+        Ok(vec)
+    }
+}
+
+impl<T> TryFromIterator<T> for Vec<T> {
+    type Error = AllocError;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, Self::Error> {
+        <Self as SpecTryFromIter<T, I::IntoIter>>::spec_try_from_iter(iter.into_iter())
+    }
+}
+
+/// Specialization trait used for `TryFromIterator` of `Vec`.
+trait SpecTryFromIter<T, I> {
+    fn spec_try_from_iter(iter: I) -> Result<Self, AllocError>
+    where
+        Self: Sized;
+}
+
+impl<T, I: Iterator<Item = T>> SpecTryFromIter<T, I> for Vec<T> {
+    #[inline]
+    default fn spec_try_from_iter(iter: I) -> Result<Self, AllocError> {
+        try_from_iter_by_push(iter)
+    }
+}
+
+impl<T, I> SpecTryFromIter<T, I> for Vec<T>
+where
+    I: Iterator<Item = T> + InPlaceIterable,
+    I::Source: AsIntoIter,
+{
+    /// Collects the items into the buffer of the source `IntoIter` if the
+    /// layouts of the source and target items are the same.
+    #[inline]
+    fn spec_try_from_iter(iter: I) -> Result<Self, AllocError> {
+        type Src<I> = <<I as SourceIter>::Source as AsIntoIter>::Item;
+
+        if mem::size_of::<T>() == mem::size_of::<Src<I>>() && mem::align_of::<T>() == mem::align_of::<Src<I>>() {
+            // SAFETY: the layouts are checked above.
+            Ok(unsafe { into_iter::collect_in_place(iter) })
+        } else {
+            try_from_iter_by_push(iter)
+        }
+    }
+}
+
+#[inline]
+fn try_from_iter_by_push<T, I: Iterator<Item = T>>(iter: I) -> Result<Vec<T>, AllocError> {
+    let mut vec = Vec::try_with_capacity(iter.size_hint().0)?;
+    for value in iter {
+        vec.try_push(value)?;
+    }
+    Ok(vec)
+}
+
+impl<T, E> TryFromIterator<Result<T, E>> for Vec<T>
+where
+    E: From<AllocError>,
+{
+    type Error = E;
+
+    #[inline]
+    fn try_from_iter<I: IntoIterator<Item = Result<T, E>>>(iter: I) -> Result<Self, Self::Error> {
+        iter::try_process(iter.into_iter(), |shunt| Vec::try_from_iter(shunt))
+    }
+}
+
+impl<T: TryClone, A: Allocator + Clone, G: GrowthPolicy> TryClone for Vec<T, A, G> {
+    #[inline]
+    fn try_clone(&self) -> Result<Self, AllocError> {
+        self.try_clone_in(self.allocator().clone())
+    }
+
+    /// Overwrites the contents of `self` with a clone of the contents of `source`.
+    ///
+    /// The existing elements of `self` are reused with `try_clone_from`, and
+    /// the buffer of `self` is only grown if `source` is longer.
+    #[inline]
+    fn try_clone_from(&mut self, source: &Self) -> Result<(), AllocError> {
+        self.truncate(source.len());
+
+        let (init, tail) = source.split_at(self.len());
+        for (dst, src) in self.iter_mut().zip(init) {
+            dst.try_clone_from(src)?;
+        }
+        self.try_extend_from_slice(tail)
+    }
+}
+
+impl<T: PartialEq, A: Allocator, G: GrowthPolicy> PartialEq for Vec<T, A, G> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: Eq, A: Allocator, G: GrowthPolicy> Eq for Vec<T, A, G> {}
+
+impl<T: PartialOrd, A: Allocator, G: GrowthPolicy> PartialOrd for Vec<T, A, G> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        PartialOrd::partial_cmp(&self.0, &other.0)
+    }
+}
+
+impl<T: Ord, A: Allocator, G: GrowthPolicy> Ord for Vec<T, A, G> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        Ord::cmp(&self.0, &other.0)
+    }
+}
+
+impl<T: Hash, A: Allocator, G: GrowthPolicy> Hash for Vec<T, A, G> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(&self.0, state)
+    }
+}
+
+impl<T: HeapSize, A: Allocator, G: GrowthPolicy> HeapSize for Vec<T, A, G> {
+    #[inline]
+    fn heap_size(&self) -> usize {
+        self.capacity() * mem::size_of::<T>() + self.as_slice().heap_size()
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator, G: GrowthPolicy> fmt::Debug for Vec<T, A, G> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> AsRef<Vec<T, A, G>> for Vec<T, A, G> {
+    #[inline]
+    fn as_ref(&self) -> &Vec<T, A, G> {
+        self
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> AsMut<Vec<T, A, G>> for Vec<T, A, G> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut Vec<T, A, G> {
+        self
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> AsRef<StdVec<T, A>> for Vec<T, A, G> {
+    #[inline]
+    fn as_ref(&self) -> &StdVec<T, A> {
+        &self.0
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> AsMut<StdVec<T, A>> for Vec<T, A, G> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut StdVec<T, A> {
+        &mut self.0
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> AsRef<[T]> for Vec<T, A, G> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> AsMut<[T]> for Vec<T, A, G> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<A: Allocator, G: GrowthPolicy> Borrow<[u8]> for Vec<u8, A, G> {
+    #[inline]
+    fn borrow(&self) -> &[u8] {
+        self
+    }
+}
+
+impl<A: Allocator, G: GrowthPolicy> BorrowMut<[u8]> for Vec<u8, A, G> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+impl<A: Allocator, G: GrowthPolicy> io::Write for Vec<u8, A, G> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.try_reserve(buf.len())
+            .map_err(|_| io::Error::from(io::ErrorKind::OutOfMemory))?;
+        self.0.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        let len = bufs.iter().map(|b| b.len()).sum();
+        self.try_reserve(len)
+            .map_err(|_| io::Error::from(io::ErrorKind::OutOfMemory))?;
+        for buf in bufs {
+            self.0.extend_from_slice(buf);
+        }
+        Ok(len)
+    }
+
+    #[inline]
+    fn is_write_vectored(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.try_reserve(buf.len())
+            .map_err(|_| io::Error::from(io::ErrorKind::OutOfMemory))?;
+        self.0.extend_from_slice(buf);
+        Ok(())
+    }
+}
+
+/// Creates a [`Vec`] containing the arguments.
+///
+/// `try_vec!` allows `Vec`s to be defined with the same syntax as array expressions.
+/// There are two forms of this macro:
+///
+/// - Create a [`Vec`] containing a given list of elements, like `try_vec![1, 2, 3]`.
+/// - Create a [`Vec`] from a given element and size, like `try_vec![1; 3]`.
+///   This will use `TryClone` to duplicate an expression.
+///
+/// The result is a `Result<Vec<T>, AllocError>`.
+#[macro_export]
+macro_rules! try_vec {
+    () => {{
+        let res = ::core::result::Result::<_, $crate::alloc::AllocError>::Ok($crate::vec::Vec::new());
+        res
+    }};
+    ($elem:expr; $n:expr) => {{
+        let res = $crate::vec::Vec::try_from_elem($elem, $n);
+        res
+    }};
+    ($($x:expr),+ $(,)?) => {{
+        let res = <$crate::vec::Vec<_> as ::core::convert::TryFrom<_>>::try_from([$($x),+]);
+        res
+    }};
+}
+
+trait ExtendWith<T> {
+    fn next(&mut self) -> Result<T, AllocError>;
+    fn last(self) -> Result<T, AllocError>;
+}
+
+struct ExtendElement<T>(T);
+
+impl<T: TryClone> ExtendWith<T> for ExtendElement<T> {
+    #[inline(always)]
+    default fn next(&mut self) -> Result<T, AllocError> {
+        self.0.try_clone()
+    }
+
+    #[inline(always)]
+    default fn last(self) -> Result<T, AllocError> {
+        Ok(self.0)
+    }
+}
+
+struct ExtendFunc<F>(F);
+
+impl<T, F> ExtendWith<T> for ExtendFunc<F>
+where
+    F: FnMut() -> Result<T, AllocError>,
+{
+    #[inline(always)]
+    fn next(&mut self) -> Result<T, AllocError> {
+        (self.0)()
+    }
+
+    #[inline(always)]
+    fn last(mut self) -> Result<T, AllocError> {
+        (self.0)()
+    }
+}
+
+/// Specialization trait used for `Vec::try_extend_from_slice`.
+trait SpecExtendFromSlice<T> {
+    fn spec_extend_from_slice(&mut self, other: &[T]) -> Result<(), AllocError>;
+}
+
+impl<T: TryClone, A: Allocator, G: GrowthPolicy> SpecExtendFromSlice<T> for Vec<T, A, G> {
+    #[inline]
+    default fn spec_extend_from_slice(&mut self, other: &[T]) -> Result<(), AllocError> {
+        self.try_reserve(other.len())?;
+
+        unsafe {
+            let mut ptr = self.as_mut_ptr().add(self.len());
+            // Use SetLenOnDrop to work around bug where compiler
+            // might not realize the store through `ptr` through self.set_len()
+            // don't alias.
+            let mut local_len = SetLenOnDrop::new(self);
+
+            // Write all elements
+            for val in other {
+                ptr::write(ptr, val.try_clone()?);
+                ptr = ptr.offset(1);
+                // Increment the length in every step in case next() panics
+                local_len.increment_len(1);
+            }
+
+            // len set by scope guard
+        }
+
+        Ok(())
+    }
+}
+
+/// Specialization trait used for `Vec::try_resize` and `Vec::try_from_elem`.
+trait SpecExtendElement<T> {
+    fn spec_extend_element(&mut self, n: usize, value: T) -> Result<(), AllocError>;
+}
+
+impl<T: TryClone, A: Allocator, G: GrowthPolicy> SpecExtendElement<T> for Vec<T, A, G> {
+    #[inline]
+    default fn spec_extend_element(&mut self, n: usize, value: T) -> Result<(), AllocError> {
+        self.try_extend_with(n, ExtendElement(value))
+    }
+}
+
+/// Primitive types, which are bitwise copyable and have no user-written `TryClone`.
+///
+/// The bulk copy paths of `try_extend_from_slice` and `try_resize` are limited
+/// to these types: specializing on `Copy` itself is unsound because of lifetimes,
+/// and would bypass user-written `TryClone` impls of `Copy` types.
+pub(crate) trait Primitive: Copy {}
+
+#[inline]
+fn extend_from_slice_copy<T: Primitive, A: Allocator, G: GrowthPolicy>(
+    vec: &mut Vec<T, A, G>,
+    other: &[T],
+) -> Result<(), AllocError> {
+    let count = other.len();
+    vec.try_reserve(count)?;
+
+    // SAFETY: `T` is `Copy`, so the elements can be duplicated bitwise.
+    unsafe {
+        let ptr = vec.as_mut_ptr().add(vec.len());
+        ptr::copy_nonoverlapping(other.as_ptr(), ptr, count);
+        vec.set_len(vec.len() + count);
+    }
+
+    Ok(())
+}
+
+#[inline]
+fn extend_element_copy<T: Primitive, A: Allocator, G: GrowthPolicy>(
+    vec: &mut Vec<T, A, G>,
+    n: usize,
+    value: T,
+) -> Result<(), AllocError> {
+    if value.is_zero() {
+        let required = vec.len().saturating_add(n);
+        let cap = cmp::max(G::grow(vec.capacity(), required, mem::size_of::<T>()), required);
+        // SAFETY: only primitives whose all-zero byte pattern is valid are `is_zero`.
+        return unsafe { vec.try_extend_zeroed(n, cap) };
+    }
+
+    vec.try_reserve(n)?;
+    // SAFETY: `T` is `Copy`, so `value` can be duplicated bitwise.
+    unsafe {
+        let len = vec.len();
+        let ptr = vec.as_mut_ptr().add(len);
+        for i in 0..n {
+            ptr::write(ptr.add(i), value);
+        }
+        vec.set_len(len + n);
+    }
+    Ok(())
+}
+
+macro_rules! impl_primitive {
+    ($($t: ty),*) => {
+        $(impl Primitive for $t {}
+
+        impl<A: Allocator, G: GrowthPolicy> SpecExtendFromSlice<$t> for Vec<$t, A, G> {
+            #[inline]
+            fn spec_extend_from_slice(&mut self, other: &[$t]) -> Result<(), AllocError> {
+                extend_from_slice_copy(self, other)
+            }
+        }
+
+        impl<A: Allocator, G: GrowthPolicy> SpecExtendElement<$t> for Vec<$t, A, G> {
+            #[inline]
+            fn spec_extend_element(&mut self, n: usize, value: $t) -> Result<(), AllocError> {
+                extend_element_copy(self, n, value)
+            }
+        })*
+    }
+}
+
+impl_primitive!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, bool);
+
+/// Checks whether a value is all zero bytes, for the types where this is cheap.
+trait IsZero {
+    fn is_zero(&self) -> bool;
+}
+
+impl<T> IsZero for T {
+    #[inline(always)]
+    default fn is_zero(&self) -> bool {
+        false
+    }
+}
+
+macro_rules! impl_is_zero {
+    ($($t: ty),*) => {
+        $(impl IsZero for $t {
+            #[inline(always)]
+            fn is_zero(&self) -> bool {
+                *self == 0
+            }
+        })*
+    }
+}
+
+impl_is_zero!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl IsZero for bool {
+    #[inline(always)]
+    fn is_zero(&self) -> bool {
+        !*self
+    }
+}
+
+impl IsZero for char {
+    #[inline(always)]
+    fn is_zero(&self) -> bool {
+        *self == '\0'
+    }
+}
+
+impl IsZero for f32 {
+    #[inline(always)]
+    fn is_zero(&self) -> bool {
+        self.to_bits() == 0
+    }
+}
+
+impl IsZero for f64 {
+    #[inline(always)]
+    fn is_zero(&self) -> bool {
+        self.to_bits() == 0
+    }
+}
+
+#[cold]
+#[inline(never)]
+fn capacity_overflow() -> ! {
+    panic!("capacity overflow");
+}
+
+/// A guard used by `try_splice`, which keeps a gap between the vector's length and
+/// its tail, and moves the tail back to the end of the vector when dropped.
+struct Splice<'a, T, A: Allocator, G: GrowthPolicy> {
+    vec: &'a mut Vec<T, A, G>,
+    tail_start: usize,
+    tail_len: usize,
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Splice<'_, T, A, G> {
+    /// Fills the gap with items from `replace_with`.
+    /// Returns `true` if the gap was filled, `false` if the iterator ran out.
+    #[inline]
+    unsafe fn fill<I: Iterator<Item = T>>(&mut self, replace_with: &mut I) -> bool {
+        while self.vec.len() < self.tail_start {
+            match replace_with.next() {
+                Some(value) => {
+                    let len = self.vec.len();
+                    ptr::write(self.vec.as_mut_ptr().add(len), value);
+                    self.vec.set_len(len + 1);
+                }
+                None => return false,
+            }
+        }
+        true
+    }
+
+    /// Makes room for inserting `additional` more elements before the tail.
+    #[inline]
+    unsafe fn try_move_tail(&mut self, additional: usize) -> Result<(), AllocError> {
+        let used = self.tail_start + self.tail_len;
+        self.vec
+            .try_reserve((used - self.vec.len()).saturating_add(additional))?;
+
+        let new_tail_start = self.tail_start + additional;
+        let ptr = self.vec.as_mut_ptr();
+        ptr::copy(ptr.add(self.tail_start), ptr.add(new_tail_start), self.tail_len);
+        self.tail_start = new_tail_start;
+        Ok(())
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Drop for Splice<'_, T, A, G> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            let start = self.vec.len();
+            if self.tail_start != start {
+                let ptr = self.vec.as_mut_ptr();
+                ptr::copy(ptr.add(self.tail_start), ptr.add(start), self.tail_len);
+            }
+            self.vec.set_len(start + self.tail_len);
+        }
+    }
+}
+
+struct SetLenOnDrop<'a, T, A: Allocator, G: GrowthPolicy> {
+    vec: &'a mut Vec<T, A, G>,
+    local_len: usize,
+}
+
+impl<'a, T, A: Allocator, G: GrowthPolicy> SetLenOnDrop<'a, T, A, G> {
+    #[inline]
+    fn new(vec: &'a mut Vec<T, A, G>) -> Self {
+        SetLenOnDrop {
+            local_len: vec.len(),
+            vec,
+        }
+    }
+
+    #[inline(always)]
+    fn increment_len(&mut self, increment: usize) {
+        self.local_len += increment;
+    }
+}
+
+impl<'a, T, A: Allocator, G: GrowthPolicy> Drop for SetLenOnDrop<'a, T, A, G> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            self.vec.set_len(self.local_len);
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use super::Vec;
+    use serde_crate::de::{DeserializeSeed, Error, SeqAccess, Visitor};
+    use serde_crate::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::fmt;
+    use std::marker::PhantomData;
+
+    impl<T: Serialize> Serialize for Vec<T> {
+        #[inline]
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_seq(self)
+        }
+    }
+
+    impl<'de, T> Deserialize<'de> for Vec<T>
+    where
+        T: Deserialize<'de>,
+    {
+        #[inline]
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            struct VecVisitor<T> {
+                _marker: PhantomData<T>,
+            }
+
+            impl<'de, T> Visitor<'de> for VecVisitor<T>
+            where
+                T: Deserialize<'de>,
+            {
+                type Value = Vec<T>;
+
+                #[inline]
+                fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.write_str("a sequence")
+                }
+
+                #[inline]
+                fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                where
+                    A: SeqAccess<'de>,
+                {
+                    let cap = seq.size_hint().unwrap_or(8).min(4096);
+                    let mut values = Vec::try_with_capacity(cap).map_err(A::Error::custom)?;
+
+                    while let Some(value) = seq.next_element()? {
+                        values.try_push(value).map_err(A::Error::custom)?;
+                    }
+
+                    Ok(values)
+                }
+            }
+
+            let visitor = VecVisitor { _marker: PhantomData };
+            deserializer.deserialize_seq(visitor)
+        }
+
+        #[inline]
+        fn deserialize_in_place<D>(deserializer: D, place: &mut Self) -> Result<(), D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            pub struct InPlaceSeed<'a, T: 'a>(pub &'a mut T);
+
+            impl<'a, 'de, T> DeserializeSeed<'de> for InPlaceSeed<'a, T>
+            where
+                T: Deserialize<'de>,
+            {
+                type Value = ();
+                fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    T::deserialize_in_place(deserializer, self.0)
+                }
+            }
+
+            struct VecInPlaceVisitor<'a, T: 'a>(&'a mut Vec<T>);
+
+            impl<'a, 'de, T> Visitor<'de> for VecInPlaceVisitor<'a, T>
+            where
+                T: Deserialize<'de>,
+            {
+                type Value = ();
+
+                #[inline]
+                fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.write_str("a sequence")
+                }
+
+                #[inline]
+                fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                where
+                    A: SeqAccess<'de>,
+                {
+                    let hint = seq.size_hint().unwrap_or(8).min(4096);
+                    if let Some(additional) = hint.checked_sub(self.0.len()) {
+                        self.0.try_reserve(additional).map_err(A::Error::custom)?;
+                    }
+
+                    for i in 0..self.0.len() {
+                        let next = {
+                            let next_place = InPlaceSeed(&mut self.0[i]);
+                            seq.next_element_seed(next_place)?
+                        };
+                        if next.is_none() {
+                            self.0.truncate(i);
+                            return Ok(());
+                        }
+                    }
+
+                    while let Some(value) = seq.next_element()? {
+                        self.0.try_push(value).map_err(A::Error::custom)?;
+                    }
+
+                    Ok(())
+                }
+            }
+
+            deserializer.deserialize_seq(VecInPlaceVisitor(place))
+        }
+    }
+}
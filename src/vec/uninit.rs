@@ -0,0 +1,155 @@
+use std::fmt;
+use std::mem::{self, MaybeUninit};
+use std::ptr;
+use std::slice;
+
+/// A writer into the spare capacity of a vector, which tracks how many slots
+/// have been initialized.
+///
+/// This `struct` is given to the closure of [`Vec::try_extend_uninit`] and
+/// [`SmallVec::try_extend_uninit`]. Only the elements written through it are
+/// appended to the vector.
+///
+/// [`Vec::try_extend_uninit`]: super::Vec::try_extend_uninit
+/// [`SmallVec::try_extend_uninit`]: crate::smallvec::SmallVec::try_extend_uninit
+pub struct UninitWriter<'a, T> {
+    buf: &'a mut [MaybeUninit<T>],
+    filled: usize,
+}
+
+impl<'a, T> UninitWriter<'a, T> {
+    #[inline]
+    pub(crate) fn new(buf: &'a mut [MaybeUninit<T>]) -> Self {
+        UninitWriter { buf, filled: 0 }
+    }
+
+    /// Returns the total number of slots of the writer.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns the number of elements written so far.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.filled
+    }
+
+    /// Returns `true` if no elements have been written yet.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.filled == 0
+    }
+
+    /// Returns the number of slots that have not been written yet.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.filled
+    }
+
+    /// Returns the elements written so far.
+    #[inline]
+    pub fn filled(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.buf.as_ptr().cast::<T>(), self.filled) }
+    }
+
+    /// Returns the elements written so far as a mutable slice.
+    #[inline]
+    pub fn filled_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.buf.as_mut_ptr().cast::<T>(), self.filled) }
+    }
+
+    /// Returns the slots that have not been written yet.
+    ///
+    /// Slots initialized through this slice are only appended to the vector
+    /// after a call to [`advance`](UninitWriter::advance).
+    #[inline]
+    pub fn unfilled_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        &mut self.buf[self.filled..]
+    }
+
+    /// Writes an element into the next slot if there is one left, otherwise
+    /// an error is returned with the element.
+    #[inline]
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        match self.buf.get_mut(self.filled) {
+            Some(slot) => {
+                slot.write(value);
+                self.filled += 1;
+                Ok(())
+            }
+            None => Err(value),
+        }
+    }
+
+    /// Copies all elements in a slice into the next slots if there are enough
+    /// of them left, otherwise nothing is written and an error is returned
+    /// with the slice.
+    #[inline]
+    pub fn extend_from_slice<'b>(&mut self, other: &'b [T]) -> Result<(), &'b [T]>
+    where
+        T: Copy,
+    {
+        let count = other.len();
+        if self.remaining() < count {
+            return Err(other);
+        }
+        unsafe {
+            let dst = self.buf.as_mut_ptr().add(self.filled).cast::<T>();
+            ptr::copy_nonoverlapping(other.as_ptr(), dst, count);
+        }
+        self.filled += count;
+        Ok(())
+    }
+
+    /// Marks the first `n` unfilled slots as written.
+    ///
+    /// # Safety
+    ///
+    /// The first `n` slots of [`unfilled_mut`](UninitWriter::unfilled_mut)
+    /// must have been initialized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the number of remaining slots.
+    #[inline]
+    pub unsafe fn advance(&mut self, n: usize) {
+        assert!(
+            n <= self.remaining(),
+            "advance (is {}) should be <= remaining (is {})",
+            n,
+            self.remaining()
+        );
+        self.filled += n;
+    }
+
+    /// Consumes the writer without dropping the written elements, and returns
+    /// their number.
+    #[inline]
+    pub(crate) fn into_len(self) -> usize {
+        let filled = self.filled;
+        mem::forget(self);
+        filled
+    }
+}
+
+impl<T> Drop for UninitWriter<'_, T> {
+    /// Drops the written elements if the writer is not committed, e.g. when
+    /// the closure writing into it panics.
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(self.filled_mut());
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for UninitWriter<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UninitWriter")
+            .field("filled", &self.filled())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
@@ -0,0 +1,208 @@
+//! arrayvec test case
+
+use fallacy::alloc::{AllocError, Layout};
+use fallacy::arrayvec::{ArrayString, ArrayVec, ExtendError};
+use fallacy::iter::IteratorExt;
+use std::fmt::Write;
+use std::mem;
+use std::rc::Rc;
+
+#[test]
+fn test_array_vec() {
+    let mut v: ArrayVec<u32, 3> = ArrayVec::new();
+    v.try_push(1).unwrap();
+    v.try_extend_from_slice(&[2, 3]).unwrap();
+    assert!(v.is_full());
+    assert_eq!(v.try_push(4).unwrap_err().element(), 4);
+    assert!(v.try_extend_from_slice(&[4]).is_err());
+    assert_eq!(v.try_insert(0, 0).unwrap_err().element(), 0);
+
+    assert_eq!(v.remove(0), 1);
+    v.try_insert(1, 5).unwrap();
+    assert_eq!(v.as_slice(), [2, 5, 3]);
+    assert_eq!(v.into_inner().unwrap(), [2, 5, 3]);
+
+    let v: ArrayVec<String, 4> = ["a".to_string(), "b".to_string()].into_iter().try_collect().unwrap();
+    let mut iter = v.into_iter();
+    assert_eq!(iter.next().as_deref(), Some("a"));
+}
+
+#[test]
+fn test_array_string() {
+    let mut s = ArrayString::<8>::new();
+    write!(s, "{}+{}", 12, 34).unwrap();
+    assert_eq!(s, "12+34");
+    assert!(write!(s, "{}", 5678).is_err());
+
+    s.clear();
+    s.try_push_str("ab").unwrap();
+    s.try_push('é').unwrap();
+    assert_eq!(s.len(), 4);
+    assert_eq!(s.try_push_str("abcde").unwrap_err().element(), "abcde");
+    assert_eq!(s.pop(), Some('é'));
+    assert_eq!(s, "ab");
+}
+
+#[test]
+fn test_array_vec_range_ops() {
+    let mut v: ArrayVec<u32, 8> = ArrayVec::new();
+    v.try_extend_from_slice(&[1, 1, 2, 3, 3]).unwrap();
+    v.dedup();
+    assert_eq!(v.as_slice(), [1, 2, 3]);
+
+    v.try_extend_from_within(1..).unwrap();
+    assert_eq!(v.as_slice(), [1, 2, 3, 2, 3]);
+    assert!(v.try_extend_from_within(..4).is_err());
+    assert_eq!(v.len(), 5);
+
+    let tail = v.split_off(3);
+    assert_eq!(tail.as_slice(), [2, 3]);
+    v.dedup_by_key(|x| *x / 2);
+    assert_eq!(v.as_slice(), [1, 2]);
+
+    let mut v: ArrayVec<String, 4> = ["a", "b", "c", "d"]
+        .iter()
+        .map(|s| s.to_string())
+        .try_collect()
+        .unwrap();
+    let mut drain = v.drain(1..3);
+    assert_eq!(drain.next().as_deref(), Some("b"));
+    drop(drain);
+    assert_eq!(v.as_slice(), ["a", "d"]);
+}
+
+#[test]
+fn test_array_string_edit() {
+    let mut s = ArrayString::<8>::try_from_str("héo").unwrap();
+    s.try_insert(3, 'l').unwrap();
+    s.try_insert_str(4, "l,").unwrap();
+    assert_eq!(s, "héll,o");
+    assert_eq!(s.try_insert(0, 'é').unwrap_err().element(), 'é');
+    assert_eq!(s.try_insert_str(0, "abc").unwrap_err().element(), "abc");
+    assert_eq!(s, "héll,o");
+
+    assert_eq!(s.remove(1), 'é');
+    s.retain(|c| c != 'l');
+    assert_eq!(s, "h,o");
+
+    let tail = s.split_off(1);
+    assert_eq!(s, "h");
+    assert_eq!(tail, ",o");
+}
+
+#[test]
+fn test_array_vec_drain_early_drop() {
+    let rc = Rc::new(());
+    let mut v: ArrayVec<Rc<()>, 6> = ArrayVec::new();
+    for _ in 0..6 {
+        v.try_push(rc.clone()).unwrap();
+    }
+
+    // the items not yielded are dropped with the iterator
+    let mut drain = v.drain(1..5);
+    assert_eq!(drain.len(), 4);
+    let first = drain.next().unwrap();
+    drop(drain);
+    assert_eq!(v.len(), 2);
+    assert_eq!(Rc::strong_count(&rc), 4);
+    drop(first);
+
+    // a leaked iterator leaks the drained range and the tail
+    mem::forget(v.drain(..1));
+    assert!(v.is_empty());
+    assert_eq!(Rc::strong_count(&rc), 3);
+}
+
+#[test]
+fn test_array_vec_retain() {
+    let rc = Rc::new(());
+    let mut v: ArrayVec<(u32, Rc<()>), 6> = (0..6).map(|i| (i, rc.clone())).try_collect().unwrap();
+    v.retain(|(i, _)| i % 3 != 0);
+    assert_eq!(v.iter().map(|(i, _)| *i).collect::<std::vec::Vec<_>>(), [1, 2, 4, 5]);
+    assert_eq!(Rc::strong_count(&rc), 5);
+
+    v.retain_mut(|(i, _)| {
+        *i *= 10;
+        *i > 20
+    });
+    assert_eq!(v.iter().map(|(i, _)| *i).collect::<std::vec::Vec<_>>(), [40, 50]);
+    assert_eq!(Rc::strong_count(&rc), 3);
+}
+
+#[test]
+fn test_capacity_error_element() {
+    let mut v: ArrayVec<String, 2> = ArrayVec::new();
+    v.try_extend(["a".to_string(), "b".to_string()]).unwrap();
+    let err = v.try_push("c".to_string()).unwrap_err();
+    assert_eq!(err.element(), "c");
+    let err = v.try_insert(0, "d".to_string()).unwrap_err();
+    assert_eq!(err.to_string(), "insufficient capacity");
+    assert_eq!(err.element(), "d");
+
+    v.pop();
+    let err = v.try_extend(["e".to_string(), "f".to_string()]).unwrap_err();
+    assert_eq!(err.element(), "f");
+    assert_eq!(v.as_slice(), ["a", "e"]);
+
+    let err = ArrayString::<2>::try_from_str("abc").unwrap_err();
+    assert_eq!(err.element(), "abc");
+}
+
+#[test]
+fn test_array_vec_vec_api() {
+    let mut v: ArrayVec<String, 4> = ArrayVec::new();
+    let strings = [String::from("a"), String::from("b")];
+    v.try_extend_from_slice(&strings).unwrap();
+    assert!(matches!(
+        v.try_extend_from_slice(&[String::new(), String::new(), String::new()]),
+        Err(ExtendError::Capacity(_))
+    ));
+    assert_eq!(v.as_slice(), ["a", "b"]);
+
+    v.try_resize(4, String::from("c")).unwrap();
+    assert_eq!(v.as_slice(), ["a", "b", "c", "c"]);
+    assert!(v.try_resize(5, String::new()).is_err());
+    v.try_resize_with(1, || unreachable!()).unwrap();
+
+    let err = v.try_resize_with(3, || Err(AllocError::new(Layout::new::<String>())));
+    assert!(matches!(err, Err(ExtendError::Alloc(_))));
+    assert_eq!(v.as_slice(), ["a"]);
+
+    let mut other: ArrayVec<String, 4> = ArrayVec::new();
+    other.try_push(String::from("d")).unwrap();
+    v.try_append(&mut other).unwrap();
+    assert!(other.is_empty());
+    assert_eq!(v.as_slice(), ["a", "d"]);
+
+    let mut v: ArrayVec<u32, 6> = ArrayVec::try_from(&[1, 2, 3][..]).unwrap();
+    v.try_splice(1..2, [7, 8, 9]).unwrap();
+    assert_eq!(v.as_slice(), [1, 7, 8, 9, 3]);
+
+    // an iterator that yields more than its size hint, until the vector is full
+    assert!(v.try_splice(..1, (0..4).filter(|_| true)).is_err());
+    assert_eq!(v.as_slice(), [0, 1, 7, 8, 9, 3]);
+    assert!(v.try_splice(.., [0; 7]).is_err());
+    assert_eq!(v.len(), 6);
+
+    v.truncate(2);
+    let spare = v.spare_capacity_mut();
+    assert_eq!(spare.len(), 4);
+    spare[0].write(5);
+    unsafe { v.set_len(3) };
+    assert_eq!(v.as_slice(), [0, 1, 5]);
+}
+
+#[test]
+fn test_array_string_write_overflow() {
+    let mut s = ArrayString::<4>::new();
+    assert!(write!(s, "{}", 12345).is_err());
+    // a failed write never runs past the buffer
+    assert!(s.len() <= 4);
+
+    s.clear();
+    write!(s, "ab").unwrap();
+    assert!(s.write_char('é').is_ok());
+    assert!(s.write_char('c').is_err());
+    assert!(s.write_str("").is_ok());
+    assert_eq!(s, "abé");
+}
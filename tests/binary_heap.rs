@@ -0,0 +1,70 @@
+//! binary_heap test case
+
+use fallacy::clone::TryClone;
+use fallacy::collections::binary_heap::PeekMut;
+use fallacy::collections::{BinaryHeap, IndexedBinaryHeap};
+use fallacy::vec::Vec;
+use std::cmp::Reverse;
+use std::mem;
+
+#[test]
+fn test_binary_heap() {
+    let mut vec = Vec::new();
+    vec.try_extend_from_slice(&[3, 1, 4, 1, 5, 9, 2, 6]).unwrap();
+    let mut heap = BinaryHeap::from(vec);
+    heap.try_push(7).unwrap();
+    heap.try_extend([0, 8]).unwrap();
+    assert_eq!(heap.peek(), Some(&9));
+
+    {
+        let mut top = heap.peek_mut().unwrap();
+        *top = 0;
+    }
+    assert_eq!(heap.peek(), Some(&8));
+    assert_eq!(PeekMut::pop(heap.peek_mut().unwrap()), 8);
+    assert_eq!(heap.pop(), Some(7));
+
+    let clone = heap.try_clone().unwrap();
+    let sorted = clone.into_sorted_vec();
+    assert_eq!(sorted.as_slice(), [0, 0, 1, 1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn test_peek_mut_leak() {
+    let mut vec = Vec::new();
+    vec.try_extend_from_slice(&[5, 3, 4, 1]).unwrap();
+    let mut heap = BinaryHeap::from(vec);
+
+    // a shared peek leaves the heap as it is
+    mem::forget(heap.peek_mut().unwrap());
+    assert_eq!(heap.len(), 4);
+
+    // a leaked mutable peek leaks the other items, not the heap order
+    let mut top = heap.peek_mut().unwrap();
+    *top = 0;
+    mem::forget(top);
+    assert_eq!(heap.len(), 1);
+    assert_eq!(heap.pop(), Some(0));
+
+    heap.try_extend([2, 7, 6]).unwrap();
+    *heap.peek_mut().unwrap() = 1;
+    assert_eq!(heap.into_sorted_vec().as_slice(), [1, 2, 6]);
+}
+
+#[test]
+fn test_indexed_binary_heap() {
+    let mut heap = IndexedBinaryHeap::new();
+    heap.try_push(0, Reverse(10)).unwrap();
+    heap.try_push(3, Reverse(5)).unwrap();
+    heap.try_push(7, Reverse(8)).unwrap();
+    assert_eq!(heap.peek(), Some((3, &Reverse(5))));
+
+    assert_eq!(heap.change_priority(0, Reverse(1)), Some(Reverse(10)));
+    assert_eq!(heap.try_push(7, Reverse(2)).unwrap(), Some(Reverse(8)));
+    assert_eq!(heap.remove(3), Some(Reverse(5)));
+    assert!(!heap.contains_key(3));
+
+    assert_eq!(heap.pop(), Some((0, Reverse(1))));
+    assert_eq!(heap.pop(), Some((7, Reverse(2))));
+    assert_eq!(heap.pop(), None);
+}
@@ -0,0 +1,286 @@
+//! btree_map test case
+
+use fallacy::clone::TryClone;
+use fallacy::collections::btree_map::Entry;
+use fallacy::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap as StdBTreeMap, BTreeSet as StdBTreeSet};
+
+fn check(map: &BTreeMap<u32, u32>, expected: &StdBTreeMap<u32, u32>) {
+    assert_eq!(map.len(), expected.len());
+    assert!(map.iter().eq(expected.iter()));
+    assert!(map.iter().rev().eq(expected.iter().rev()));
+}
+
+#[test]
+fn test_btree_map() {
+    let mut map = BTreeMap::new();
+    let mut expected = StdBTreeMap::new();
+    let mut seed = 1u32;
+    for i in 0..3000 {
+        seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+        let key = (seed >> 16) % 1000;
+        if i % 3 == 2 {
+            assert_eq!(map.remove(&key), expected.remove(&key));
+        } else {
+            assert_eq!(map.try_insert(key, i).unwrap(), expected.insert(key, i));
+        }
+    }
+    check(&map, &expected);
+    assert_eq!(map.first_key_value(), expected.first_key_value());
+    assert_eq!(map.last_key_value(), expected.last_key_value());
+
+    assert!(map.range(100..=300).eq(expected.range(100..=300)));
+    assert!(map.range(..500).rev().eq(expected.range(..500).rev()));
+    assert_eq!(map.range(2000..).next(), None);
+
+    for (_, v) in map.range_mut(200..400) {
+        *v = 0;
+    }
+    for (_, v) in expected.range_mut(200..400) {
+        *v = 0;
+    }
+    check(&map, &expected);
+
+    map.retain(|k, _| k % 7 != 0);
+    expected.retain(|k, _| k % 7 != 0);
+    check(&map, &expected);
+
+    let clone = map.try_clone().unwrap();
+    assert_eq!(clone, map);
+
+    let mut tail = map.try_split_off(&600).unwrap();
+    let mut expected_tail = expected.split_off(&600);
+    check(&map, &expected);
+    check(&tail, &expected_tail);
+
+    map.try_append(&mut tail).unwrap();
+    expected.append(&mut expected_tail);
+    assert!(tail.is_empty());
+    assert_eq!(map, clone);
+
+    while let Some((k, v)) = map.pop_first() {
+        assert_eq!(expected.pop_first(), Some((k, v)));
+        if let Some(last) = map.pop_last() {
+            assert_eq!(expected.pop_last(), Some(last));
+        }
+    }
+    assert!(expected.is_empty());
+}
+
+#[test]
+fn test_btree_map_entry() {
+    let mut map = BTreeMap::new();
+    for word in ["a", "b", "a", "c", "a", "b"] {
+        *map.try_entry(word).unwrap().or_insert(0) += 1;
+    }
+    assert_eq!(map[&"a"], 3);
+    assert_eq!(map[&"b"], 2);
+
+    match map.try_entry("c").unwrap() {
+        Entry::Occupied(entry) => assert_eq!(entry.remove(), 1),
+        Entry::Vacant(_) => unreachable!(),
+    }
+    assert!(!map.contains_key("c"));
+    assert_eq!(map.into_iter().collect::<Vec<_>>(), [("a", 3), ("b", 2)]);
+}
+
+#[test]
+fn test_btree_set() {
+    let mut set = BTreeSet::new();
+    for i in (0..100).rev() {
+        assert!(set.try_insert(i).unwrap());
+    }
+    assert!(!set.try_insert(10).unwrap());
+    assert!(set.remove(&10));
+    assert_eq!(set.range(8..13).copied().collect::<Vec<_>>(), [8, 9, 11, 12]);
+
+    let tail = set.try_split_off(&50).unwrap();
+    assert_eq!(set.last(), Some(&49));
+    assert_eq!(tail.first(), Some(&50));
+    assert_eq!(tail.len(), 50);
+}
+
+/// A deterministic pseudo-random number generator for the differential tests.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: u32) -> u32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) as u32) % bound
+    }
+}
+
+#[test]
+fn test_btree_map_differential() {
+    for (seed, key_space) in [(1, 64), (2, 700), (3, 5000)] {
+        let mut rng = Lcg(seed);
+        let mut map: BTreeMap<u32, u32> = BTreeMap::new();
+        let mut expected = StdBTreeMap::new();
+
+        for i in 0..20_000 {
+            let key = rng.next(key_space);
+            match rng.next(100) {
+                // grow faster than shrink, so the tree gets several levels deep
+                0..=44 => assert_eq!(map.try_insert(key, i).unwrap(), expected.insert(key, i)),
+                45..=74 => assert_eq!(map.remove(&key), expected.remove(&key)),
+                75..=79 => assert_eq!(map.remove_entry(&key), expected.remove_entry(&key)),
+                80..=84 => {
+                    let value = map.try_entry(key).unwrap().or_insert(i);
+                    *value += 1;
+                    *expected.entry(key).or_insert(i) += 1;
+                }
+                85..=87 => assert_eq!(map.pop_first(), expected.pop_first()),
+                88..=90 => assert_eq!(map.pop_last(), expected.pop_last()),
+                91..=94 => {
+                    let end = key + rng.next(key_space / 4 + 1);
+                    for (_, v) in map.range_mut(key..end) {
+                        *v = v.wrapping_mul(3);
+                    }
+                    for (_, v) in expected.range_mut(key..end) {
+                        *v = v.wrapping_mul(3);
+                    }
+                    assert!(map.range(key..=end).eq(expected.range(key..=end)));
+                    assert!(map.range(..key).rev().eq(expected.range(..key).rev()));
+                }
+                95 => {
+                    let m = rng.next(5) + 2;
+                    map.retain(|k, _| k % m != 0);
+                    expected.retain(|k, _| k % m != 0);
+                }
+                96..=97 => {
+                    let mut tail = map.try_split_off(&key).unwrap();
+                    let mut expected_tail = expected.split_off(&key);
+                    check(&map, &expected);
+                    check(&tail, &expected_tail);
+                    map.try_append(&mut tail).unwrap();
+                    expected.append(&mut expected_tail);
+                    assert!(tail.is_empty());
+                }
+                _ => {
+                    assert_eq!(map.get(&key), expected.get(&key));
+                    assert_eq!(map.get_key_value(&key), expected.get_key_value(&key));
+                    assert_eq!(map.first_key_value(), expected.first_key_value());
+                    assert_eq!(map.last_key_value(), expected.last_key_value());
+                }
+            }
+            assert_eq!(map.len(), expected.len());
+            if i % 1000 == 0 {
+                check(&map, &expected);
+            }
+        }
+        check(&map, &expected);
+        assert!(map.keys().eq(expected.keys()));
+        assert!(map.values().eq(expected.values()));
+        assert!(map.into_iter().eq(expected.into_iter()));
+    }
+}
+
+#[test]
+fn test_btree_map_append_overlapping() {
+    let mut rng = Lcg(7);
+    for _ in 0..50 {
+        let mut a = BTreeMap::new();
+        let mut b = BTreeMap::new();
+        let mut expected_a = StdBTreeMap::new();
+        let mut expected_b = StdBTreeMap::new();
+        for i in 0..rng.next(300) {
+            let key = rng.next(400);
+            a.try_insert(key, i).unwrap();
+            expected_a.insert(key, i);
+        }
+        for i in 0..rng.next(300) {
+            let key = rng.next(400);
+            b.try_insert(key, i + 1000).unwrap();
+            expected_b.insert(key, i + 1000);
+        }
+        // the values of `b` win on equal keys
+        a.try_append(&mut b).unwrap();
+        expected_a.append(&mut expected_b);
+        assert!(b.is_empty());
+        check(&a, &expected_a);
+    }
+}
+
+#[test]
+fn test_btree_map_free_list_reuse() {
+    use fallacy::mem::HeapSize;
+
+    let mut map = BTreeMap::new();
+    for key in 0..2000u32 {
+        map.try_insert(key, key).unwrap();
+    }
+    let size = map.heap_size();
+
+    // removing every entry puts the nodes on the free list, and inserting
+    // the same entries again takes them back without growing the arena
+    for round in 0..3 {
+        for key in 0..2000u32 {
+            assert_eq!(map.remove(&key), Some(key + round * 2000));
+        }
+        assert!(map.is_empty());
+        assert_eq!(map.heap_size(), size);
+        for key in 0..2000u32 {
+            map.try_insert(key, key + (round + 1) * 2000).unwrap();
+        }
+        assert_eq!(map.heap_size(), size);
+    }
+
+    map.clear();
+    assert!(map.is_empty());
+    assert_eq!(map.iter().next(), None);
+}
+
+#[test]
+fn test_btree_set_operations() {
+    let mut rng = Lcg(11);
+    let mut set = BTreeSet::new();
+    let mut expected = StdBTreeSet::new();
+    for _ in 0..5000 {
+        let value = rng.next(1000);
+        if rng.next(3) == 0 {
+            assert_eq!(set.remove(&value), expected.remove(&value));
+        } else {
+            assert_eq!(set.try_insert(value).unwrap(), expected.insert(value));
+        }
+    }
+    assert_eq!(set.len(), expected.len());
+    assert!(set.iter().eq(expected.iter()));
+    assert!(set.iter().rev().eq(expected.iter().rev()));
+    assert_eq!(set.first(), expected.first());
+    assert_eq!(set.last(), expected.last());
+    assert!(set.range(100..200).eq(expected.range(100..200)));
+
+    for value in 0..1000 {
+        assert_eq!(set.contains(&value), expected.contains(&value));
+        assert_eq!(set.get(&value), expected.get(&value));
+    }
+    let value = *expected.iter().nth(10).unwrap();
+    assert_eq!(set.take(&value), expected.take(&value));
+    assert_eq!(set.take(&value), None);
+
+    set.retain(|v| v % 2 == 0);
+    expected.retain(|v| v % 2 == 0);
+    assert!(set.iter().eq(expected.iter()));
+
+    assert_eq!(set.pop_first(), expected.pop_first());
+    assert_eq!(set.pop_last(), expected.pop_last());
+
+    let mut tail = set.try_split_off(&500).unwrap();
+    let mut expected_tail = expected.split_off(&500);
+    assert!(set.iter().eq(expected.iter()));
+    assert!(tail.iter().eq(expected_tail.iter()));
+
+    tail.try_insert(1).unwrap();
+    expected_tail.insert(1);
+    set.try_append(&mut tail).unwrap();
+    expected.append(&mut expected_tail);
+    assert!(tail.is_empty());
+    assert!(set.iter().eq(expected.iter()));
+
+    let clone = set.try_clone().unwrap();
+    assert_eq!(clone, set);
+    assert!(set.into_iter().eq(expected.into_iter()));
+}
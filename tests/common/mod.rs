@@ -0,0 +1,24 @@
+//! Fixtures shared by the integration tests.
+
+use std::alloc::{AllocError, Allocator, Global, Layout};
+use std::cell::Cell;
+use std::ptr::NonNull;
+
+/// An allocator whose allocations fail while `fail` is set.
+#[derive(Clone, Copy)]
+pub struct FailingAlloc<'a> {
+    pub fail: &'a Cell<bool>,
+}
+
+unsafe impl Allocator for FailingAlloc<'_> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if self.fail.get() {
+            return Err(AllocError);
+        }
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        Global.deallocate(ptr, layout)
+    }
+}
@@ -0,0 +1,59 @@
+//! ffi test case
+
+use fallacy::ffi::{FfiString, FfiVec};
+use fallacy::string::String;
+use fallacy::vec::Vec;
+
+#[test]
+fn test_raw_parts() {
+    let mut v = Vec::try_with_capacity(8).unwrap();
+    v.try_push(1u32).unwrap();
+    let (ptr, len, cap) = v.into_raw_parts();
+    let v = unsafe { Vec::from_raw_parts(ptr, len, cap) };
+    assert_eq!(v.as_slice(), &[1]);
+
+    let leaked = v.try_leak().unwrap();
+    let b = unsafe { Box::from_raw(leaked as *mut [u32]) };
+    assert_eq!(&*b, &[1]);
+
+    let s = String::try_from("hi").unwrap();
+    let (ptr, len, cap) = s.into_raw_parts();
+    let s = unsafe { String::from_raw_parts(ptr, len, cap) };
+    assert_eq!(s, "hi");
+}
+
+#[test]
+fn test_ffi_buffers() {
+    let mut v = FfiVec::<u8>::new();
+    v.try_reserve(16).unwrap();
+    assert!(v.capacity() >= 16);
+    unsafe {
+        *v.as_mut_ptr() = 42;
+    }
+    let mut v = v.into_vec();
+    unsafe { v.set_len(1) };
+    assert_eq!(v.as_slice(), &[42]);
+
+    let mut s = FfiString::from(String::try_from("hello").unwrap());
+    s.try_push_str(", world").unwrap();
+    assert_eq!(s.as_str(), "hello, world");
+    assert_eq!(s.into_string(), "hello, world");
+}
+
+#[cfg(feature = "ffi")]
+#[test]
+fn test_extern_helpers() {
+    use fallacy::ffi::*;
+
+    let mut v = fallacy_ffi_vec_new();
+    assert!(fallacy_ffi_vec_try_reserve(Some(&mut v), 10));
+    assert!(v.capacity() >= 10);
+    fallacy_ffi_vec_free(Some(&mut v));
+    assert_eq!(v.capacity(), 0);
+    assert!(!fallacy_ffi_vec_try_reserve(None, 10));
+
+    let mut s = fallacy_ffi_string_new();
+    assert!(fallacy_ffi_string_try_reserve(Some(&mut s), 10));
+    fallacy_ffi_string_free(Some(&mut s));
+    assert!(s.is_empty());
+}
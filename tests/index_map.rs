@@ -0,0 +1,244 @@
+//! index_map test case
+
+#![feature(allocator_api)]
+
+mod common;
+
+use common::FailingAlloc;
+use fallacy::clone::TryClone;
+use fallacy::collections::index_map::Entry;
+use fallacy::collections::{IndexMap, IndexSet};
+use std::cell::Cell;
+use std::hash::{BuildHasher, Hasher};
+use std::panic::{self, AssertUnwindSafe};
+
+/// A hasher that maps every key to one of the last three slots of the table,
+/// so that all entries share long probe sequences wrapping around the end.
+#[derive(Clone, Copy, Default)]
+struct CollidingHasher(u64);
+
+impl Hasher for CollidingHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = self.0.wrapping_mul(31).wrapping_add(u64::from(b));
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        u64::MAX - self.0 % 3
+    }
+}
+
+impl BuildHasher for CollidingHasher {
+    type Hasher = CollidingHasher;
+
+    fn build_hasher(&self) -> CollidingHasher {
+        CollidingHasher(0)
+    }
+}
+
+#[test]
+fn test_index_map() {
+    let mut map = IndexMap::new();
+    for i in (0..100).rev() {
+        assert_eq!(map.try_insert(i, i * 10).unwrap(), None);
+    }
+    assert_eq!(map.try_insert(50, 0).unwrap(), Some(500));
+    assert_eq!(map.len(), 100);
+    assert_eq!(map.get_index(0), Some((&99, &990)));
+    assert_eq!(map.get_index_of(&50), Some(49));
+    assert_eq!(map[&50], 0);
+
+    assert_eq!(map.swap_remove(&99), Some(990));
+    assert_eq!(map.get_index(0), Some((&0, &0)));
+    assert_eq!(map.shift_remove(&98), Some(980));
+    assert_eq!(map.get_index(0), Some((&0, &0)));
+    assert_eq!(map.get_index(1), Some((&97, &970)));
+    for i in 0..98 {
+        assert!(map.contains_key(&i));
+    }
+
+    map.try_sort_keys().unwrap();
+    assert!(map.keys().copied().eq(0..98));
+    assert_eq!(map.get_index_of(&42), Some(42));
+
+    map.retain(|k, _| k % 2 == 0);
+    assert!(map.keys().copied().eq((0..98).step_by(2)));
+    assert_eq!(map.get(&42), Some(&420));
+    assert_eq!(map.get(&43), None);
+
+    let clone = map.try_clone().unwrap();
+    assert_eq!(clone, map);
+}
+
+#[test]
+fn test_index_map_entry() {
+    let mut map = IndexMap::new();
+    for word in ["b", "a", "b", "c", "b"] {
+        *map.try_entry(word).unwrap().or_insert(0) += 1;
+    }
+    assert_eq!(map.iter().collect::<Vec<_>>(), [(&"b", &3), (&"a", &1), (&"c", &1)]);
+
+    match map.try_entry("a").unwrap() {
+        Entry::Occupied(entry) => {
+            assert_eq!(entry.index(), 1);
+            assert_eq!(entry.shift_remove(), 1);
+        }
+        Entry::Vacant(_) => unreachable!(),
+    }
+    assert_eq!(map.into_iter().collect::<Vec<_>>(), [("b", 3), ("c", 1)]);
+}
+
+#[test]
+fn test_index_set() {
+    let mut set = IndexSet::new();
+    assert!(set.try_insert("z").unwrap());
+    assert!(set.try_insert("y").unwrap());
+    assert!(!set.try_insert("z").unwrap());
+    assert_eq!(set.try_insert_full("x").unwrap(), (2, true));
+    assert_eq!(set[1], "y");
+    set.try_sort().unwrap();
+    assert_eq!(set.iter().copied().collect::<Vec<_>>(), ["x", "y", "z"]);
+    assert!(set.swap_remove("x"));
+    assert_eq!(set.iter().copied().collect::<Vec<_>>(), ["z", "y"]);
+}
+
+#[test]
+fn test_index_map_remove_across_resize() {
+    let mut map = IndexMap::new();
+    for i in 0..10 {
+        map.try_insert(i, i).unwrap();
+    }
+    let mut expected: Vec<i32> = (0..10).collect();
+    for i in 10..1000 {
+        map.try_insert(i, i).unwrap();
+        expected.push(i);
+        if i % 3 == 0 {
+            let key = expected.swap_remove(i as usize % expected.len());
+            assert_eq!(map.swap_remove(&key), Some(key));
+        } else if i % 5 == 0 {
+            let key = expected.remove(i as usize % expected.len());
+            assert_eq!(map.shift_remove(&key), Some(key));
+        }
+    }
+    assert!(map.keys().eq(expected.iter()));
+    for (index, key) in expected.iter().enumerate() {
+        assert_eq!(map.get_full(key), Some((index, key, key)));
+    }
+    for i in 0..1000 {
+        assert_eq!(map.contains_key(&i), expected.contains(&i));
+    }
+}
+
+#[test]
+fn test_index_map_deletion_chains() {
+    let mut map = IndexMap::with_hasher(CollidingHasher::default());
+    for i in 0..40u32 {
+        map.try_insert(i, i).unwrap();
+    }
+    // Removing from the middle of the chains must shift the later entries
+    // back so that no lookup stops at the freed slot.
+    for i in (0..40).step_by(3) {
+        assert_eq!(map.swap_remove(&i), Some(i));
+        for j in 0..40 {
+            assert_eq!(map.get(&j).is_some(), j > i || j % 3 != 0);
+        }
+    }
+    for i in (1..40).step_by(3) {
+        assert_eq!(map.shift_remove(&i), Some(i));
+    }
+    assert!(map.keys().all(|k| k % 3 == 2));
+    for i in 0..40 {
+        assert_eq!(map.get(&i), (i % 3 == 2).then_some(&i));
+    }
+    for i in 40..60 {
+        map.try_insert(i, i).unwrap();
+    }
+    for i in 40..60 {
+        assert_eq!(map.get_index_of(&i), Some(i as usize - 27));
+    }
+}
+
+#[test]
+fn test_index_map_panic_rebuilds_indices() {
+    let mut map = IndexMap::new();
+    for i in 0..50 {
+        map.try_insert(i, i).unwrap();
+    }
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        map.retain(|&k, _| {
+            assert!(k < 20, "retain panic");
+            k % 2 == 0
+        })
+    }));
+    assert!(res.is_err());
+    for (index, (key, _)) in map.iter().enumerate() {
+        assert_eq!(map.get_index_of(key), Some(index));
+    }
+    for i in (1..20).step_by(2) {
+        assert!(!map.contains_key(&i));
+    }
+    for i in 20..50 {
+        assert_eq!(map.get(&i), Some(&i));
+    }
+
+    let mut calls = 0;
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        map.try_sort_by(|_, _, b, _| {
+            calls += 1;
+            assert!(calls < 20, "sort panic");
+            b.cmp(&0).reverse()
+        })
+    }));
+    assert!(res.is_err());
+    assert_eq!(map.len(), 40);
+    for (index, (key, value)) in map.iter().enumerate() {
+        assert_eq!(map.get_full(key), Some((index, key, value)));
+    }
+
+    let mut set: IndexSet<i32> = IndexSet::new();
+    for i in 0..50 {
+        set.try_insert(i).unwrap();
+    }
+    let res = panic::catch_unwind(AssertUnwindSafe(|| set.retain(|&v| v < 40 || panic!())));
+    assert!(res.is_err());
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        set.try_sort_by(|a, b| if *a == 25 { panic!() } else { b.cmp(a) })
+    }));
+    assert!(res.is_err());
+    for (index, value) in set.iter().enumerate() {
+        assert_eq!(set.get_index_of(value), Some(index));
+    }
+    for i in 0..40 {
+        assert!(set.contains(&i));
+    }
+}
+
+#[test]
+fn test_index_map_try_entry_alloc_failure() {
+    let fail = Cell::new(false);
+    let mut map = IndexMap::with_hasher_in(CollidingHasher::default(), FailingAlloc { fail: &fail });
+    let mut i = 0u32;
+    while map.len() < map.capacity() || map.is_empty() {
+        map.try_insert(i, i).unwrap();
+        i += 1;
+    }
+
+    fail.set(true);
+    assert!(map.try_entry(i).is_err());
+    assert!(map.try_insert(i, i).is_err());
+    assert_eq!(map.len(), i as usize);
+    match map.try_entry(0).unwrap() {
+        Entry::Occupied(mut entry) => *entry.get_mut() += 100,
+        Entry::Vacant(_) => unreachable!(),
+    }
+    assert_eq!(map.get(&i), None);
+
+    fail.set(false);
+    *map.try_entry(i).unwrap().or_insert(0) += 1;
+    assert_eq!(map.get(&i), Some(&1));
+    assert_eq!(map.get(&0), Some(&100));
+    for j in 1..i {
+        assert_eq!(map.get_index_of(&j), Some(j as usize));
+    }
+}
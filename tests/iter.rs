@@ -0,0 +1,46 @@
+//! iter test case
+
+use fallacy::alloc::AllocError;
+use fallacy::boxed::Box;
+use fallacy::collections::HashMap;
+use fallacy::prelude::*;
+
+#[test]
+fn test_try_collect() {
+    let v: Vec<i32> = (1..4).try_collect().unwrap();
+    assert_eq!(v.as_slice(), &[1, 2, 3]);
+
+    let b: Box<[i32]> = (1..4).filter(|x| x % 2 == 1).try_collect().unwrap();
+    assert_eq!(&*b, &[1, 3]);
+
+    let s: String = "abc".chars().rev().try_collect().unwrap();
+    assert_eq!(s, "cba");
+    let s: String = ["ab", "c"].into_iter().try_collect().unwrap();
+    assert_eq!(s, "abc");
+
+    let m: HashMap<i32, i32> = (1..4).map(|x| (x, x * x)).try_collect().unwrap();
+    assert_eq!(m.get(&3), Some(&9));
+
+    let r: Result<Vec<i32>, AllocError> = (1..4).map(Ok).try_collect();
+    assert_eq!(r.unwrap().as_slice(), &[1, 2, 3]);
+}
+
+#[test]
+fn test_try_collect_error() {
+    #[derive(Debug, PartialEq)]
+    enum Error {
+        Alloc,
+        Odd(i32),
+    }
+
+    impl From<AllocError> for Error {
+        fn from(_: AllocError) -> Self {
+            Error::Alloc
+        }
+    }
+
+    let r: Result<Vec<i32>, Error> = (2..6)
+        .map(|x| if x % 2 == 0 { Ok(x) } else { Err(Error::Odd(x)) })
+        .try_collect();
+    assert_eq!(r.unwrap_err(), Error::Odd(3));
+}
@@ -0,0 +1,173 @@
+//! linked_list test case
+
+#![feature(allocator_api)]
+
+use fallacy::alloc::InterchangeableAllocator;
+use fallacy::clone::TryClone;
+use fallacy::collections::LinkedList;
+use std::alloc::{AllocError, Allocator, Global, Layout};
+use std::cell::Cell;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[test]
+fn test_linked_list() {
+    let mut list = LinkedList::new();
+    list.try_push_back(2).unwrap();
+    list.try_push_back(3).unwrap();
+    list.try_push_front(1).unwrap();
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [1, 2, 3]);
+    assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), [3, 2, 1]);
+
+    let mut tail = list.split_off(1);
+    assert_eq!(list.len(), 1);
+    assert_eq!(tail.iter().copied().collect::<Vec<_>>(), [2, 3]);
+    list.append(&mut tail);
+    assert!(tail.is_empty());
+
+    let clone = list.try_clone().unwrap();
+    assert_eq!(clone, list);
+    assert_eq!(list.pop_front(), Some(1));
+    assert_eq!(list.pop_back(), Some(3));
+    assert_eq!(list.pop_back(), Some(2));
+    assert_eq!(list.pop_back(), None);
+}
+
+#[test]
+fn test_linked_list_cursor() {
+    let mut list = LinkedList::new();
+    list.try_extend([1, 2, 3, 4, 5]).unwrap();
+
+    // Move the element 4 to the front, as an LRU list would on access.
+    let mut cursor = list.cursor_front_mut();
+    while cursor.current() != Some(&mut 4) {
+        cursor.move_next();
+    }
+    assert_eq!(cursor.index(), Some(3));
+    let node = cursor.remove_current_as_list().unwrap();
+    assert_eq!(cursor.current(), Some(&mut 5));
+    let mut front = list.cursor_front_mut();
+    front.splice_before(node);
+    assert_eq!(front.index(), Some(1));
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [4, 1, 2, 3, 5]);
+
+    let mut cursor = list.cursor_back_mut();
+    cursor.try_insert_before(6).unwrap();
+    cursor.try_insert_after(7).unwrap();
+    assert_eq!(cursor.index(), Some(5));
+    cursor.move_prev();
+    let before = cursor.split_before();
+    assert_eq!(before.iter().copied().collect::<Vec<_>>(), [4, 1, 2, 3]);
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [6, 5, 7]);
+
+    let mut cursor = list.cursor_front_mut();
+    assert_eq!(cursor.remove_current(), Some(6));
+    let after = cursor.split_after();
+    assert_eq!(after.iter().copied().collect::<Vec<_>>(), [7]);
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [5]);
+}
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+
+/// An allocator counting the live allocations of all its values. The values
+/// are interchangeable because they all allocate from `Global`.
+#[derive(Clone, Copy)]
+struct Counting(u32);
+
+unsafe impl Allocator for Counting {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        LIVE.fetch_add(1, Ordering::Relaxed);
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        LIVE.fetch_sub(1, Ordering::Relaxed);
+        Global.deallocate(ptr, layout)
+    }
+}
+
+unsafe impl InterchangeableAllocator for Counting {}
+
+/// A pool counting its own live allocations.
+#[derive(Default)]
+struct Pool {
+    live: Cell<usize>,
+}
+
+unsafe impl Allocator for Pool {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.live.set(self.live.get() + 1);
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.live.set(self.live.get() - 1);
+        Global.deallocate(ptr, layout)
+    }
+}
+
+#[test]
+fn test_linked_list_distinct_allocators() {
+    let mut list = LinkedList::new_in(Counting(1));
+    let mut other = LinkedList::new_in(Counting(2));
+    list.try_extend([1, 2]).unwrap();
+    other.try_extend([3, 4]).unwrap();
+    list.append(&mut other);
+    other.try_push_back(0).unwrap();
+    list.cursor_front_mut().splice_before(other);
+    assert_eq!(list.allocator().0, 1);
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+    assert_eq!(LIVE.load(Ordering::Relaxed), 5);
+    drop(list);
+    assert_eq!(LIVE.load(Ordering::Relaxed), 0);
+
+    // `&Pool` is not interchangeable, two lists may only be joined when
+    // their nodes come from the same pool.
+    let pool = Pool::default();
+    let mut list = LinkedList::new_in(&pool);
+    let mut other = LinkedList::new_in(&pool);
+    list.try_extend([1, 2]).unwrap();
+    other.try_extend([3]).unwrap();
+    unsafe {
+        list.append_unchecked(&mut other);
+        list.cursor_back_mut().splice_after_unchecked(other);
+    }
+    let tail = list.split_off(1);
+    assert_eq!(tail.iter().copied().collect::<Vec<_>>(), [2, 3]);
+    drop((list, tail));
+    assert_eq!(pool.live.get(), 0);
+}
+
+#[test]
+fn test_linked_list_drop_panic() {
+    use std::cell::Cell;
+    use std::panic::{self, AssertUnwindSafe};
+
+    struct PanicOnDrop<'a> {
+        drops: &'a Cell<usize>,
+        panic: bool,
+    }
+
+    impl Drop for PanicOnDrop<'_> {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+            if self.panic {
+                panic!("panic in drop");
+            }
+        }
+    }
+
+    let drops = Cell::new(0);
+    let mut list = LinkedList::new();
+    for i in 0..5 {
+        list.try_push_back(PanicOnDrop {
+            drops: &drops,
+            panic: i == 1,
+        })
+        .unwrap();
+    }
+    // the elements after the one that panicked are still dropped
+    let res = panic::catch_unwind(AssertUnwindSafe(|| drop(list)));
+    assert!(res.is_err());
+    assert_eq!(drops.get(), 5);
+}
@@ -0,0 +1,56 @@
+//! mem test case
+
+use fallacy::boxed::Box;
+use fallacy::mem::HeapSize;
+use fallacy::string::String;
+use fallacy::sync::Arc;
+use fallacy::vec::Vec;
+use std::mem;
+
+#[test]
+fn test_heap_size() {
+    let mut v: Vec<String> = Vec::try_with_capacity(4).unwrap();
+    v.try_push(String::try_from("hello").unwrap()).unwrap();
+    v.try_push(String::try_from("world!").unwrap()).unwrap();
+    let strings: usize = v.iter().map(|s| s.capacity()).sum();
+    assert_eq!(v.heap_size(), 4 * mem::size_of::<String>() + strings);
+
+    let b = Box::try_new([1u64; 4]).unwrap();
+    assert_eq!(b.heap_size(), 32);
+
+    let a = Arc::try_new(0u64).unwrap();
+    let a2 = a.clone();
+    let shared = 2 * mem::size_of::<usize>() + 8;
+    assert_eq!(a.heap_size() + a2.heap_size(), shared);
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn test_derive_heap_size() {
+    #[derive(HeapSize)]
+    struct Named<T> {
+        id: u32,
+        items: Vec<T>,
+    }
+
+    #[derive(HeapSize)]
+    enum Node {
+        Leaf,
+        Text(String),
+        Pair { left: Box<Node>, right: Box<Node> },
+    }
+
+    let mut items = Vec::try_with_capacity(3).unwrap();
+    items.try_push(7u16).unwrap();
+    let named = Named { id: 1, items };
+    assert_eq!(named.id, 1);
+    assert_eq!(named.heap_size(), 6);
+
+    let text = String::try_from("abc").unwrap();
+    let cap = text.capacity();
+    let node = Node::Pair {
+        left: Box::try_new(Node::Leaf).unwrap(),
+        right: Box::try_new(Node::Text(text)).unwrap(),
+    };
+    assert_eq!(node.heap_size(), 2 * mem::size_of::<Node>() + cap);
+}
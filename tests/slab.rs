@@ -0,0 +1,208 @@
+//! slab test case
+
+#![feature(allocator_api)]
+
+mod common;
+
+use common::FailingAlloc;
+use fallacy::clone::TryClone;
+use fallacy::slab::Slab;
+use fallacy::try_vec;
+use std::cell::Cell;
+use std::rc::Rc;
+
+#[test]
+fn test_slab() {
+    let mut slab = Slab::new();
+    let a = slab.try_insert("a").unwrap();
+    let b = slab.try_insert("b").unwrap();
+    let c = slab.try_insert("c").unwrap();
+    assert_eq!((a, b, c), (0, 1, 2));
+    assert_eq!(slab[b], "b");
+
+    assert_eq!(slab.remove(b), "b");
+    assert_eq!(slab.try_remove(b), None);
+    assert!(!slab.contains(b));
+    assert_eq!(slab.vacant_key(), b);
+    assert_eq!(slab.try_insert("d").unwrap(), b);
+    assert_eq!(slab.len(), 3);
+
+    let entry = slab.try_vacant_entry().unwrap();
+    let key = entry.key();
+    assert_eq!(*entry.insert("e"), "e");
+    assert_eq!(slab.get(key), Some(&"e"));
+
+    slab.retain(|key, _| key % 2 == 0);
+    assert_eq!(slab.iter().collect::<Vec<_>>(), [(0, &"a"), (2, &"c")]);
+    assert_eq!(slab.try_insert("f").unwrap(), 3);
+    assert_eq!(slab.try_insert("g").unwrap(), 1);
+
+    let clone = slab.try_clone().unwrap();
+    assert_eq!(
+        clone.into_iter().rev().collect::<Vec<_>>(),
+        [(3, "f"), (2, "c"), (1, "g"), (0, "a")]
+    );
+}
+
+#[test]
+fn test_slab_key_reuse() {
+    let mut slab = Slab::new();
+    for i in 0..10 {
+        assert_eq!(slab.try_insert(i).unwrap(), i);
+    }
+    for key in [2, 7, 4, 9] {
+        assert_eq!(slab.remove(key), key);
+    }
+    assert_eq!(slab.len(), 6);
+
+    // The most recently freed key is reused first.
+    for key in [9, 4, 7, 2, 10, 11] {
+        assert_eq!(slab.vacant_key(), key);
+        assert_eq!(slab.try_insert(key * 100).unwrap(), key);
+    }
+    for key in 0..12 {
+        let value = if [2, 4, 7, 9, 10, 11].contains(&key) {
+            key * 100
+        } else {
+            key
+        };
+        assert_eq!(slab.get(key), Some(&value));
+    }
+    assert_eq!(slab.try_remove(12), None);
+    assert_eq!(slab.try_remove(3), Some(3));
+    assert_eq!(slab.try_remove(3), None);
+    assert_eq!(slab.vacant_key(), 3);
+}
+
+#[test]
+#[should_panic(expected = "invalid key")]
+fn test_slab_remove_vacant() {
+    let mut slab = Slab::new();
+    let key = slab.try_insert(1).unwrap();
+    slab.remove(key);
+    slab.remove(key);
+}
+
+#[test]
+fn test_slab_vacant_entry_dropped() {
+    let mut slab = Slab::new();
+    slab.try_insert("a").unwrap();
+    assert_eq!(slab.try_vacant_entry().unwrap().key(), 1);
+    assert_eq!(slab.len(), 1);
+    assert!(!slab.contains(1));
+    assert_eq!(slab.iter().count(), 1);
+    assert_eq!(slab.vacant_key(), 1);
+    assert_eq!(slab.try_insert("b").unwrap(), 1);
+
+    slab.remove(0);
+    assert_eq!(slab.try_vacant_entry().unwrap().key(), 0);
+    assert_eq!(slab.len(), 1);
+    assert_eq!(slab.vacant_key(), 0);
+    let entry = slab.try_vacant_entry().unwrap();
+    let key = entry.key();
+    assert_eq!(*entry.insert("c"), "c");
+    assert_eq!(key, 0);
+    assert_eq!(slab.iter().collect::<Vec<_>>(), [(0, &"c"), (1, &"b")]);
+}
+
+#[test]
+fn test_slab_retain() {
+    let rc = Rc::new(());
+    let mut slab = Slab::new();
+    for _ in 0..10 {
+        slab.try_insert(rc.clone()).unwrap();
+    }
+    slab.remove(3);
+    let mut seen = Vec::new();
+    slab.retain(|key, _| {
+        seen.push(key);
+        key % 3 != 0
+    });
+    assert_eq!(seen, [0, 1, 2, 4, 5, 6, 7, 8, 9]);
+    assert_eq!(Rc::strong_count(&rc), 7);
+    assert_eq!(slab.len(), 6);
+    assert_eq!(slab.iter().map(|(key, _)| key).collect::<Vec<_>>(), [1, 2, 4, 5, 7, 8]);
+
+    // Every removed key is back on the free list.
+    let mut keys: Vec<usize> = (0..4).map(|_| slab.try_insert(rc.clone()).unwrap()).collect();
+    keys.sort_unstable();
+    assert_eq!(keys, [0, 3, 6, 9]);
+    assert_eq!(slab.try_insert(rc.clone()).unwrap(), 10);
+
+    slab.retain(|_, _| false);
+    assert!(slab.is_empty());
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn test_slab_try_reserve_vacant() {
+    let fail = Cell::new(false);
+    let mut slab = Slab::try_with_capacity_in(8, FailingAlloc { fail: &fail }).unwrap();
+    for i in 0..8 {
+        slab.try_insert(i).unwrap();
+    }
+    for key in [1, 3, 5] {
+        slab.remove(key);
+    }
+
+    // The vacant slots are enough, so nothing is allocated.
+    fail.set(true);
+    slab.try_reserve(3).unwrap();
+    slab.try_reserve_exact(2).unwrap();
+    assert_eq!(slab.capacity(), 8);
+    for _ in 0..3 {
+        slab.try_insert(0).unwrap();
+    }
+    assert_eq!(slab.len(), 8);
+    assert!(slab.try_reserve(1).is_err());
+    assert!(slab.try_insert(0).is_err());
+    assert!(slab.try_vacant_entry().is_err());
+    assert_eq!(slab.len(), 8);
+
+    // Only the values beyond the vacant slots need new capacity.
+    fail.set(false);
+    slab.remove(0);
+    slab.remove(6);
+    slab.try_reserve_exact(5).unwrap();
+    assert!(slab.capacity() >= 11);
+    fail.set(true);
+    for _ in 0..5 {
+        slab.try_insert(1).unwrap();
+    }
+    assert_eq!(slab.len(), 11);
+}
+
+#[test]
+fn test_slab_try_clone() {
+    let mut slab = Slab::new();
+    for i in 0..6 {
+        slab.try_insert(try_vec![i; 2].unwrap()).unwrap();
+    }
+    slab.remove(4);
+    slab.remove(1);
+
+    let mut clone = slab.try_clone().unwrap();
+    assert_eq!(clone.len(), 4);
+    assert!(clone
+        .iter()
+        .map(|(k, v)| (k, v.as_slice()))
+        .eq(slab.iter().map(|(k, v)| (k, v.as_slice()))));
+    clone[0].try_push(10).unwrap();
+    assert_eq!(slab[0].as_slice(), &[0, 0]);
+    assert_eq!(clone[0].as_slice(), &[0, 0, 10]);
+
+    // The clone reuses the freed keys in the same order.
+    for slab in [&mut slab, &mut clone] {
+        assert_eq!(slab.try_insert(try_vec![].unwrap()).unwrap(), 1);
+        assert_eq!(slab.try_insert(try_vec![].unwrap()).unwrap(), 4);
+        assert_eq!(slab.try_insert(try_vec![].unwrap()).unwrap(), 6);
+    }
+
+    let fail = Cell::new(false);
+    let mut slab = Slab::new_in(FailingAlloc { fail: &fail });
+    slab.try_insert(1).unwrap();
+    fail.set(true);
+    assert!(slab.try_clone().is_err());
+    fail.set(false);
+    assert_eq!(slab.try_clone().unwrap().get(0), Some(&1));
+}
@@ -0,0 +1,148 @@
+//! slice test case
+
+use fallacy::prelude::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+
+/// The global allocator of this test binary, whose allocations on the current
+/// thread fail while `FAIL` is set.
+struct FailingGlobal;
+
+thread_local! {
+    static FAIL: Cell<bool> = const { Cell::new(false) };
+}
+
+unsafe impl GlobalAlloc for FailingGlobal {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if FAIL.with(Cell::get) {
+            return std::ptr::null_mut();
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: FailingGlobal = FailingGlobal;
+
+/// Runs `f` with every allocation on the current thread failing.
+fn failing<R>(f: impl FnOnce() -> R) -> R {
+    FAIL.with(|fail| fail.set(true));
+    let r = f();
+    FAIL.with(|fail| fail.set(false));
+    r
+}
+
+#[test]
+fn test_try_sort() {
+    let mut v: Vec<u32> = (0..500u32)
+        .map(|i| i.wrapping_mul(2654435761) % 97)
+        .try_collect()
+        .unwrap();
+    let mut expected = v.as_slice().to_vec();
+    expected.sort();
+    v.try_sort().unwrap();
+    assert_eq!(v.as_slice(), expected.as_slice());
+
+    let mut s = [5, 1, 4, 2, 3];
+    s.try_sort_by(|a, b| b.cmp(a)).unwrap();
+    assert_eq!(s, [5, 4, 3, 2, 1]);
+}
+
+#[test]
+fn test_try_sort_stable() {
+    let mut v: Vec<(u32, usize)> = (0..300).map(|i| ((i * 7 % 5) as u32, i)).try_collect().unwrap();
+    v.try_sort_by_key(|p| p.0).unwrap();
+    assert!(v
+        .windows(2)
+        .all(|w| w[0].0 < w[1].0 || (w[0].0 == w[1].0 && w[0].1 < w[1].1)));
+
+    let mut calls = 0;
+    v.try_sort_by_cached_key(|p| {
+        calls += 1;
+        p.1 % 3
+    })
+    .unwrap();
+    assert_eq!(calls, 300);
+    assert!(v.windows(2).all(|w| w[0].1 % 3 <= w[1].1 % 3));
+    assert!(v
+        .windows(2)
+        .all(|w| w[0].1 % 3 != w[1].1 % 3 || (w[0].0, w[0].1) < (w[1].0, w[1].1)));
+}
+
+#[test]
+fn test_try_sort_alloc_failure() {
+    let input: Vec<u32> = (0..100u32)
+        .map(|i| i.wrapping_mul(2654435761) % 31)
+        .try_collect()
+        .unwrap();
+    let mut v = input.try_clone().unwrap();
+
+    assert!(failing(|| v.try_sort()).is_err());
+    assert_eq!(v, input);
+    assert!(failing(|| v.try_sort_by(|a, b| b.cmp(a))).is_err());
+    assert_eq!(v, input);
+    assert!(failing(|| v.as_mut_slice().try_sort_by_key(|x| x % 7)).is_err());
+    assert_eq!(v, input);
+
+    // Short slices are sorted in place and never allocate.
+    let mut s = [3, 1, 2];
+    assert!(failing(|| s.try_sort()).is_ok());
+    assert_eq!(s, [1, 2, 3]);
+}
+
+#[test]
+fn test_try_sort_panic() {
+    /// An element that counts how many times it is dropped.
+    struct Item {
+        key: u32,
+        drops: Rc<Cell<usize>>,
+    }
+
+    impl Drop for Item {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+        }
+    }
+
+    const LEN: u32 = 200;
+
+    // Panic in the insertion sort of the first run, and in a later merge.
+    for panic_at in [5, 1500] {
+        let drops = Rc::new(Cell::new(0));
+        let mut v: Vec<Item> = (0..LEN)
+            .map(|i| Item {
+                key: i.wrapping_mul(2654435761) % LEN,
+                drops: drops.clone(),
+            })
+            .try_collect()
+            .unwrap();
+        let mut expected: Vec<u32> = v.iter().map(|item| item.key).try_collect().unwrap();
+
+        let mut calls = 0;
+        let r = panic::catch_unwind(AssertUnwindSafe(|| {
+            v.try_sort_by(|a, b| {
+                calls += 1;
+                if calls == panic_at {
+                    panic!("comparator panicked");
+                }
+                a.key.cmp(&b.key)
+            })
+        }));
+        assert!(r.is_err());
+        assert_eq!(drops.get(), 0);
+
+        let mut keys: Vec<u32> = v.iter().map(|item| item.key).try_collect().unwrap();
+        keys.try_sort().unwrap();
+        expected.try_sort().unwrap();
+        assert_eq!(keys, expected);
+
+        drop(v);
+        assert_eq!(drops.get(), LEN as usize);
+    }
+}
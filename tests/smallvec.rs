@@ -0,0 +1,264 @@
+//! smallvec test case
+
+#![feature(allocator_api)]
+#![feature(core_io_borrowed_buf)]
+
+mod common;
+
+use common::FailingAlloc;
+use fallacy::clone::TryClone;
+use fallacy::iter::IteratorExt;
+use fallacy::smallvec::SmallVec;
+use fallacy::vec::Vec;
+use std::cell::Cell;
+
+#[test]
+fn test_spill() {
+    let mut v: SmallVec<[u32; 4]> = SmallVec::new();
+    for i in 0..4 {
+        v.try_push(i).unwrap();
+    }
+    assert!(!v.spilled());
+    assert_eq!(v.capacity(), 4);
+
+    v.try_push(4).unwrap();
+    assert!(v.spilled());
+    assert_eq!(v.as_slice(), [0, 1, 2, 3, 4]);
+
+    v.try_insert(0, 9).unwrap();
+    assert_eq!(v.remove(1), 0);
+    assert_eq!(v.swap_remove(0), 9);
+    assert_eq!(v.as_slice(), [4, 1, 2, 3]);
+
+    v.try_shrink_to_fit().unwrap();
+    assert!(!v.spilled());
+    assert_eq!(v.as_slice(), [4, 1, 2, 3]);
+}
+
+#[test]
+fn test_vec_conversion() {
+    let mut vec: Vec<String> = Vec::try_with_capacity(8).unwrap();
+    vec.try_push("a".to_string()).unwrap();
+    let ptr = vec.as_ptr();
+
+    let v: SmallVec<[String; 2]> = SmallVec::from_vec(vec);
+    assert!(v.spilled());
+    let vec = v.into_vec().unwrap();
+    assert_eq!(vec.as_ptr(), ptr);
+    assert_eq!(vec.as_slice(), ["a".to_string()]);
+
+    let mut v: SmallVec<[String; 2]> = SmallVec::new();
+    v.try_push("b".to_string()).unwrap();
+    let vec = v.into_vec().unwrap();
+    assert_eq!(vec.as_slice(), ["b".to_string()]);
+
+    // a failed allocation gives the elements back
+    let fail = Cell::new(true);
+    let mut v: SmallVec<[String; 2], _> = SmallVec::new_in(FailingAlloc { fail: &fail });
+    v.try_push("c".to_string()).unwrap();
+    let (v, _) = v.into_vec().unwrap_err();
+    assert_eq!(v.as_slice(), ["c".to_string()]);
+}
+
+#[test]
+fn test_try_clone_and_into_iter() {
+    let mut v: SmallVec<[u64; 2]> = SmallVec::new();
+    v.try_resize(3, 7).unwrap();
+    v.retain_mut(|x| {
+        *x += 1;
+        true
+    });
+    v.retain(|x| *x == 8);
+    let c = v.try_clone().unwrap();
+    assert_eq!(c, v);
+
+    let mut iter = c.into_iter();
+    assert_eq!(iter.next(), Some(8));
+    assert_eq!(iter.next_back(), Some(8));
+    assert_eq!(iter.len(), 1);
+}
+
+#[test]
+fn test_vec_api() {
+    let mut v: SmallVec<[u32; 4]> = SmallVec::new();
+    v.try_copy_from_slice(&[1, 1, 2, 3]).unwrap();
+    v.dedup();
+    assert_eq!(v.as_slice(), [1, 2, 3]);
+
+    let mut other: SmallVec<[u32; 4]> = SmallVec::new();
+    other.try_copy_from_slice(&[4, 5]).unwrap();
+    v.try_append(&mut other).unwrap();
+    assert!(other.is_empty());
+    assert!(v.spilled());
+    assert_eq!(v.as_slice(), [1, 2, 3, 4, 5]);
+
+    let tail = v.try_split_off(2).unwrap();
+    assert!(!tail.spilled());
+    assert_eq!(tail.as_slice(), [3, 4, 5]);
+    v.try_shrink_to(0).unwrap();
+    assert!(!v.spilled());
+    assert_eq!(v.as_slice(), [1, 2]);
+
+    v.try_extend_from_within(..).unwrap();
+    v.dedup_by_key(|x| *x / 2);
+    assert_eq!(v.as_slice(), [1, 2, 1, 2]);
+
+    // the tail is moved along when the vector spills while splicing
+    v.try_splice(1..3, (10..20).filter(|x| x % 3 == 0)).unwrap();
+    assert_eq!(v.as_slice(), [1, 12, 15, 18, 2]);
+
+    let drained: Vec<u32> = v.drain(1..4).try_collect().unwrap();
+    assert_eq!(drained.as_slice(), [12, 15, 18]);
+    assert_eq!(v.as_slice(), [1, 2]);
+
+    let mut drain = v.drain(..);
+    assert_eq!(drain.next_back(), Some(2));
+    drop(drain);
+    assert!(v.is_empty());
+}
+
+#[test]
+fn test_constructors_and_conversions() {
+    let v: SmallVec<[u32; 4]> = SmallVec::try_from_elem(7, 3).unwrap();
+    assert!(!v.spilled());
+    assert_eq!(v.as_slice(), [7, 7, 7]);
+
+    let v: SmallVec<[usize; 2]> = SmallVec::try_from_fn(5, |i| Ok(i * 2)).unwrap();
+    assert!(v.spilled());
+    assert_eq!(v.as_slice(), [0, 2, 4, 6, 8]);
+
+    let v: SmallVec<[u64; 4]> = SmallVec::try_zeroed(3).unwrap();
+    assert!(!v.spilled());
+    assert_eq!(v.as_slice(), [0, 0, 0]);
+    let v: SmallVec<[u64; 4]> = SmallVec::try_zeroed(6).unwrap();
+    assert!(v.spilled());
+    assert_eq!(v.as_slice(), [0; 6]);
+
+    let b = v.try_into_boxed_slice().unwrap();
+    assert_eq!(&*b, [0; 6]);
+
+    let mut v: SmallVec<[u32; 4]> = SmallVec::new();
+    v.try_copy_from_slice(&[1, 2]).unwrap();
+    let leaked = v.try_leak().unwrap();
+    assert_eq!(leaked, [1, 2]);
+    drop(unsafe { Box::from_raw(leaked) });
+
+    let fail = Cell::new(false);
+    let mut v: SmallVec<[String; 1]> = SmallVec::new();
+    v.try_push("a".to_string()).unwrap();
+    v.try_push("b".to_string()).unwrap();
+    let c = v.try_clone_in(FailingAlloc { fail: &fail }).unwrap();
+    assert_eq!(c.as_slice(), v.as_slice());
+    fail.set(true);
+    assert!(v.try_clone_in(FailingAlloc { fail: &fail }).is_err());
+}
+
+#[test]
+fn test_try_map_in_place() {
+    // inline elements are mapped into the inline storage of the result
+    let mut v: SmallVec<[u32; 4]> = SmallVec::new();
+    v.try_copy_from_slice(&[1, 2, 3]).unwrap();
+    let w: SmallVec<[String; 4]> = v.try_map_in_place(|x| x.to_string()).unwrap();
+    assert!(!w.spilled());
+    assert_eq!(w.as_slice(), ["1", "2", "3"]);
+
+    // and spill if they do not fit
+    let w: SmallVec<[String; 2]> = w.try_map_in_place(|s| s + "!").unwrap();
+    assert!(w.spilled());
+    assert_eq!(w.as_slice(), ["1!", "2!", "3!"]);
+
+    // a spilled buffer is reused when the layouts match
+    let mut v: SmallVec<[u32; 2]> = SmallVec::new();
+    v.try_copy_from_slice(&[1, 2, 3]).unwrap();
+    let ptr = v.as_ptr() as usize;
+    let w: SmallVec<[f32; 2]> = v.try_map_in_place(|x| x as f32 * 0.5).unwrap();
+    assert_eq!(w.as_ptr() as usize, ptr);
+    assert_eq!(w.as_slice(), [0.5, 1.0, 1.5]);
+}
+
+#[test]
+fn test_extend_uninit() {
+    let mut v: SmallVec<[u32; 2]> = SmallVec::new();
+    v.try_push(0).unwrap();
+    let count = v
+        .try_extend_uninit(4, |writer| {
+            writer.push(1).unwrap();
+            writer.extend_from_slice(&[2, 3]).unwrap();
+            writer.len()
+        })
+        .unwrap();
+    assert_eq!(count, 3);
+    assert!(v.spilled());
+    assert_eq!(v.as_slice(), [0, 1, 2, 3]);
+
+    let mut bytes: SmallVec<[u8; 8]> = SmallVec::new();
+    bytes
+        .try_extend_borrowed_buf(4, |mut cursor| cursor.append(b"hel"))
+        .unwrap();
+    assert!(!bytes.spilled());
+    assert_eq!(bytes.as_slice(), b"hel");
+}
+
+#[test]
+fn test_atomic_extend() {
+    use fallacy::alloc::{AllocError, Layout};
+    use std::rc::Rc;
+
+    #[derive(Debug)]
+    struct Flaky(Rc<Cell<usize>>);
+
+    impl TryClone for Flaky {
+        fn try_clone(&self) -> Result<Self, AllocError> {
+            if self.0.get() == 0 {
+                return Err(AllocError::new(Layout::new::<Flaky>()));
+            }
+            self.0.set(self.0.get() - 1);
+            Ok(Flaky(self.0.clone()))
+        }
+    }
+
+    let budget = Rc::new(Cell::new(0));
+    let src = [Flaky(budget.clone()), Flaky(budget.clone()), Flaky(budget.clone())];
+    let mut v: SmallVec<[Flaky; 2]> = SmallVec::new();
+    v.try_push(Flaky(budget.clone())).unwrap();
+
+    // the plain variants keep the elements added before the failure
+    budget.set(2);
+    assert!(v.try_extend_from_slice(&src).is_err());
+    assert_eq!(v.len(), 3);
+    v.truncate(1);
+    budget.set(1);
+    assert!(v.try_resize(4, Flaky(budget.clone())).is_err());
+    assert_eq!(v.len(), 2);
+    v.truncate(1);
+    let mut calls = 0;
+    let res = v.try_resize_with(4, || {
+        calls += 1;
+        if calls == 3 {
+            return Err(AllocError::new(Layout::new::<Flaky>()));
+        }
+        Ok(Flaky(budget.clone()))
+    });
+    assert!(res.is_err());
+    assert_eq!(v.len(), 3);
+    v.truncate(1);
+
+    // the atomic variants roll back to the original length
+    budget.set(2);
+    assert!(v.try_extend_from_slice_atomic(&src).is_err());
+    assert_eq!(v.len(), 1);
+    budget.set(1);
+    assert!(v.try_resize_atomic(4, Flaky(budget.clone())).is_err());
+    assert_eq!(v.len(), 1);
+    let mut calls = 0;
+    let res = v.try_resize_with_atomic(4, || {
+        calls += 1;
+        if calls == 3 {
+            return Err(AllocError::new(Layout::new::<Flaky>()));
+        }
+        Ok(Flaky(budget.clone()))
+    });
+    assert!(res.is_err());
+    assert_eq!(v.len(), 1);
+    assert_eq!(Rc::strong_count(&budget), 5);
+}
@@ -0,0 +1,14 @@
+//! string test case
+
+use fallacy::string::String;
+
+#[test]
+fn test_try_into_boxed_str() {
+    let mut s = String::try_with_capacity(32).unwrap();
+    s.try_push_str("hello").unwrap();
+    s.try_shrink_to_fit().unwrap();
+    assert_eq!(s.capacity(), 5);
+
+    let b = s.try_into_boxed_str().unwrap();
+    assert_eq!(&*b, "hello");
+}
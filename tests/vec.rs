@@ -0,0 +1,413 @@
+//! vec test case
+
+#![feature(allocator_api)]
+#![feature(core_io_borrowed_buf)]
+
+mod common;
+
+use common::FailingAlloc;
+use fallacy::iter::IteratorExt;
+use fallacy::string::String;
+use fallacy::try_vec;
+use fallacy::vec::Vec;
+use std::cell::Cell;
+
+#[test]
+fn test_try_insert_append_split_off() {
+    let mut v = Vec::new();
+    v.try_push(1).unwrap();
+    v.try_push(3).unwrap();
+    v.try_insert(1, 2).unwrap();
+    v.try_insert(3, 4).unwrap();
+    assert_eq!(v.as_slice(), &[1, 2, 3, 4]);
+
+    let mut other = Vec::new();
+    other.try_push(5).unwrap();
+    v.try_append(&mut other).unwrap();
+    assert!(other.is_empty());
+    assert_eq!(v.as_slice(), &[1, 2, 3, 4, 5]);
+
+    let tail = v.try_split_off(3).unwrap();
+    assert_eq!(v.as_slice(), &[1, 2, 3]);
+    assert_eq!(tail.as_slice(), &[4, 5]);
+
+    v.try_extend_from_within(1..).unwrap();
+    assert_eq!(v.as_slice(), &[1, 2, 3, 2, 3]);
+}
+
+#[test]
+fn test_try_vec() {
+    let v: Vec<i32> = try_vec![].unwrap();
+    assert!(v.is_empty());
+    assert_eq!(try_vec![1, 2, 3].unwrap().as_slice(), &[1, 2, 3]);
+    assert_eq!(try_vec![7u8; 3].unwrap().as_slice(), &[7, 7, 7]);
+    assert_eq!(Vec::try_from_fn(4, |i| Ok(i * 2)).unwrap().as_slice(), &[0, 2, 4, 6]);
+}
+
+#[test]
+fn test_try_shrink() {
+    let mut v: Vec<u32> = Vec::try_with_capacity(16).unwrap();
+    v.try_push(1).unwrap();
+    v.try_push(2).unwrap();
+    v.try_shrink_to(8).unwrap();
+    assert!(v.capacity() >= 8);
+    v.try_shrink_to_fit().unwrap();
+    assert_eq!(v.capacity(), 2);
+
+    let b = v.try_into_boxed_slice().unwrap();
+    assert_eq!(&*b, &[1, 2]);
+}
+
+#[test]
+fn test_try_splice() {
+    let mut v = try_vec![1, 2, 3, 4, 5].unwrap();
+    v.try_splice(1..3, [7, 8, 9]).unwrap();
+    assert_eq!(v.as_slice(), &[1, 7, 8, 9, 4, 5]);
+
+    // an iterator that yields more elements than its size hint
+    v.try_splice(..2, (0..20).filter(|x| x % 2 == 0)).unwrap();
+    assert_eq!(v.as_slice(), &[0, 2, 4, 6, 8, 10, 12, 14, 16, 18, 8, 9, 4, 5]);
+
+    v.try_splice(2.., []).unwrap();
+    assert_eq!(v.as_slice(), &[0, 2]);
+}
+
+#[test]
+fn test_try_splice_alloc_failure() {
+    let fail = Cell::new(false);
+    let mut v = Vec::try_with_capacity_in(5, FailingAlloc { fail: &fail }).unwrap();
+    v.try_extend_from_slice(&[1, 2, 3, 4, 5]).unwrap();
+    fail.set(true);
+
+    // the up-front reservation fails, the vector is left unchanged
+    assert!(v.try_splice(1..2, [7, 8, 9]).is_err());
+    assert_eq!(v.as_slice(), &[1, 2, 3, 4, 5]);
+    assert_eq!(v.capacity(), 5);
+
+    // the iterator yields more than its size hint and growing fails, the
+    // range holds the elements yielded so far and the tail is kept after them
+    let mut n = 6;
+    let iter = std::iter::from_fn(|| {
+        n += 1;
+        Some(n)
+    });
+    assert!(v.try_splice(1..3, iter).is_err());
+    assert_eq!(v.as_slice(), &[1, 7, 8, 4, 5]);
+}
+
+#[test]
+fn test_try_splice_alloc_failure_drops_pending() {
+    use std::rc::Rc;
+
+    let fail = Cell::new(false);
+    let rc = Rc::new(());
+    let mut v = Vec::new_in(FailingAlloc { fail: &fail });
+    v.try_reserve_exact(3).unwrap();
+    for _ in 0..3 {
+        v.try_push(rc.clone()).unwrap();
+    }
+    fail.set(true);
+
+    // the third element yielded needs the room, it is dropped and the fourth
+    // one is never yielded
+    let mut yielded = 0;
+    let iter = std::iter::from_fn(|| {
+        yielded += 1;
+        Some(rc.clone())
+    });
+    assert!(v.try_splice(1..3, iter.take(4)).is_err());
+    assert_eq!(yielded, 3);
+    assert_eq!(v.len(), 3);
+    assert_eq!(Rc::strong_count(&rc), 4);
+}
+
+#[test]
+fn test_retain_dedup_extract_if() {
+    let mut v = try_vec![1, 1, 2, 3, 3, 4, 5, 6].unwrap();
+    v.dedup();
+    assert_eq!(v.as_slice(), &[1, 2, 3, 4, 5, 6]);
+    v.retain(|x| *x != 4);
+    assert_eq!(v.as_slice(), &[1, 2, 3, 5, 6]);
+    let evens: std::vec::Vec<i32> = v.extract_if(.., |x| *x % 2 == 0).collect();
+    assert_eq!(evens, [2, 6]);
+    assert_eq!(v.as_slice(), &[1, 3, 5]);
+}
+
+#[test]
+fn test_try_map_in_place() {
+    let v = try_vec![1u32, 2, 3].unwrap();
+    let ptr = v.as_ptr() as usize;
+    let w = v.try_map_in_place(|x| x as f32 * 0.5).unwrap();
+    assert_eq!(w.as_ptr() as usize, ptr);
+    assert_eq!(w.as_slice(), &[0.5, 1.0, 1.5]);
+
+    let mut iter = w.into_iter();
+    iter.next();
+    let x = iter.try_map_in_place(|x| x as u8).unwrap();
+    assert_eq!(x.as_slice(), &[1, 1]);
+
+    let v = try_vec![String::new(), String::new()].unwrap();
+    let ptr = v.as_ptr() as usize;
+    let mut iter = v.into_iter();
+    iter.next_back();
+    let v: Vec<String> = iter.try_collect().unwrap();
+    assert_eq!(v.as_ptr() as usize, ptr);
+    assert_eq!(v.len(), 1);
+
+    let v = try_vec![1u32, 2, 3, 4].unwrap();
+    let ptr = v.as_ptr() as usize;
+    let w: Vec<i32> = v.into_iter().map(|x| x as i32 * 2).try_collect().unwrap();
+    assert_eq!(w.as_ptr() as usize, ptr);
+    assert_eq!(w.as_slice(), &[2, 4, 6, 8]);
+
+    let w: Vec<i32> = w.into_iter().filter(|x| *x > 2).take(2).try_collect().unwrap();
+    assert_eq!(w.as_ptr() as usize, ptr);
+    assert_eq!(w.as_slice(), &[4, 6]);
+
+    let w: Vec<u8> = w.into_iter().map(|x| x as u8).try_collect().unwrap();
+    assert_eq!(w.as_slice(), &[4, 6]);
+}
+
+#[test]
+fn test_growth_policy() {
+    use fallacy::vec::{CappedRatio, Exact, FixedIncrement};
+
+    let mut v = Vec::<u8>::new().with_growth_policy::<Exact>();
+    for i in 0..10 {
+        v.try_push(i).unwrap();
+        assert_eq!(v.capacity(), v.len());
+    }
+
+    let mut v = Vec::<u8>::new().with_growth_policy::<FixedIncrement<16>>();
+    v.try_push(0).unwrap();
+    assert_eq!(v.capacity(), 16);
+    v.try_resize(17, 0).unwrap();
+    assert_eq!(v.capacity(), 32);
+
+    let mut v = Vec::<u8>::try_with_capacity(64)
+        .unwrap()
+        .with_growth_policy::<CappedRatio<64>>();
+    v.try_resize(65, 0).unwrap();
+    assert_eq!(v.capacity(), 96);
+}
+
+#[test]
+fn test_custom_growth_policy_traits() {
+    use fallacy::vec::GrowthPolicy;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    /// A growth policy that derives none of the comparison traits.
+    struct Plain;
+
+    impl GrowthPolicy for Plain {
+        fn grow(_capacity: usize, required: usize, _elem_size: usize) -> usize {
+            required
+        }
+    }
+
+    fn hash_of<T: Hash>(t: &T) -> u64 {
+        let mut h = DefaultHasher::new();
+        t.hash(&mut h);
+        h.finish()
+    }
+
+    let a = try_vec![1, 2, 3].unwrap().with_growth_policy::<Plain>();
+    let b = try_vec![1, 2, 4].unwrap().with_growth_policy::<Plain>();
+    assert_eq!(a, a);
+    assert_ne!(a, b);
+    assert!(a < b);
+    assert_eq!(a.cmp(&b), std::cmp::Ordering::Less);
+    let c = try_vec![1, 2, 3].unwrap().with_growth_policy::<Plain>();
+    assert_eq!(hash_of(&a), hash_of(&c));
+
+    let s = String::try_from("abc").unwrap().with_growth_policy::<Plain>();
+    let t = String::try_from("abd").unwrap().with_growth_policy::<Plain>();
+    assert_eq!(s, s);
+    assert!(s < t);
+    assert_eq!(s.cmp(&t), std::cmp::Ordering::Less);
+}
+
+#[test]
+fn test_extend_from_slice_copy() {
+    use fallacy::borrow::{Cow, TryToOwned};
+    use fallacy::clone::TryClone;
+
+    let src: std::vec::Vec<u64> = (0..1000).collect();
+    let mut v = Vec::new();
+    v.try_extend_from_slice(&src).unwrap();
+    v.try_extend_from_slice(&src[..10]).unwrap();
+    assert_eq!(v.len(), 1010);
+    assert_eq!(&v[1000..], &src[..10]);
+
+    let strings = [String::try_from("a").unwrap(), String::try_from("b").unwrap()];
+    let mut owned = Vec::new();
+    owned.try_extend_from_slice(&strings).unwrap();
+    assert_eq!(owned.as_slice(), &strings);
+
+    let cow: Cow<'_, [u8]> = Cow::Borrowed(&[1, 2]);
+    let cloned = cow.try_clone().unwrap();
+    assert_eq!(&*cloned, &[1, 2]);
+    assert_eq!(cow.into_owned().unwrap().as_slice(), &[1, 2]);
+    assert_eq!([3u8, 4][..].try_to_owned().unwrap().as_slice(), &[3, 4]);
+
+    // A user-written `TryClone` of a `Copy` type is still called.
+    #[derive(Clone, Copy)]
+    struct Counted<'a>(&'a Cell<usize>);
+
+    impl TryClone for Counted<'_> {
+        fn try_clone(&self) -> Result<Self, fallacy::alloc::AllocError> {
+            self.0.set(self.0.get() + 1);
+            Ok(*self)
+        }
+    }
+
+    let clones = Cell::new(0);
+    let mut v = Vec::new();
+    v.try_extend_from_slice(&[Counted(&clones); 3]).unwrap();
+    v.try_resize(5, Counted(&clones)).unwrap();
+    assert_eq!(clones.get(), 4);
+}
+
+#[test]
+fn test_zeroed() {
+    let v = Vec::<u64>::try_zeroed(1000).unwrap();
+    assert_eq!(v.len(), 1000);
+    assert!(v.iter().all(|&x| x == 0));
+
+    let v = Vec::<[f32; 3]>::try_zeroed(4).unwrap();
+    assert_eq!(v.as_slice(), &[[0.0; 3]; 4]);
+
+    let mut v = Vec::new();
+    v.try_resize(100, 0u32).unwrap();
+    assert_eq!(v.as_slice(), &[0; 100][..]);
+    v.try_resize(200, 7).unwrap();
+    assert_eq!(&v[..100], &[0; 100][..]);
+    assert_eq!(&v[100..], &[7; 100][..]);
+    v.try_resize(300, 0).unwrap();
+    assert!(v[200..].iter().all(|&x| x == 0));
+
+    let v = try_vec![0u8; 64].unwrap();
+    assert_eq!(v.capacity(), 64);
+    assert!(v.iter().all(|&x| x == 0));
+}
+
+#[test]
+fn test_atomic_extend() {
+    use fallacy::alloc::{AllocError, Layout};
+    use fallacy::clone::TryClone;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[derive(Debug)]
+    struct Flaky(Rc<Cell<usize>>);
+
+    impl TryClone for Flaky {
+        fn try_clone(&self) -> Result<Self, AllocError> {
+            if self.0.get() == 0 {
+                return Err(AllocError::new(Layout::new::<Flaky>()));
+            }
+            self.0.set(self.0.get() - 1);
+            Ok(Flaky(self.0.clone()))
+        }
+    }
+
+    let budget = Rc::new(Cell::new(0));
+    let src = [Flaky(budget.clone()), Flaky(budget.clone()), Flaky(budget.clone())];
+    let mut v = Vec::new();
+    v.try_push(Flaky(budget.clone())).unwrap();
+
+    budget.set(2);
+    assert!(v.try_extend_from_slice(&src).is_err());
+    assert_eq!(v.len(), 3);
+
+    v.truncate(1);
+    budget.set(2);
+    assert!(v.try_extend_from_slice_atomic(&src).is_err());
+    assert_eq!(v.len(), 1);
+
+    budget.set(1);
+    assert!(v.try_resize_atomic(4, Flaky(budget.clone())).is_err());
+    assert_eq!(v.len(), 1);
+    assert_eq!(Rc::strong_count(&budget), 5);
+}
+
+#[test]
+fn test_within_capacity() {
+    let mut v = Vec::try_with_capacity(4).unwrap();
+    v.push_within_capacity(1).unwrap();
+    v.insert_within_capacity(0, 0).unwrap();
+    v.extend_from_slice_within_capacity(&[2, 3]).unwrap();
+    assert_eq!(v.as_slice(), &[0, 1, 2, 3]);
+    assert_eq!(v.push_within_capacity(4), Err(4));
+    assert_eq!(v.insert_within_capacity(0, 4), Err(4));
+    assert_eq!(v.extend_from_slice_within_capacity(&[4]), Err(&[4][..]));
+    assert_eq!(v.capacity(), 4);
+
+    let mut s = String::new();
+    s.try_reserve_exact(4).unwrap();
+    s.push_str_within_capacity("abc").unwrap();
+    assert_eq!(s.push_str_within_capacity("de"), Err("de"));
+    assert_eq!(s.push_within_capacity('é'), Err('é'));
+    s.push_within_capacity('d').unwrap();
+    assert_eq!(s, "abcd");
+}
+
+#[test]
+fn test_extend_uninit() {
+    let mut v = Vec::new();
+    v.try_push(0u32).unwrap();
+    let count = v
+        .try_extend_uninit(4, |writer| {
+            writer.push(1).unwrap();
+            writer.extend_from_slice(&[2, 3]).unwrap();
+            assert_eq!(writer.extend_from_slice(&[4, 5]), Err(&[4, 5][..]));
+            writer.len()
+        })
+        .unwrap();
+    assert_eq!(count, 3);
+    assert_eq!(v.as_slice(), &[0, 1, 2, 3]);
+
+    v.try_extend_uninit(2, |writer| {
+        writer.unfilled_mut()[0].write(4);
+        unsafe { writer.advance(1) };
+    })
+    .unwrap();
+    assert_eq!(v.as_slice(), &[0, 1, 2, 3, 4]);
+
+    let mut bytes = Vec::new();
+    let mut src: &[u8] = b"hello";
+    bytes
+        .try_extend_borrowed_buf(16, |mut cursor| {
+            let n = src.len().min(3);
+            cursor.append(&src[..n]);
+            src = &src[n..];
+        })
+        .unwrap();
+    assert_eq!(bytes.as_slice(), b"hel");
+}
+
+#[test]
+fn test_try_clone() {
+    use fallacy::alloc::Global;
+    use fallacy::clone::TryClone;
+
+    let mut nested: Vec<Vec<u32>> = Vec::new();
+    nested.try_push(try_vec![1, 2].unwrap()).unwrap();
+    nested.try_push(try_vec![3].unwrap()).unwrap();
+    let slices = |v: &Vec<Vec<u32>>| v.iter().map(|x| x.as_slice().to_vec()).collect::<std::vec::Vec<_>>();
+    let cloned = nested.try_clone().unwrap();
+    assert_eq!(slices(&cloned), slices(&nested));
+
+    let mut dst: Vec<Vec<u32>> = Vec::try_with_capacity(8).unwrap();
+    dst.try_push(try_vec![9; 4].unwrap()).unwrap();
+    let inner = dst[0].as_ptr();
+    dst.try_clone_from(&nested).unwrap();
+    assert_eq!(slices(&dst), slices(&nested));
+    assert_eq!(dst[0].as_ptr(), inner);
+    assert_eq!(dst.capacity(), 8);
+
+    let v = Vec::try_from_elem_in(7u8, 3, Global).unwrap();
+    let heap: Vec<u8> = v.try_clone_in(Global).unwrap();
+    assert_eq!(heap.as_slice(), &[7, 7, 7]);
+}
@@ -0,0 +1,115 @@
+//! vec_deque test case
+
+use fallacy::clone::TryClone;
+use fallacy::collections::VecDeque;
+use fallacy::vec::Vec;
+
+#[test]
+fn test_push_and_pop() {
+    let mut d = VecDeque::new();
+    d.try_push_back(2).unwrap();
+    d.try_push_back(3).unwrap();
+    d.try_push_front(1).unwrap();
+    d.try_insert(1, 9).unwrap();
+    assert_eq!(d.make_contiguous(), [1, 9, 2, 3]);
+    assert_eq!(d.remove(1), Some(9));
+    assert_eq!(d.pop_front(), Some(1));
+    assert_eq!(d.pop_back(), Some(3));
+
+    d.try_extend([4, 5]).unwrap();
+    let c = d.try_clone().unwrap();
+    assert_eq!(c, d);
+    assert_eq!(c.iter().copied().collect::<std::vec::Vec<_>>(), [2, 4, 5]);
+}
+
+#[test]
+fn test_vec_conversion() {
+    let mut vec = Vec::try_with_capacity(4).unwrap();
+    vec.try_extend_from_slice(&[1, 2, 3]).unwrap();
+    let ptr = vec.as_ptr();
+
+    let mut d = VecDeque::from(vec);
+    d.rotate_left(1);
+    d.try_push_front(0).unwrap();
+    let vec = Vec::from(d);
+    assert_eq!(vec.as_ptr(), ptr);
+    assert_eq!(vec.as_slice(), [0, 2, 3, 1]);
+}
+
+#[test]
+fn test_wrap_around() {
+    let mut d = VecDeque::try_with_capacity(4).unwrap();
+    let cap = d.capacity();
+    for i in 0..cap {
+        d.try_push_back(i).unwrap();
+    }
+    // the front and back wrap around the end of the buffer without growing it
+    for i in 0..cap * 3 {
+        assert_eq!(d.pop_front(), Some(i));
+        d.try_push_back(i + cap).unwrap();
+        assert_eq!(d.capacity(), cap);
+    }
+    for i in 0..cap {
+        assert_eq!(d.pop_back(), Some(cap * 4 - 1 - i));
+        d.try_push_front(i).unwrap();
+    }
+    assert_eq!(d.capacity(), cap);
+    let (front, back) = d.as_slices();
+    assert_eq!(front.len() + back.len(), cap);
+    assert_eq!(
+        d.iter().copied().collect::<std::vec::Vec<_>>(),
+        (0..cap).rev().collect::<std::vec::Vec<_>>()
+    );
+
+    // growing a wrapped deque keeps the order
+    d.try_push_front(100).unwrap();
+    d.try_push_back(200).unwrap();
+    assert_eq!(d.front(), Some(&100));
+    assert_eq!(d.back(), Some(&200));
+    assert_eq!(d.len(), cap + 2);
+}
+
+#[test]
+fn test_vec_round_trip_reuses_buffer() {
+    let mut vec: Vec<u32> = Vec::try_with_capacity(8).unwrap();
+    vec.try_extend_from_slice(&[1, 2, 3, 4]).unwrap();
+    let ptr = vec.as_ptr();
+
+    let d = VecDeque::from(vec);
+    assert!(d.capacity() >= 8);
+    let vec = Vec::from(d);
+    assert_eq!(vec.as_ptr(), ptr);
+    assert_eq!(vec.capacity(), 8);
+    assert_eq!(vec.as_slice(), [1, 2, 3, 4]);
+
+    // an empty deque converts into an empty vector
+    let vec = Vec::from(VecDeque::<u32>::new());
+    assert!(vec.is_empty());
+}
+
+#[test]
+fn test_try_resize_with_error() {
+    use fallacy::alloc::{AllocError, Layout};
+    use std::rc::Rc;
+
+    let rc = Rc::new(());
+    let mut d = VecDeque::new();
+    d.try_push_back(rc.clone()).unwrap();
+
+    let mut n = 0;
+    let res = d.try_resize_with(5, || {
+        n += 1;
+        if n == 3 {
+            return Err(AllocError::new(Layout::new::<Rc<()>>()));
+        }
+        Ok(rc.clone())
+    });
+    assert!(res.is_err());
+    assert_eq!(d.len(), 1);
+    assert_eq!(Rc::strong_count(&rc), 2);
+
+    d.try_resize_with(3, || Ok(rc.clone())).unwrap();
+    assert_eq!(d.len(), 3);
+    d.try_resize_with(1, || unreachable!()).unwrap();
+    assert_eq!(Rc::strong_count(&rc), 2);
+}